@@ -24,10 +24,9 @@ fn hash_map(bench: &mut Bencher) {
 }
 
 fn read_vector(bench: &mut Bencher) {
-    let mut distances = vec![f32::INFINITY; 5102];
-    bench.iter(|| {
-        let i = distances[2345];
-    })
+    let distances = vec![f32::INFINITY; 5102];
+    // returning the value read keeps it from being optimized away
+    bench.iter(|| distances[2345])
 }
 
 fn read_hash_map(bench: &mut Bencher) {
@@ -36,25 +35,19 @@ fn read_hash_map(bench: &mut Bencher) {
         distances.insert(i, f32::INFINITY);
     }
 
-    bench.iter(|| {
-        let i = distances.get(&2345).unwrap();
-    })
+    bench.iter(|| *distances.get(&2345).unwrap())
 }
 
 fn clone_vec(bench: &mut Bencher) {
     let v = RefCell::new(vec![0_usize, 5000]);
 
-    bench.iter(|| {
-        let cv = v.borrow().clone();
-    })
+    bench.iter(|| v.borrow().clone())
 }
 
 fn borrow_vec(bench: &mut Bencher) {
     let v = RefCell::new(vec![0_usize, 5000]);
 
-    bench.iter(|| {
-        let rv = v.borrow();
-    })
+    bench.iter(|| v.borrow().len())
 }
 
 benchmark_group!(write_benches, vector, hash_map);
@@ -0,0 +1,6 @@
+use assignment_solver::cli;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(cli::run(&args));
+}
@@ -0,0 +1,442 @@
+//! # CLI
+//!
+//! A headless command-line interface to the solver, for scripts and batch runs. It offers three
+//! subcommands:
+//!
+//! ```text
+//! solve <input> <output> [--input-format F] [--output-format F] [--objective O]
+//!       [--combine C] [--worker-weight W] [--task-weight W] [--layout L] [--stats S]
+//!       [--if-exists E] [--delimiter D] [--decimal D] [--encoding E] [--rank-costs R]
+//!       [--unranked U] [--normalize N] [--sum-to T] [--previous P] [--change-penalty C]
+//!       [--backups B]
+//! validate <input> [--input-format F] [--delimiter D] [--decimal D] [--encoding E]
+//!          [--rank-costs R] [--unranked U]
+//! convert <input> <output> [--input-format F] [--output-format F] [--if-exists E]
+//!         [--delimiter D] [--decimal D] [--encoding E] [--rank-costs R] [--unranked U]
+//! ```
+//!
+//! File formats default to the type implied by each file's extension, falling back on CSV. The
+//! solver's progress is printed to stderr, and the process exits with one of the `EXIT_*` codes so
+//! that scripts can tell what went wrong.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::io::{CsvDialect, DecimalSeparator, Delimiter, FileType, OutputLayout, OverwritePolicy,
+                RankCosts, RankScheme, ReadError, TextEncoding, UnrankedTasks};
+use crate::network::{CostNormalization, Objective, ScoreCombination};
+use crate::solver::{PreviousRoster, Solver, SolverError, SolverOptions};
+use crate::status::{Status, StatusTracker};
+#[cfg(test)]
+mod test;
+
+/// Exit code for a successful run
+pub const EXIT_SUCCESS: i32 = 0;
+/// Exit code for a command line that could not be understood
+pub const EXIT_USAGE: i32 = 1;
+/// Exit code for an input file that could not be parsed, or that failed validation
+pub const EXIT_PARSE: i32 = 2;
+/// Exit code for a problem that has no solution
+pub const EXIT_INFEASIBLE: i32 = 3;
+/// Exit code for a file that could not be opened, read or written
+pub const EXIT_IO: i32 = 4;
+
+const USAGE: &str = "\
+Usage:
+    assignment-solver-cli solve <input> <output> [options]
+    assignment-solver-cli validate <input> [options]
+    assignment-solver-cli convert <input> <output> [options]
+
+Options:
+    --input-format <format>   csv, long-csv, ranked-csv, json, xlsx or ods (default: from the
+                              input file's extension)
+    --output-format <format>  csv, long-csv, json, xlsx or ods (default: from the output file's
+                              extension)
+    --objective <objective>   min-cost (default), stable or min-cost-and-stability
+    --combine <combination>   worker-only (default), sum, product or min
+    --worker-weight <weight>  weight of the workers' costs when combining with sum (default: 1)
+    --task-weight <weight>    weight of the tasks' scores when combining with sum (default: 1)
+    --normalize <method>      rescale each worker's costs before solving: none (default),
+                              z-score, min-max, rank or sum
+    --sum-to <total>          total of each worker's costs when normalizing with sum (default: 1)
+    --previous <file>         a CSV output file of previous assignments to change as little as
+                              possible
+    --change-penalty <cost>   cost of moving a worker from their previous task (default: 1)
+    --backups <count>         also list up to this many backup workers for each task (default: 0)
+    --layout <layout>         by-task (default) or by-worker, giving each worker's task, cost,
+                              best available cost and regret
+    --stats <file>            also write a CSV report of statistics about the assignments
+    --if-exists <policy>      replace (default), refuse or backup an existing output file
+    --delimiter <delimiter>   auto (default), comma, semicolon or tab between CSV fields
+    --decimal <separator>     dot (default) or comma in CSV numbers; with auto, comma decimals
+                              are written with semicolon delimiters
+    --encoding <encoding>     text encoding of CSV files, e.g. utf-8 (default) or windows-1252;
+                              a byte order mark in the input overrides it
+    --rank-costs <costs>      how ranked-csv input turns ranks into costs: linear (default),
+                              exponential, or each rank's cost in order, e.g. \"0 1 3\"
+    --unranked <treatment>    ineligible (default) or the cost of tasks a worker did not rank";
+
+/// Run the command line given by `args` (not including the program name), returning the code the
+/// process should exit with
+pub fn run(args: &[String]) -> i32 {
+    let result = match args.split_first() {
+        Some((command, rest)) => match command.as_str() {
+            "solve" => solve(rest),
+            "validate" => validate(rest),
+            "convert" => convert(rest),
+            "help" | "--help" | "-h" => {
+                println!("{}", USAGE);
+                return EXIT_SUCCESS;
+            },
+            _ => Err(CliError::Usage(format!("Unknown command {}!", command)))
+        },
+        None => Err(CliError::Usage("No command given!".to_string()))
+    };
+
+    match result {
+        Ok(()) => EXIT_SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            if let CliError::Usage(_) = e {
+                eprintln!("{}", USAGE);
+            }
+            e.exit_code()
+        }
+    }
+}
+
+/// Solve an input file and write the assignments to an output file
+fn solve(args: &[String]) -> Result<(), CliError> {
+    let args = Arguments::parse(args, &["input-format", "output-format", "objective", "combine",
+                                        "worker-weight", "task-weight", "layout", "stats",
+                                        "if-exists", "delimiter", "decimal", "encoding",
+                                        "rank-costs", "unranked", "normalize", "sum-to",
+                                        "previous", "change-penalty", "backups"])?;
+    let (infile, outfile) = args.get_two_files()?;
+    let options = SolverOptions {
+        objective: args.get_objective()?,
+        score_combination: args.get_score_combination()?,
+        normalization: args.get_cost_normalization()?,
+        layout: args.get_layout()?,
+        overwrite: args.get_overwrite_policy()?,
+        dialect: args.get_dialect()?,
+        ranking: args.get_ranking()?,
+        previous_roster: args.get_previous_roster()?,
+        num_backups: args.get_num_backups()?,
+    };
+
+    let solver = Solver::new(args.get_file_type("input-format", &infile)?,
+                             args.get_file_type("output-format", &outfile)?,
+                             options);
+    let progress = StderrProgress::new();
+    let result = solver.assign_workers(infile, outfile.clone(), &progress);
+    progress.finish();
+    result?;
+    eprintln!("Assignments written to {}", outfile);
+
+    if let Some(stats_file) = args.options.get("stats") {
+        solver.write_statistics(stats_file.clone())?;
+        eprintln!("Statistics written to {}", stats_file);
+    }
+    Ok(())
+}
+
+/// Check an input file for every issue that would stop it being read or solved, without solving it
+fn validate(args: &[String]) -> Result<(), CliError> {
+    let args = Arguments::parse(args, &["input-format", "delimiter", "decimal", "encoding",
+                                        "rank-costs", "unranked"])?;
+    let infile = args.get_one_file()?;
+    let options = SolverOptions {
+        dialect: args.get_dialect()?,
+        ranking: args.get_ranking()?,
+        ..SolverOptions::default()
+    };
+
+    let solver = Solver::new(args.get_file_type("input-format", &infile)?, FileType::Csv,
+                             options);
+    let report = solver.validate_input(infile.clone())?;
+    for issue in &report.issues {
+        println!("{}", issue);
+    }
+
+    match report.count_errors() {
+        0 => {
+            println!("{} is valid: {} tasks and {} workers", infile, report.num_tasks,
+                     report.num_workers);
+            Ok(())
+        },
+        num_errors => Err(CliError::Invalid(format!("{} has {} errors!", infile, num_errors)))
+    }
+}
+
+/// Rewrite an input file in another format
+fn convert(args: &[String]) -> Result<(), CliError> {
+    let args = Arguments::parse(args, &["input-format", "output-format", "if-exists",
+                                        "delimiter", "decimal", "encoding", "rank-costs",
+                                        "unranked"])?;
+    let (infile, outfile) = args.get_two_files()?;
+    let options = SolverOptions {
+        overwrite: args.get_overwrite_policy()?,
+        dialect: args.get_dialect()?,
+        ranking: args.get_ranking()?,
+        ..SolverOptions::default()
+    };
+
+    let solver = Solver::new(args.get_file_type("input-format", &infile)?,
+                             args.get_file_type("output-format", &outfile)?,
+                             options);
+    solver.convert_input(infile.clone(), outfile.clone())?;
+
+    eprintln!("Converted {} to {}", infile, outfile);
+    Ok(())
+}
+
+/// Anything that stops a subcommand from finishing
+#[derive(Debug)]
+enum CliError {
+    /// The command line itself was wrong
+    Usage(String),
+    /// The solver gave up
+    Solver(SolverError),
+    /// Validation found errors in the input file
+    Invalid(String),
+}
+
+impl CliError {
+    /// Get the code the process should exit with: input files that cannot be parsed are told apart
+    /// from files that cannot be opened by whether the reader returned a parse error
+    fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => EXIT_USAGE,
+            CliError::Invalid(_) => EXIT_PARSE,
+            CliError::Solver(SolverError::Input(ReadError::Parse(_))) => EXIT_PARSE,
+            CliError::Solver(SolverError::Input(ReadError::Io(_))) => EXIT_IO,
+            CliError::Solver(SolverError::Infeasible(_)) => EXIT_INFEASIBLE,
+            CliError::Solver(SolverError::Output(_)) => EXIT_IO,
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(message) | CliError::Invalid(message) => write!(f, "{}", message),
+            CliError::Solver(e) => write!(f, "{}", e)
+        }
+    }
+}
+
+impl From<SolverError> for CliError {
+    fn from(e: SolverError) -> Self {
+        CliError::Solver(e)
+    }
+}
+
+/// A subcommand's arguments, split into file names and `--name value` options
+struct Arguments {
+    files: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Arguments {
+    /// Split the arguments into file names and options, accepting only the named options. Options
+    /// can be given either as `--name value` or as `--name=value`.
+    fn parse(args: &[String], allowed_options: &[&str]) -> Result<Arguments, CliError> {
+        let mut files = Vec::new();
+        let mut options = HashMap::new();
+        let mut arg_iter = args.iter();
+        while let Some(arg) = arg_iter.next() {
+            let option = match arg.strip_prefix("--") {
+                Some(option) => option,
+                None => {
+                    files.push(arg.to_string());
+                    continue;
+                }
+            };
+
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) => (name, value.to_string()),
+                None => match arg_iter.next() {
+                    Some(value) => (option, value.to_string()),
+                    None => return Err(CliError::Usage(format!("Missing value for --{}!", option)))
+                }
+            };
+            if !allowed_options.contains(&name) {
+                return Err(CliError::Usage(format!("Unknown option --{}!", name)));
+            }
+            options.insert(name.to_string(), value);
+        }
+
+        Ok(Arguments { files, options })
+    }
+
+    /// Get the only file name given
+    fn get_one_file(&self) -> Result<String, CliError> {
+        match self.files.as_slice() {
+            [infile] => Ok(infile.to_string()),
+            _ => Err(CliError::Usage(format!("Expected 1 file name, found {}!", self.files.len())))
+        }
+    }
+
+    /// Get the input and output file names
+    fn get_two_files(&self) -> Result<(String, String), CliError> {
+        match self.files.as_slice() {
+            [infile, outfile] => Ok((infile.to_string(), outfile.to_string())),
+            _ => Err(CliError::Usage(format!("Expected 2 file names, found {}!", self.files.len())))
+        }
+    }
+
+    /// Get the file type chosen with the given option, or else the one implied by the file's
+    /// extension, or else CSV
+    fn get_file_type(&self, option: &str, filename: &str) -> Result<FileType, CliError> {
+        match self.options.get(option) {
+            Some(name) => FileType::from_str(name).map_err(CliError::Usage),
+            None => Ok(FileType::from_path(filename).unwrap_or(FileType::Csv))
+        }
+    }
+
+    /// Get the objective chosen with `--objective`, or the default one
+    fn get_objective(&self) -> Result<Objective, CliError> {
+        match self.options.get("objective").map(String::as_str) {
+            None | Some("min-cost") => Ok(Objective::MinCost),
+            Some("stable") => Ok(Objective::StableMatching),
+            Some("min-cost-and-stability") => Ok(Objective::MinCostAndStability),
+            Some(other) => Err(CliError::Usage(format!("Unknown objective {}!", other)))
+        }
+    }
+
+    /// Get the score combination chosen with `--combine` and, for weighted sums, the weights
+    fn get_score_combination(&self) -> Result<ScoreCombination, CliError> {
+        match self.options.get("combine").map(String::as_str) {
+            None | Some("worker-only") => Ok(ScoreCombination::WorkerOnly),
+            Some("sum") => Ok(ScoreCombination::WeightedSum {
+                worker_weight: self.get_weight("worker-weight")?,
+                task_weight: self.get_weight("task-weight")?,
+            }),
+            Some("product") => Ok(ScoreCombination::Product),
+            Some("min") => Ok(ScoreCombination::Min),
+            Some(other) => Err(CliError::Usage(format!("Unknown score combination {}!", other)))
+        }
+    }
+
+    /// Get the cost normalization chosen with `--normalize` and, for sums, the total
+    fn get_cost_normalization(&self) -> Result<CostNormalization, CliError> {
+        match self.options.get("normalize").map(String::as_str) {
+            None | Some("none") => Ok(CostNormalization::None),
+            Some("z-score") => Ok(CostNormalization::ZScore),
+            Some("min-max") => Ok(CostNormalization::MinMax),
+            Some("rank") => Ok(CostNormalization::Rank),
+            Some("sum") => Ok(CostNormalization::SumTo(self.get_weight("sum-to")?)),
+            Some(other) => Err(CliError::Usage(format!("Unknown cost normalization {}!", other)))
+        }
+    }
+
+    /// Get the previous roster given with `--previous`, if any, with the penalty for changing it
+    /// given with `--change-penalty`
+    fn get_previous_roster(&self) -> Result<Option<PreviousRoster>, CliError> {
+        match self.options.get("previous") {
+            Some(filename) => Ok(Some(PreviousRoster {
+                filename: filename.clone(),
+                change_penalty: self.get_weight("change-penalty")?,
+            })),
+            None => Ok(None)
+        }
+    }
+
+    /// Get the number of backup workers to list for each task given with `--backups`, which
+    /// defaults to none
+    fn get_num_backups(&self) -> Result<usize, CliError> {
+        match self.options.get("backups") {
+            Some(count) => usize::from_str(count).map_err(|err| CliError::Usage(
+                format!(r#"Expected a whole number for --backups, found "{}"; error: {}"#,
+                        count, err))),
+            None => Ok(0)
+        }
+    }
+
+    /// Get the output layout chosen with `--layout`, or the default one
+    fn get_layout(&self) -> Result<OutputLayout, CliError> {
+        match self.options.get("layout") {
+            Some(name) => OutputLayout::from_str(name).map_err(CliError::Usage),
+            None => Ok(OutputLayout::default())
+        }
+    }
+
+    /// Get the policy for existing output files chosen with `--if-exists`, or the default one
+    fn get_overwrite_policy(&self) -> Result<OverwritePolicy, CliError> {
+        match self.options.get("if-exists") {
+            Some(name) => OverwritePolicy::from_str(name).map_err(CliError::Usage),
+            None => Ok(OverwritePolicy::default())
+        }
+    }
+
+    /// Get the CSV dialect chosen with `--delimiter`, `--decimal` and `--encoding`, defaulting each
+    /// part that was not chosen
+    fn get_dialect(&self) -> Result<CsvDialect, CliError> {
+        Ok(CsvDialect {
+            delimiter: self.get_parsed::<Delimiter>("delimiter")?,
+            decimal_separator: self.get_parsed::<DecimalSeparator>("decimal")?,
+            encoding: self.get_parsed::<TextEncoding>("encoding")?,
+        })
+    }
+
+    /// Get the scheme for turning ranks into costs chosen with `--rank-costs` and `--unranked`,
+    /// defaulting each part that was not chosen
+    fn get_ranking(&self) -> Result<RankScheme, CliError> {
+        Ok(RankScheme {
+            costs: self.get_parsed::<RankCosts>("rank-costs")?,
+            unranked: self.get_parsed::<UnrankedTasks>("unranked")?,
+        })
+    }
+
+    /// Parse the value given with the named option, or get the default value if it was not given
+    fn get_parsed<T>(&self, option: &str) -> Result<T, CliError>
+        where T: FromStr<Err = String> + Default {
+        match self.options.get(option) {
+            Some(value) => T::from_str(value).map_err(CliError::Usage),
+            None => Ok(T::default())
+        }
+    }
+
+    /// Get the weight (or total) given with the named option, which defaults to 1
+    fn get_weight(&self, option: &str) -> Result<f32, CliError> {
+        match self.options.get(option) {
+            Some(weight) => f32::from_str(weight).map_err(|err| CliError::Usage(
+                format!(r#"Expected numeric value for --{}, found "{}"; error: {}"#,
+                        option, weight, err))),
+            None => Ok(1.0)
+        }
+    }
+}
+
+/// Reports the solver's progress on a single line of stderr, rewriting it as each percentage point
+/// is completed
+struct StderrProgress {
+    last_percent: Cell<Option<u32>>,
+}
+
+impl StderrProgress {
+    fn new() -> Self {
+        StderrProgress { last_percent: Cell::new(None) }
+    }
+
+    /// End the progress line, if one was started
+    fn finish(&self) {
+        if self.last_percent.get().is_some() {
+            eprintln!();
+        }
+    }
+}
+
+impl StatusTracker for StderrProgress {
+    fn set_status(&self, new_status: Status) {
+        if let Status::InProgress(fraction) = new_status {
+            let percent = (fraction * 100.0) as u32;
+            if self.last_percent.get() != Some(percent) {
+                eprint!("\rSolving: {:>3}%", percent);
+                self.last_percent.set(Some(percent));
+            }
+        }
+    }
+}
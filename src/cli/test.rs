@@ -0,0 +1,76 @@
+use crate::cli::*;
+use crate::io::TestOutputDir;
+
+/// Run the CLI with the given arguments
+fn run_with(args: &[&str]) -> i32 {
+    run(&args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>())
+}
+
+#[test]
+fn test_usage_errors() {
+    let output = TestOutputDir::new("test_usage_errors");
+    assert_eq!(run_with(&[]), EXIT_USAGE);
+    assert_eq!(run_with(&["assign"]), EXIT_USAGE);
+    assert_eq!(run_with(&["validate"]), EXIT_USAGE);
+    assert_eq!(run_with(&["validate", "src/io/csv/test-data/testInput.csv", "--objective",
+                          "stable"]), EXIT_USAGE);
+    assert_eq!(run_with(&["solve", "src/io/csv/test-data/testInput.csv",
+                          &output.path("cliOutput.csv"), "--combine=average"]),
+               EXIT_USAGE);
+    assert_eq!(run_with(&["solve", "src/io/csv/test-data/testInput.csv",
+                          &output.path("cliOutput.csv"), "--layout=by-row"]),
+               EXIT_USAGE);
+}
+
+#[test]
+fn test_validate() {
+    assert_eq!(run_with(&["validate", "src/io/csv/test-data/testInput.csv"]), EXIT_SUCCESS);
+    assert_eq!(run_with(&["validate", "src/io/csv/test-data/inputBadMin.csv"]), EXIT_PARSE);
+    assert_eq!(run_with(&["validate", "src/io/csv/test-data/noSuchFile.csv"]), EXIT_IO);
+    assert_eq!(run_with(&["validate", "src/io/csv/test-data/inputManyProblems.csv"]), EXIT_PARSE);
+}
+
+#[test]
+fn test_solve() {
+    let output = TestOutputDir::new("test_solve");
+    assert_eq!(run_with(&["solve", "src/io/csv/test-data/testInput.csv",
+                          &output.path("cliOutput.csv")]), EXIT_SUCCESS);
+    // stable matching and combined scores need tasks' preferences, which this input lacks
+    assert_eq!(run_with(&["solve", "src/io/csv/test-data/testInput.csv",
+                          &output.path("cliStableOutput.csv"),
+                          "--objective", "stable"]), EXIT_PARSE);
+    assert_eq!(run_with(&["solve", "src/io/csv/test-data/testInput.csv",
+                          &output.path("cliSumOutput.csv"),
+                          "--combine", "sum"]), EXIT_PARSE);
+    assert_eq!(run_with(&["solve", "src/io/csv/test-data/testInput.csv",
+                          &output.path("cliByWorkerOutput.csv"),
+                          "--layout", "by-worker"]), EXIT_SUCCESS);
+    assert_eq!(run_with(&["solve", "src/io/csv/test-data/testInput.csv",
+                          &output.path("cliOutput.csv"),
+                          "--stats", &output.path("cliStatistics.csv")]), EXIT_SUCCESS);
+    let statistics = std::fs::read_to_string(output.path("cliStatistics.csv")).unwrap();
+    assert!(statistics.starts_with("Workers assigned:,10\nWorkers:,10\n"));
+}
+
+#[test]
+fn test_solve_refuse_overwrite() {
+    let output = TestOutputDir::new("test_solve_refuse_overwrite");
+    std::fs::write(output.path("existingOutput.csv"), "earlier output").unwrap();
+    assert_eq!(run_with(&["solve", "src/io/csv/test-data/testInput.csv",
+                          &output.path("existingOutput.csv"), "--if-exists", "refuse"]),
+               EXIT_IO);
+    assert_eq!(std::fs::read_to_string(output.path("existingOutput.csv")).unwrap(),
+               "earlier output");
+}
+
+#[test]
+fn test_convert() {
+    let output = TestOutputDir::new("test_convert");
+    for input in ["inputTaskPreferences", "inputStaffingCosts", "inputOptionalWorkers"] {
+        let outfile = output.path(&format!("cliConverted-{}.csv", input));
+        assert_eq!(run_with(&["convert", &format!("src/io/csv/test-data/{}.csv", input),
+                              &outfile]), EXIT_SUCCESS);
+        assert_eq!(run_with(&["solve", &outfile, &output.path("cliConvertedOutput.csv"),
+                              "--objective", "min-cost"]), EXIT_SUCCESS);
+    }
+}
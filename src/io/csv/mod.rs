@@ -1,13 +1,15 @@
 //! Structs that implement the Reader and Writer traits for CSV-formatted files.
 
 use std::cell::RefCell;
-use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
+use std::collections::HashMap;
+use std::io::Write;
 use std::iter::zip;
 use std::rc::Rc;
 use std::str::FromStr;
-use crate::io::{Reader, Writer};
-use crate::network::Network;
+use crate::io::{CsvDialect, get_network_issues, OutputLayout, ParseError, RankScheme, ReadError,
+                Reader, SourceLocation, ValidationIssue, Writer};
+use crate::network::{CostNormalization, Criterion, Network, Optimality, Severity, Statistics,
+                     Task, TaskDetails, Worker};
 #[cfg(test)]
 mod test;
 
@@ -15,11 +17,11 @@ mod test;
 /// reads the file and passes input from that file into the network struct it helps build.
 /// CSV inputs should be structured as follows:
 ///     --------------|-----------------|-----------------|-----------------|----
-///       <ignored>   |   Task Name 1   |   Task Name 2   |   Task Name 3   | ...
+///         Tasks     |   Task Name 1   |   Task Name 2   |   Task Name 3   | ...
 ///     --------------|-----------------|-----------------|-----------------|----
-///       <ignored>   |   Task 1 Min    |   Task 2 Min    |   Task 3 Min    | ...
+///        Minimum    |   Task 1 Min    |   Task 2 Min    |   Task 3 Min    | ...
 ///     --------------|-----------------|-----------------|-----------------|----
-///       <ignored>   |   Task 1 Max    |   Task 2 Max    |   Task 3 Max    | ...
+///        Maximum    |   Task 1 Max    |   Task 2 Max    |   Task 3 Max    | ...
 ///     --------------|-----------------|-----------------|-----------------|----
 ///     Worker 1 Name | Task 1 Affinity | Task 2 Affinity | Task 3 Affinity | ...
 ///     --------------|-----------------|-----------------|-----------------|----
@@ -29,138 +31,991 @@ mod test;
 /// Task minima and maxima must be non-negative integers, and setting the max to 0 will be treated
 /// as 0 rather than as infinite. Affinities can be any 32-bit floating-point value, including
 /// negative numbers, and if left blank will represent an unacceptable assignment (e.g. the worker
-/// cannot do the corresponding task).
+/// cannot do the corresponding task). The header rows after the task names, the attribute columns
+/// and the labelled sections that can follow the workers are listed in the "CSV Input Format"
+/// section of the README. Reading fails with the first error found, as a `ParseError` giving the
+/// row and column, while validating reports every issue.
 pub(super) struct CsvReader {
     // keep list of task IDs to pair up with affinities when reading worker data
     tasks: RefCell<Vec<Rc<String>>>,
+    // names of the attribute columns between the workers' names and the first task's column
+    attributes: RefCell<Vec<Rc<String>>>,
+    // problems found in the rows read so far
+    issues: RefCell<Vec<ValidationIssue>>,
+    dialect: CsvDialect,
+    // how to turn ranked lists of tasks into affinities, if the workers' rows hold them
+    ranking: Option<RankScheme>,
 }
 
 impl CsvReader {
-    /// Create a new `CsvReader` struct
-    pub fn new() -> CsvReader {
-        CsvReader { tasks: RefCell::new(Vec::new()) }
+    /// Create a new `CsvReader` struct that reads files in the given dialect
+    pub fn new(dialect: CsvDialect) -> CsvReader {
+        CsvReader {
+            tasks: RefCell::new(Vec::new()),
+            attributes: RefCell::new(Vec::new()),
+            issues: RefCell::new(Vec::new()),
+            dialect,
+            ranking: None,
+        }
     }
 
-    /// Read a provided file line by line to construct a Network from it
-    fn process_file<R>(&mut self, reader: R, network: &Network) -> std::io::Result<()>
-        where R: BufRead {
-        let mut line_iter = reader.lines();
+    /// Create a new `CsvReader` struct that reads files in the given dialect, whose workers' rows
+    /// list tasks in order of preference, and turns the ranks into affinities with the given scheme
+    pub fn ranked(dialect: CsvDialect, ranking: RankScheme) -> CsvReader {
+        CsvReader { ranking: Some(ranking), ..CsvReader::new(dialect) }
+    }
 
-        // initialize tasks
-        let task_names = match line_iter.next() {
-            Some(line) => line?,
-            None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
-                                                   "Empty input file!"))
+    /// Read the decoded text of a file record by record to construct a Network from it
+    fn process_file(&mut self, text: &str, network: &Network) -> Result<(), ReadError> {
+        let delimiter = self.dialect.get_input_delimiter(text);
+        let mut lines = numbered_lines(text);
+        self.process_rows(std::iter::from_fn(|| read_record(&mut lines, delimiter)), network)
+    }
+
+    /// Construct a Network from rows of cells laid out as described above, failing with the first
+    /// error found. Other readers whose files hold the same layout (e.g. spreadsheets) can pass
+    /// their rows in here.
+    pub(super) fn process_rows<I>(&mut self, rows: I, network: &Network)
+        -> Result<(), ReadError>
+        where I: Iterator<Item = Result<Vec<String>, ReadError>> {
+        self.read_rows(rows, network)?;
+        match self.issues.borrow().iter().find(|issue| issue.is_error()) {
+            Some(issue) => Err(issue.get_error().clone().into()),
+            None => Ok(())
+        }
+    }
+
+    /// Construct as much of a Network as possible from rows of cells laid out as described above,
+    /// returning every issue found with the rows and then with the network
+    pub(super) fn validate_rows<I>(&mut self, rows: I, network: &Network)
+        -> std::io::Result<Vec<ValidationIssue>>
+        where I: Iterator<Item = Result<Vec<String>, ReadError>> {
+        match self.read_rows(rows, network) {
+            Ok(()) => {},
+            Err(ReadError::Parse(err)) =>
+                self.issues.borrow_mut().push(ValidationIssue::from_error(Severity::Error, *err)),
+            Err(ReadError::Io(err)) => return Err(err)
+        }
+        let mut issues = self.issues.take();
+        issues.extend(get_network_issues(network));
+        Ok(issues)
+    }
+
+    /// Read the rows into the network, recording the problems found in them. Only rows that cannot
+    /// be read at all are an error.
+    fn read_rows<I>(&mut self, rows: I, network: &Network) -> Result<(), ReadError>
+        where I: Iterator<Item = Result<Vec<String>, ReadError>> {
+        self.issues.borrow_mut().clear();
+        let mut row_iter = (1..).zip(rows);
+        let names = match row_iter.next() {
+            Some((_, row)) => row?,
+            None => {
+                self.report(Severity::Error, None, None, "Empty input file!".to_string());
+                return Ok(());
+            }
         };
-        let task_minima = match line_iter.next() {
-            Some(line) => line?,
-            None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
-                                                   "No minimum requirements for tasks!"))
+        let attributes: Vec<Rc<String>> = names.iter().skip(1)
+            .map_while(|cell| get_attribute_name(cell))
+            .map(|name| Rc::new(name.to_string()))
+            .collect();
+        network.set_worker_attribute_names(attributes.clone());
+        *self.attributes.borrow_mut() = attributes;
+
+        // older files may label the rows of minimums and maximums with anything, so if neither of
+        // the two rows after the task names has a header label, they are read by their positions
+        let first_rows: Vec<_> = row_iter.by_ref().take(2).collect();
+        let positional = !first_rows.iter()
+            .any(|(_, row)| matches!(row, Ok(r) if get_header_row(r).is_some()));
+        let mut positional_rows = if positional {
+            vec![HeaderRow::Maximum, HeaderRow::Minimum]
+        } else {
+            Vec::new()
+        };
+        let mut row_iter = first_rows.into_iter().chain(row_iter).peekable();
+
+        // the header rows end with the first row after the minimums and maximums that is not a
+        // header row that is still to come, so that e.g. a worker named Max is not mistaken for one
+        let mut header_rows: HashMap<HeaderRow, (usize, Vec<String>)> = HashMap::new();
+        while let Some((row_number, row)) = row_iter.next_if(|(_, row)| {
+            let complete = header_rows.contains_key(&HeaderRow::Minimum)
+                && header_rows.contains_key(&HeaderRow::Maximum);
+            match row {
+                Ok(r) => !complete || matches!(get_header_row(r),
+                                               Some(kind) if !header_rows.contains_key(&kind)),
+                Err(_) => true
+            }
+        }) {
+            let r = row?;
+            match positional_rows.pop().or_else(|| get_header_row(&r)) {
+                Some(kind) if header_rows.contains_key(&kind) =>
+                    self.report(Severity::Error, Some(row_number), Some(1), format!(
+                        r#"Duplicate header row "{}"!"#, get_label(&r).unwrap_or_default())),
+                Some(kind) => {
+                    header_rows.insert(kind, (row_number, r));
+                },
+                None => self.report(Severity::Warning, Some(row_number), Some(1), format!(
+                    r#"Ignoring header row with unknown label "{}""#,
+                    get_label(&r).unwrap_or_default()))
+            }
+        }
+
+        // initialize tasks
+        let Some((min_row, minima)) = header_rows.get(&HeaderRow::Minimum) else {
+            self.report(Severity::Error, None, None,
+                        "No minimum requirements for tasks!".to_string());
+            return Ok(());
         };
-        let task_maxima = match line_iter.next() {
-            Some(line) => line?,
-            None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
-                                                   "No maximum capacities for tasks!"))
+        let Some((max_row, maxima)) = header_rows.get(&HeaderRow::Maximum) else {
+            self.report(Severity::Error, None, None,
+                        "No maximum capacities for tasks!".to_string());
+            return Ok(());
         };
-        self.process_tasks(network, &task_names, &task_minima, &task_maxima)?;
+        if !self.process_tasks(network, &names, (*min_row, minima), (*max_row, maxima)) {
+            // without the tasks, the workers' columns cannot be matched up with them
+            return Ok(());
+        }
 
-        // initialize workers
-        for line in line_iter {
-            match line {
-                Ok(l) => self.process_worker(network, &l)?,
-                Err(err) => return Err(err)
+        // apply staffing costs to tasks if the file includes them
+        if let Some((row_number, staffing_costs)) = header_rows.get(&HeaderRow::StaffingCosts) {
+            self.process_staffing_costs(network, *row_number, staffing_costs);
+        }
+        self.process_task_details(network, &header_rows);
+
+        // sort the remaining rows into workers, optional workers, the blocks of any further
+        // criteria and tasks' preferences for workers. The workers are only added once every
+        // criterion has been read, as their affinities weigh together their values for each one.
+        let mut section = Section::Workers;
+        let mut worker_rows = Vec::new();
+        let mut preference_rows = Vec::new();
+        let mut conflict_rows = Vec::new();
+        let mut criteria: Vec<CriterionBlock> = Vec::new();
+        for (row_number, row) in row_iter {
+            let r = row?;
+            if has_label(&r, CRITERION_LABEL) {
+                // a criterion row ahead of every worker names the criterion of their own rows
+                if worker_rows.is_empty() && criteria.is_empty() {
+                    criteria.push(self.process_criterion(row_number, &r, &criteria));
+                } else {
+                    if criteria.is_empty() {
+                        criteria.push(CriterionBlock::new(Rc::new(DEFAULT_CRITERION.to_string()),
+                                                          1.0));
+                    }
+                    criteria.push(self.process_criterion(row_number, &r, &criteria));
+                    section = Section::Criterion(criteria.len() - 1);
+                }
+                continue;
+            }
+            if has_label(&r, CONFLICTS_LABEL) {
+                section = Section::Conflicts;
+                continue;
+            }
+            match section {
+                Section::Preferences => preference_rows.push((row_number, r)),
+                _ if has_label(&r, TASK_PREFERENCES_LABEL) => section = Section::Preferences,
+                Section::Conflicts => conflict_rows.push((row_number, r)),
+                Section::Criterion(criterion_id) =>
+                    self.process_criterion_values(row_number, r, &mut criteria[criterion_id]),
+                _ if has_label(&r, OPTIONAL_WORKERS_LABEL) => section = Section::OptionalWorkers,
+                Section::Workers => worker_rows.push((row_number, r, false)),
+                Section::OptionalWorkers => worker_rows.push((row_number, r, true))
             }
         }
 
-        Ok(())
+        if !criteria.is_empty() {
+            network.set_criteria(criteria.iter()
+                .map(|block| Criterion::new(Rc::clone(&block.name), block.weight))
+                .collect());
+        }
+        for (row_number, r, optional) in worker_rows {
+            self.process_worker(network, row_number, &r, optional, &criteria);
+        }
+        for block in criteria.iter().skip(1) {
+            for (name, (row_number, _)) in &block.rows {
+                if !network.has_worker(name) {
+                    self.report(Severity::Error, Some(*row_number), Some(1), format!(
+                        "Values of criterion {} provided for unknown worker {}!", block.name,
+                        name));
+                }
+            }
+        }
+        for (row_number, r) in preference_rows {
+            self.process_task_preferences(network, row_number, &r);
+        }
+        for (row_number, r) in conflict_rows {
+            self.process_conflicts(network, row_number, &r);
+        }
+        add_worker_groups(network)
+    }
+
+    /// Start a criterion from the row that names it and gives its weight, which is 1 if blank
+    fn process_criterion(&self, row: usize, cells: &[String], criteria: &[CriterionBlock])
+        -> CriterionBlock {
+        let name = Rc::new(cells.get(1).map(|name| name.trim()).unwrap_or_default().to_string());
+        if name.is_empty() {
+            self.report(Severity::Error, Some(row), Some(2),
+                        "Missing criterion's name!".to_string());
+        } else if criteria.iter().any(|block| block.name == name) {
+            self.report(Severity::Error, Some(row), Some(2),
+                        format!("Duplicate criterion {}!", name));
+        }
+        let weight_text = cells.get(2).map(|weight| weight.trim()).unwrap_or_default();
+        let weight = match self.dialect.parse_number::<f32>(weight_text) {
+            _ if weight_text.is_empty() => 1.0,
+            Ok(w) if w.is_finite() => w,
+            Ok(_) => {
+                self.report_invalid(row, 3, weight_text, "finite criterion weight", format!(
+                    r#"Expected finite weight for criterion {}, found "{}"!"#, name, weight_text));
+                1.0
+            },
+            Err(err) => {
+                self.report_invalid(row, 3, weight_text, "numeric criterion weight", format!(
+                    r#"Expected numeric weight for criterion {}, found "{}"; error: {}"#, name,
+                    weight_text, err));
+                1.0
+            }
+        };
+        CriterionBlock::new(name, weight)
+    }
+
+    /// Hold on to a worker's row of values for a criterion until the worker is added
+    fn process_criterion_values(&self, row: usize, cells: Vec<String>,
+                                block: &mut CriterionBlock) {
+        let Some(name) = cells.first() else {
+            self.report(Severity::Error, Some(row), None, "Missing worker's name!".to_string());
+            return;
+        };
+        let worker_name = Rc::new(name.trim().to_string());
+        if block.rows.contains_key(&worker_name) {
+            self.report(Severity::Error, Some(row), Some(1), format!(
+                "Duplicate values of criterion {} for worker {}!", block.name, worker_name));
+            return;
+        }
+        block.rows.insert(worker_name, (row, cells));
+    }
+
+    /// Record a problem found at the given row and column, if known
+    fn report(&self, severity: Severity, row: Option<usize>, column: Option<usize>,
+              message: String) {
+        self.issues.borrow_mut().push(ValidationIssue::new(severity, row, column, message));
+    }
+
+    /// Record an error for the text found at the given row and column, which is not a valid value
+    /// of the expected kind
+    fn report_invalid(&self, row: usize, column: usize, text: &str, expected: &str,
+                      message: String) {
+        let error = ParseError::invalid_value(SourceLocation::at(Some(row), Some(column)), text,
+                                              expected, message);
+        self.issues.borrow_mut().push(ValidationIssue::from_error(Severity::Error, error));
     }
 
     /// Construct the tasks from lists of their names and the lower and upper bounds on number of
-    /// assigned workers
-    fn process_tasks(&mut self, network: &Network, task_names: &str, task_minima: &str,
-                     task_maxima: &str) -> std::io::Result<()> {
-        let names = task_names.split(',').collect::<Vec<&str>>();
-        let minima = task_minima.split(',').collect::<Vec<&str>>();
-        let maxima = task_maxima.split(',').collect::<Vec<&str>>();
+    /// assigned workers, returning whether the rows lined up well enough to do so. A task whose
+    /// bounds are invalid is still added, so that the workers' columns stay matched up with it.
+    fn process_tasks(&mut self, network: &Network, names: &[String],
+                     (min_row, minima): (usize, &[String]), (max_row, maxima): (usize, &[String]))
+        -> bool {
         if names.len() != minima.len() || names.len() != maxima.len() {
             // mismatched input sizes imply either missing or extra data and thus bad input format
-            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
-                                           "Mismatched input data for tasks: each task must have both \
-                                           a minimum and a maximum number of workers specified."));
+            self.report(Severity::Error, None, None,
+                        "Mismatched input data for tasks: each task must have both a minimum \
+                        and a maximum number of workers specified.".to_string());
+            return false;
         }
 
-        for (name, (minimum, maximum)) in zip(names, zip(minima, maxima)).skip(1) {
+        for (column, (name, (minimum, maximum))) in
+            (1..).zip(zip(names, zip(minima, maxima))).skip(self.get_first_task_column() - 1) {
             let lower = match usize::from_str(minimum.trim()) {
                 Ok(m) => m,
-                Err(err) =>
-                    return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
-                                                   format!(r#"Expected integer minimum, found "{}"; error: {}"#,
-                                                           minimum, err)))
+                Err(err) => {
+                    self.report_invalid(min_row, column, minimum, "integer minimum", format!(
+                        r#"Expected integer minimum, found "{}"; error: {}"#, minimum, err));
+                    0
+                }
             };
             let upper = match usize::from_str(maximum.trim()) {
+                Ok(m) if m < lower => {
+                    self.report(Severity::Error, Some(max_row), Some(column),
+                                "Maximum cannot be less than minimum!".to_string());
+                    lower
+                },
                 Ok(m) => m,
-                Err(err) =>
-                    return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
-                                                   format!(r#"Expected integer maximum, found "{}"; error: {}"#,
-                                                           maximum, err)))
+                Err(err) => {
+                    self.report_invalid(max_row, column, maximum, "integer maximum", format!(
+                        r#"Expected integer maximum, found "{}"; error: {}"#, maximum, err));
+                    usize::MAX
+                }
             };
-            if upper < lower {
-                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
-                                               "Maximum cannot be less than minimum!".to_string()));
-            }
 
             let task_name = Rc::new(name.trim().to_string());
+            if self.tasks.borrow().contains(&task_name) {
+                self.report(Severity::Error, Some(1), Some(column),
+                            format!("Duplicate task {}!", task_name));
+            }
             self.tasks.borrow_mut().push(Rc::clone(&task_name));
             network.add_task(task_name, lower, upper);
         }
-        Ok(())
+        true
     }
 
-    /// Add a new worker to the network under construction
-    fn process_worker(&mut self, network: &Network, worker_info: &str) -> std::io::Result<()> {
-        let mut affinities = Vec::new();
-        let mut info = worker_info.split(',');
-        let worker_name = info.next()
-            .expect("Problem reading worker's name!")
-            .trim().to_string();
+    /// Set the schedule of marginal staffing costs for each task that has one
+    fn process_staffing_costs(&mut self, network: &Network, row: usize, staffing_costs: &[String]) {
+        let tasks = self.tasks.borrow();
+        let first_column = self.get_first_task_column();
+        for (column, (task_name, schedule)) in
+            (first_column..).zip(zip(tasks.iter(), staffing_costs.iter().skip(first_column - 1))) {
+            let mut marginal_costs = Vec::new();
+            let mut valid = true;
+            for cost in schedule.split_whitespace() {
+                match self.dialect.parse_number::<f32>(cost) {
+                    Ok(c) if c.is_finite() => marginal_costs.push(c),
+                    Ok(_) => {
+                        self.report_invalid(row, column, cost, "finite staffing cost", format!(
+                            r#"Expected finite staffing cost, found "{}"!"#, cost));
+                        valid = false;
+                    },
+                    Err(err) => {
+                        self.report_invalid(row, column, cost, "numeric staffing cost", format!(
+                            r#"Expected numeric staffing cost, found "{}"; error: {}"#, cost, err));
+                        valid = false;
+                    }
+                }
+            }
+            if marginal_costs.windows(2).any(|pair| pair[1] < pair[0]) {
+                self.report(Severity::Error, Some(row), Some(column),
+                            format!("Staffing costs for task {} must not decrease!", task_name));
+                valid = false;
+            }
+
+            if valid && !marginal_costs.is_empty() {
+                network.set_staffing_costs(task_name, marginal_costs);
+            }
+        }
+        self.report_extra_values(row, staffing_costs, "staffing costs");
+    }
 
+    /// Read the optional header rows of task priorities, groups, shortage penalties and required
+    /// skills into each task's details. Blank entries leave the detail unset.
+    fn process_task_details(&mut self, network: &Network,
+                            header_rows: &HashMap<HeaderRow, (usize, Vec<String>)>) {
         let tasks = self.tasks.borrow();
-        for task_name in tasks.iter() {
-            let val = match info.next() {
-                Some(v) => v,
-                None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
-                                                       format!("Too few task affinities for worker {}!",
-                                                               worker_name)))
+        let first_column = self.get_first_task_column();
+        let mut details = vec![TaskDetails::default(); tasks.len()];
+        for kind in [HeaderRow::Priority, HeaderRow::TaskGroup, HeaderRow::ShortagePenalty,
+                     HeaderRow::RequiredSkills] {
+            let Some((row, cells)) = header_rows.get(&kind) else {
+                continue;
             };
+            for (column, (task_details, cell)) in
+                (first_column..).zip(zip(details.iter_mut(), cells.iter().skip(first_column - 1))) {
+                let text = cell.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                match kind {
+                    HeaderRow::Priority => match i32::from_str(text) {
+                        Ok(priority) => task_details.priority = Some(priority),
+                        Err(err) => self.report_invalid(*row, column, text, "integer priority",
+                            format!(r#"Expected integer priority, found "{}"; error: {}"#, text,
+                                    err))
+                    },
+                    HeaderRow::TaskGroup => task_details.group = Some(Rc::new(text.to_string())),
+                    HeaderRow::ShortagePenalty => match self.dialect.parse_number::<f32>(text) {
+                        Ok(penalty) if penalty.is_finite() && penalty >= 0.0 =>
+                            task_details.shortage_penalty = Some(penalty),
+                        Ok(_) => self.report_invalid(
+                            *row, column, text, "non-negative shortage penalty",
+                            format!(r#"Expected non-negative shortage penalty, found "{}"!"#,
+                                    text)),
+                        Err(err) => self.report_invalid(
+                            *row, column, text, "numeric shortage penalty",
+                            format!(r#"Expected numeric shortage penalty, found "{}"; error: {}"#,
+                                    text, err))
+                    },
+                    HeaderRow::RequiredSkills => task_details.required_skills = text
+                        .split(SKILL_SEPARATOR)
+                        .map(str::trim)
+                        .filter(|skill| !skill.is_empty())
+                        .map(|skill| Rc::new(skill.to_string()))
+                        .collect(),
+                    _ => {}
+                }
+            }
+            self.report_extra_values(*row, cells, kind.describe());
+        }
+
+        for (task_name, task_details) in zip(tasks.iter(), details) {
+            if task_details != TaskDetails::default() {
+                network.set_task_details(task_name, task_details);
+            }
+        }
+    }
+
+    /// Add a new worker to the network under construction. With criteria, the values in the
+    /// worker's own row are for the first one, and their affinities weigh together their values for
+    /// every criterion.
+    fn process_worker(&mut self, network: &Network, row: usize, worker_info: &[String],
+                      optional: bool, criteria: &[CriterionBlock]) {
+        let Some(name) = worker_info.first() else {
+            self.report(Severity::Error, Some(row), None, "Missing worker's name!".to_string());
+            return;
+        };
+        let worker_name = Rc::new(name.trim().to_string());
+        if network.has_worker(&worker_name) {
+            self.report(Severity::Error, Some(row), Some(1),
+                        format!("Duplicate worker {}!", worker_name));
+            return;
+        }
+
+        let attributes = self.get_attributes(worker_info);
+        let optional = self.is_marked_optional(row, &attributes) || optional;
+        let tasks = self.tasks.borrow();
+        let affinities = match &self.ranking {
+            Some(ranking) => self.parse_ranks(&tasks, ranking, row, &worker_name, worker_info),
+            None => self.parse_scores(&tasks, row, &worker_name, worker_info,
+                                      "task affinities", "worker affinity")
+        };
+        let mut criterion_values = vec![affinities];
+        for block in criteria.iter().skip(1) {
+            match block.rows.get(&worker_name) {
+                Some((criterion_row, cells)) => criterion_values.push(self.parse_scores(
+                    &tasks, *criterion_row, &worker_name, cells,
+                    &format!("values of criterion {}", block.name),
+                    &format!("criterion {}", block.name))),
+                None => {
+                    self.report(Severity::Error, Some(row), Some(1), format!(
+                        "No values of criterion {} for worker {}!", block.name, worker_name));
+                    criterion_values.push(Vec::new());
+                }
+            }
+        }
+        let affinities = if criteria.is_empty() {
+            criterion_values.swap_remove(0)
+        } else {
+            network.combine_criteria(&criterion_values)
+        };
+        if optional {
+            network.add_optional_worker(Rc::clone(&worker_name), &affinities);
+        } else {
+            network.add_worker(Rc::clone(&worker_name), &affinities);
+        }
+        if !criteria.is_empty() {
+            network.set_worker_criterion_values(&worker_name, &criterion_values);
+        }
+        if !attributes.is_empty() {
+            network.set_worker_attributes(&worker_name, attributes);
+        }
+    }
+
+    /// Get the first column that holds a task's values, after the label and attribute columns
+    fn get_first_task_column(&self) -> usize {
+        self.attributes.borrow().len() + 2
+    }
+
+    /// Get a worker's value in each attribute column, blank for any beyond the end of the row
+    fn get_attributes(&self, worker_info: &[String]) -> Vec<String> {
+        (1..=self.attributes.borrow().len())
+            .map(|column| worker_info.get(column)
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default())
+            .collect()
+    }
+
+    /// Check whether a worker's value in the "Optional" attribute column, if there is one, marks
+    /// them as optional
+    fn is_marked_optional(&self, row: usize, attributes: &[String]) -> bool {
+        let Some(index) = self.attributes.borrow().iter()
+            .position(|name| name.eq_ignore_ascii_case(OPTIONAL_ATTRIBUTE)) else {
+            return false;
+        };
+        let value = &attributes[index];
+        match value.to_lowercase().as_str() {
+            "" | "no" | "n" | "false" | "0" => false,
+            "yes" | "y" | "true" | "1" => true,
+            _ => {
+                self.report_invalid(row, index + 2, value, "yes or no", format!(
+                    r#"Expected yes or no for whether the worker is optional, found "{}"!"#,
+                    value));
+                false
+            }
+        }
+    }
+
+    /// Record the scores that each task gives a worker who has already been added to the network
+    fn process_task_preferences(&mut self, network: &Network, row: usize,
+                                preference_info: &[String]) {
+        let Some(name) = preference_info.first() else {
+            self.report(Severity::Error, Some(row), None, "Missing worker's name!".to_string());
+            return;
+        };
+        let worker_name = Rc::new(name.trim().to_string());
+        if !network.has_worker(&worker_name) {
+            self.report(Severity::Error, Some(row), Some(1),
+                        format!("Task preferences provided for unknown worker {}!", worker_name));
+            return;
+        }
 
+        let tasks = self.tasks.borrow();
+        let scores = self.parse_scores(&tasks, row, &worker_name, preference_info,
+                                       "task preferences", "task preference");
+        network.add_task_preferences(&worker_name, &scores);
+    }
+
+    /// Record that every pair of the workers listed in a row must not share a task. The workers
+    /// must already have been added to the network.
+    fn process_conflicts(&self, network: &Network, row: usize, cells: &[String]) {
+        let mut worker_names: Vec<Rc<String>> = Vec::new();
+        for (column, cell) in (1..).zip(cells) {
+            let name = Rc::new(cell.trim().to_string());
+            if name.is_empty() {
+                continue;
+            }
+            if network.has_worker(&name) {
+                worker_names.push(name);
+            } else {
+                self.report(Severity::Error, Some(row), Some(column),
+                            format!("Conflict listed for unknown worker {}!", name));
+            }
+        }
+        if worker_names.len() == 1 {
+            self.report(Severity::Warning, Some(row), None, format!(
+                "Ignoring conflict that lists only worker {}", worker_names[0]));
+        }
+        for (position, first) in worker_names.iter().enumerate() {
+            for second in &worker_names[position + 1..] {
+                network.add_conflict(first, second);
+            }
+        }
+    }
+
+    /// Pair each task with the value in its column of a worker's row, skipping blank values and
+    /// reporting invalid ones. The descriptions name the values in the issues reported, in plural
+    /// and singular form respectively.
+    fn parse_scores<'a>(&self, tasks: &'a [Rc<String>], row: usize, worker_name: &str,
+                        cells: &[String], plural_description: &str, singular_description: &str)
+        -> Vec<(&'a Rc<String>, f32)> {
+        let first_column = self.get_first_task_column();
+        let values = cells.get(first_column - 1..).unwrap_or_default();
+        if values.len() < tasks.len() {
+            self.report(Severity::Error, Some(row), None,
+                        format!("Too few {} for worker {}!", plural_description, worker_name));
+        }
+
+        let mut scores = Vec::new();
+        for (column, (task_name, val)) in (first_column..).zip(zip(tasks.iter(), values.iter())) {
             if !val.is_empty() {
-                let aff = match f32::from_str(val) {
-                    Ok(v) => v,
-                    Err(err) =>
-                        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
-                                                       format!(r#"Expected numeric value for worker affinity, found "{}"; error: {}"#,
-                                                               val, err)))
-                };
-                affinities.push((task_name, aff)); // task ID stored in self.tasks
+                match self.dialect.parse_number::<f32>(val) {
+                    Ok(v) if v.is_finite() => scores.push((task_name, v)),
+                    Ok(_) => self.report_invalid(
+                        row, column, val, &format!("finite {}", singular_description), format!(
+                            r#"Expected finite value for {}, found "{}"!"#,
+                            singular_description, val)),
+                    Err(err) => self.report_invalid(
+                        row, column, val, &format!("numeric {}", singular_description), format!(
+                            r#"Expected numeric value for {}, found "{}"; error: {}"#,
+                            singular_description, val, err))
+                }
             }
         }
+        self.report_extra_values(row, cells, plural_description);
 
-        network.add_worker(Rc::new(worker_name), &affinities);
+        scores
+    }
 
-        Ok(())
+    /// Pair each task with the cost the ranking scheme gives it from its rank in a worker's list of
+    /// task names, reporting names that are not tasks or are listed twice
+    fn parse_ranks<'a>(&self, tasks: &'a [Rc<String>], ranking: &RankScheme, row: usize,
+                       worker_name: &str, cells: &[String]) -> Vec<(&'a Rc<String>, f32)> {
+        let first_column = self.get_first_task_column();
+        let mut ranked_tasks = Vec::new();
+        for (column, cell) in (1..).zip(cells.iter()).skip(first_column - 1) {
+            let name = cell.trim();
+            if name.is_empty() {
+                continue;
+            }
+            match tasks.iter().position(|task| task.as_str() == name) {
+                Some(task_id) if ranked_tasks.contains(&task_id) =>
+                    self.report(Severity::Error, Some(row), Some(column), format!(
+                        "Task {} is ranked more than once by worker {}!", name, worker_name)),
+                Some(task_id) => ranked_tasks.push(task_id),
+                None => self.report_invalid(row, column, name, "task name", format!(
+                    "Unknown task {} ranked by worker {}!", name, worker_name))
+            }
+        }
+
+        ranking.get_affinities(&ranked_tasks, tasks.len()).into_iter()
+            .map(|(task_id, cost)| (&tasks[task_id], cost))
+            .collect()
+    }
+
+    /// Warn about any values in a row beyond the last task's column, which are ignored
+    fn report_extra_values(&self, row: usize, cells: &[String], description: &str) {
+        let last_column = self.get_first_task_column() + self.tasks.borrow().len() - 1;
+        for (column, cell) in (1..).zip(cells.iter()).skip(last_column) {
+            if !cell.trim().is_empty() {
+                self.report(Severity::Warning, Some(row), Some(column), format!(
+                    r#"Ignoring "{}" in {} beyond the last task's column"#, cell, description));
+            }
+        }
+    }
+}
+
+/// Label in the first column of the optional row of staffing costs
+pub(super) const STAFFING_COSTS_LABEL: &str = "Staffing Costs";
+
+/// Label in the first column of the optional row of task priorities
+pub(super) const PRIORITY_LABEL: &str = "Priority";
+
+/// Label in the first column of the optional row of task groups
+pub(super) const TASK_GROUP_LABEL: &str = "Task Group";
+
+/// Label in the first column of the optional row of shortage penalties
+pub(super) const SHORTAGE_PENALTY_LABEL: &str = "Shortage Penalty";
+
+/// Label in the first column of the optional row of required skills
+pub(super) const REQUIRED_SKILLS_LABEL: &str = "Required Skills";
+
+/// Separator between the skills a task requires, within its entry in the row of required skills
+pub(super) const SKILL_SEPARATOR: char = ';';
+
+/// The rows that can follow the row of task names, in any order, each recognized by its label
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum HeaderRow {
+    Minimum,
+    Maximum,
+    StaffingCosts,
+    Priority,
+    TaskGroup,
+    ShortagePenalty,
+    RequiredSkills,
+}
+
+impl HeaderRow {
+    /// Recognize a header row from its label, ignoring case. Besides the labels the writers use,
+    /// this accepts the short forms and the "Thresholds" and "Capacities" of older files.
+    fn from_label(label: &str) -> Option<HeaderRow> {
+        match label.to_lowercase().as_str() {
+            "minimum" | "minimums" | "min" | "thresholds" => Some(HeaderRow::Minimum),
+            "maximum" | "maximums" | "max" | "capacities" => Some(HeaderRow::Maximum),
+            _ => [(STAFFING_COSTS_LABEL, HeaderRow::StaffingCosts),
+                  (PRIORITY_LABEL, HeaderRow::Priority),
+                  (TASK_GROUP_LABEL, HeaderRow::TaskGroup),
+                  (SHORTAGE_PENALTY_LABEL, HeaderRow::ShortagePenalty),
+                  (REQUIRED_SKILLS_LABEL, HeaderRow::RequiredSkills)].into_iter()
+                .find(|(known, _)| label.eq_ignore_ascii_case(known))
+                .map(|(_, kind)| kind)
+        }
+    }
+
+    /// Describe the values the row holds, for reporting problems with them
+    fn describe(self) -> &'static str {
+        match self {
+            HeaderRow::Minimum => "minimums",
+            HeaderRow::Maximum => "maximums",
+            HeaderRow::StaffingCosts => "staffing costs",
+            HeaderRow::Priority => "priorities",
+            HeaderRow::TaskGroup => "task groups",
+            HeaderRow::ShortagePenalty => "shortage penalties",
+            HeaderRow::RequiredSkills => "required skills",
+        }
     }
 }
 
+/// Get the labelled rows of the tasks' details that any task has set, each with every task's
+/// entry, for writing input files
+pub(super) fn get_task_detail_rows(tasks: &[Task], dialect: &CsvDialect)
+    -> Vec<(&'static str, Vec<String>)> {
+    let row = |value: &dyn Fn(&TaskDetails) -> Option<String>| tasks.iter()
+        .map(|task| value(task.get_details()).unwrap_or_default())
+        .collect::<Vec<String>>();
+    let rows = [
+        (PRIORITY_LABEL, row(&|details| details.priority.map(|p| p.to_string()))),
+        (TASK_GROUP_LABEL, row(&|details| details.group.as_ref().map(|g| g.to_string()))),
+        (SHORTAGE_PENALTY_LABEL,
+         row(&|details| details.shortage_penalty.map(|p| dialect.format_number(p)))),
+        (REQUIRED_SKILLS_LABEL, row(&|details| Some(details.required_skills.iter()
+            .map(|skill| skill.as_str())
+            .collect::<Vec<&str>>()
+            .join(&format!("{} ", SKILL_SEPARATOR))))),
+    ];
+    rows.into_iter()
+        .filter(|(_, cells)| cells.iter().any(|cell| !cell.is_empty()))
+        .collect()
+}
+
+/// Name of the attribute column whose yes or no values mark workers as optional
+pub(super) const OPTIONAL_ATTRIBUTE: &str = "Optional";
+
+/// Name of the attribute column that puts workers with the same value in it in a group that must
+/// share a task
+pub(super) const GROUP_ATTRIBUTE: &str = "Group";
+
+/// Label in the first column of the row that separates mandatory workers from optional workers
+pub(super) const OPTIONAL_WORKERS_LABEL: &str = "Optional Workers";
+
+/// Label in the first column of the row that separates worker affinities from task preferences
+pub(super) const TASK_PREFERENCES_LABEL: &str = "Task Preferences";
+
+/// Label in the first column of the row that names a criterion and gives its weight
+pub(super) const CRITERION_LABEL: &str = "Criterion";
+
+/// Label in the first column of the row that starts the lists of workers who must not share a task
+pub(super) const CONFLICTS_LABEL: &str = "Conflicts";
+
+/// Label of the section of the output that lists the workers left without a task
+pub(super) const UNASSIGNED_WORKERS_LABEL: &str = "Unassigned workers:";
+
+/// Label of the section of the output that lists the optional workers left without a task
+pub(super) const UNUSED_OPTIONAL_WORKERS_LABEL: &str = "Unused optional workers:";
+
+/// Label of the section of the output that lists the pairs blocking a stable matching
+pub(super) const BLOCKING_PAIRS_LABEL: &str = "Blocking pairs:";
+
+/// Label of the section of the output that lists the workers moved from their previous tasks
+pub(super) const MOVED_WORKERS_LABEL: &str = "Moved workers:";
+
+/// Label of the section of the output that lists each task's backup workers
+pub(super) const BACKUP_WORKERS_LABEL: &str = "Backup workers:";
+
+/// Describe whether a solution is proven optimal or was found heuristically, for the output
+pub(super) fn format_optimality(optimality: &Optimality) -> String {
+    match optimality.limit {
+        _ if optimality.proven_optimal => "proven optimal".to_string(),
+        Some(limit) => format!("heuristic ({})", limit),
+        None => "heuristic".to_string()
+    }
+}
+
+/// The labelled rows of totals at the top of the output, ahead of the assignments
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum TotalRow {
+    TotalScore,
+    StaffingCostTotal,
+    RawCostTotal,
+    ChangePenaltyTotal,
+    /// One of the rows that break the total score down by criterion
+    CriterionTotal,
+    WorkerScoreTotal,
+    TaskScoreTotal,
+    /// The row that says whether the solution is proven optimal
+    Solution,
+    LowerBound,
+    OptimalityGap,
+}
+
+impl TotalRow {
+    /// Every row of totals, in the order the writers put them
+    const ALL: [TotalRow; 10] = [
+        TotalRow::TotalScore, TotalRow::StaffingCostTotal, TotalRow::RawCostTotal,
+        TotalRow::ChangePenaltyTotal, TotalRow::CriterionTotal, TotalRow::WorkerScoreTotal,
+        TotalRow::TaskScoreTotal, TotalRow::Solution, TotalRow::LowerBound,
+        TotalRow::OptimalityGap,
+    ];
+
+    /// Get the label in the first column of the row
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            TotalRow::TotalScore => "Total score:",
+            TotalRow::StaffingCostTotal => "Staffing cost total:",
+            TotalRow::RawCostTotal => "Raw cost total:",
+            TotalRow::ChangePenaltyTotal => "Change penalty total:",
+            TotalRow::CriterionTotal => "Criterion total:",
+            TotalRow::WorkerScoreTotal => "Worker score total:",
+            TotalRow::TaskScoreTotal => "Task score total:",
+            TotalRow::Solution => "Solution:",
+            TotalRow::LowerBound => "Lower bound:",
+            TotalRow::OptimalityGap => "Optimality gap:",
+        }
+    }
+
+    /// Recognize a row of totals from its label, ignoring case
+    fn from_label(label: &str) -> Option<TotalRow> {
+        TotalRow::ALL.into_iter().find(|row| label.eq_ignore_ascii_case(row.label()))
+    }
+}
+
+/// Name of the criterion of the workers' own rows, when further criteria follow without it being
+/// named
+const DEFAULT_CRITERION: &str = "Cost";
+
+/// The sections of the rows after the header rows, each started by a labelled row
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Section {
+    Workers,
+    OptionalWorkers,
+    /// The rows giving each worker's values for the criterion with this index
+    Criterion(usize),
+    Preferences,
+    Conflicts,
+}
+
+/// A criterion read from the rows, along with each worker's row of values for it, keyed by the
+/// worker's name
+struct CriterionBlock {
+    name: Rc<String>,
+    weight: f32,
+    rows: HashMap<Rc<String>, (usize, Vec<String>)>,
+}
+
+impl CriterionBlock {
+    fn new(name: Rc<String>, weight: f32) -> CriterionBlock {
+        CriterionBlock { name, weight, rows: HashMap::new() }
+    }
+}
+
+/// Get the lines of a file's decoded text for `read_record`, each with its line number
+pub(super) fn numbered_lines(text: &str)
+    -> impl Iterator<Item = (usize, std::io::Result<String>)> + '_ {
+    (1..).zip(text.lines().map(|line| Ok(line.to_string())))
+}
+
+/// Read the next record from the numbered lines of a file, following RFC 4180: fields are separated
+/// by the delimiter, and a field wrapped in double quotes can contain delimiters, line breaks and
+/// double quotes (written twice). Whitespace before a field's opening quote is ignored. Returns
+/// `None` once every line has been read.
+pub(super) fn read_record<I>(lines: &mut I, delimiter: char)
+    -> Option<Result<Vec<String>, ReadError>>
+    where I: Iterator<Item = (usize, std::io::Result<String>)> {
+    let (mut line_number, mut line) = match lines.next()? {
+        (line_number, Ok(line)) => (line_number, line),
+        (_, Err(err)) => return Some(Err(err.into()))
+    };
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    // where the quoted field being read opened, to point at it if it is never closed
+    let mut quote_location = SourceLocation::default();
+    loop {
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                match c {
+                    '"' if chars.peek() == Some(&'"') => {
+                        field.push('"');
+                        chars.next();
+                    },
+                    '"' => in_quotes = false,
+                    _ => field.push(c)
+                }
+            } else {
+                match c {
+                    _ if c == delimiter => fields.push(std::mem::take(&mut field)),
+                    '"' if field.trim().is_empty() => {
+                        field.clear();
+                        in_quotes = true;
+                        quote_location =
+                            SourceLocation::at(Some(line_number), Some(fields.len() + 1));
+                    },
+                    _ => field.push(c)
+                }
+            }
+        }
+
+        if !in_quotes {
+            break;
+        }
+        // the quoted field continues on the next line
+        (line_number, line) = match lines.next() {
+            Some((line_number, Ok(line))) => (line_number, line),
+            Some((_, Err(err))) => return Some(Err(err.into())),
+            None => return Some(Err(ParseError::malformed(
+                quote_location, "Unterminated quoted field at end of file!".to_string()).into()))
+        };
+        field.push('\n');
+    }
+    fields.push(field);
+
+    Some(Ok(fields))
+}
+
+/// Get the label in the first column of a row, if it has any cells
+fn get_label(row: &[String]) -> Option<&str> {
+    row.first().map(|label| label.trim())
+}
+
+/// Get which row of totals at the top of the output a row is, if it is one
+fn get_total_row(row: &[String]) -> Option<TotalRow> {
+    get_label(row).and_then(TotalRow::from_label)
+}
+
+/// Check whether a row starts with the given section label, ignoring case
+pub(super) fn has_label(row: &[String], label: &str) -> bool {
+    get_label(row).is_some_and(|l| l.eq_ignore_ascii_case(label))
+}
+
+/// Get the name of the attribute a column holds from its header in the row of task names, if the
+/// header is wrapped in square brackets, e.g. "[Department]"
+fn get_attribute_name(header: &str) -> Option<&str> {
+    header.trim().strip_prefix('[')?.strip_suffix(']').map(str::trim)
+}
+
+/// Format the header of an attribute column
+pub(super) fn format_attribute_header(name: &str) -> String {
+    format!("[{}]", name)
+}
+
+/// Put the workers who have the same value in the "Group" attribute, if the network has one, in a
+/// group that must share a task, named after the value. Workers with a blank value are not in a
+/// group, and a worker listed twice, or already in a group, is an error.
+pub(super) fn add_worker_groups(network: &Network) -> Result<(), ReadError> {
+    let Some(attribute_id) = network.get_worker_attribute_names().iter()
+        .position(|name| name.eq_ignore_ascii_case(GROUP_ATTRIBUTE)) else {
+        return Ok(());
+    };
+    let grouped: Vec<Rc<String>> = network.get_worker_groups().into_iter()
+        .flat_map(|(_, members)| members)
+        .collect();
+    let mut groups: Vec<(Rc<String>, Vec<Rc<String>>)> = Vec::new();
+    for worker in network.get_workers() {
+        let group_name = worker.get_attribute(attribute_id);
+        if group_name.is_empty() {
+            continue;
+        }
+        let worker_name = Rc::clone(worker.get_name());
+        if grouped.contains(&worker_name)
+            || groups.iter().any(|(_, members)| members.contains(&worker_name)) {
+            return Err(ParseError::malformed(SourceLocation::default(), format!(
+                "Worker {} is already in a group!", worker_name)).into());
+        }
+        match groups.iter_mut().find(|(name, _)| name.as_str() == group_name) {
+            Some((_, members)) => members.push(worker_name),
+            None => groups.push((Rc::new(group_name.to_string()), vec![worker_name]))
+        }
+    }
+    for (name, members) in groups {
+        network.add_worker_group(name, &members);
+    }
+    Ok(())
+}
+
+/// Get the kind of header row a row is, if its label is one
+fn get_header_row(row: &[String]) -> Option<HeaderRow> {
+    get_label(row).and_then(HeaderRow::from_label)
+}
+
 impl Reader for CsvReader {
-    /// Create file handle and pass it to the `process_file` method for reading
-    fn read_file(&mut self, filename: String, network: &Network) -> std::io::Result<()> {
-        let f = File::open(filename)?;
-        self.process_file(BufReader::new(f), network)
+    /// Read and decode the file, and pass its text to the `process_file` method for reading
+    fn read_file(&mut self, filename: String, network: &Network) -> Result<(), ReadError> {
+        let text = self.dialect.decode(&std::fs::read(filename)?)?;
+        self.process_file(&text, network)
     }
 
     fn clone_task_names(&self) -> Vec<Rc<String>> {
         self.tasks.borrow().clone()
     }
+
+    /// Read the file record by record, collecting every issue with its rows and the network
+    fn validate_file(&mut self, filename: String, network: &Network)
+        -> std::io::Result<Vec<ValidationIssue>> {
+        let text = match self.dialect.decode(&std::fs::read(filename)?) {
+            Ok(text) => text,
+            Err(ReadError::Parse(err)) =>
+                return Ok(vec![ValidationIssue::from_error(Severity::Error, *err)]),
+            Err(ReadError::Io(err)) => return Err(err)
+        };
+        let delimiter = self.dialect.get_input_delimiter(&text);
+        let mut lines = numbered_lines(&text);
+        self.validate_rows(std::iter::from_fn(|| read_record(&mut lines, delimiter)), network)
+    }
 }
 
 /// A writer for CSV-formatted output data. Given a network that contains its min cost max flow,
@@ -175,78 +1030,479 @@ impl Reader for CsvReader {
 ///      Worker 2 Name  |  Worker 4 Name  |  Worker 6 Name  |  Worker 8 Name  | ...
 ///     ----------------|-----------------|-----------------|-----------------|----
 ///     ...
+/// Rows of further totals can follow the total score, and labelled sections (e.g. of unassigned
+/// workers or backups) can follow the table. With the by-worker layout, the task columns are
+/// replaced by a header row and one row per worker. Both layouts, the totals and the sections are
+/// listed in the "CSV Output Format" section of the README. Names are quoted wherever needed so
+/// that they read back as written.
 pub(super) struct CsvWriter {
     task_names: Vec<Rc<String>>,
+    layout: OutputLayout,
+    dialect: CsvDialect,
 }
 
 impl CsvWriter {
-    /// Create a new `CsvWriter`
-    pub fn new(task_names: Vec<Rc<String>>) -> CsvWriter {
+    /// Create a new `CsvWriter` with the given layout for its assignments, writing in the given
+    /// dialect
+    pub fn new(task_names: Vec<Rc<String>>, layout: OutputLayout, dialect: CsvDialect)
+        -> CsvWriter {
         CsvWriter {
             task_names,
+            layout,
+            dialect,
         }
     }
 
-    /// Write outputs collected from a Network into a file handle, in CSV format
-    fn write(&self, outputs: &Network, mut file: File) -> std::io::Result<()> {
+    /// Write outputs collected from a Network into a buffer, in CSV format
+    fn write(&self, outputs: &Network, out: &mut Vec<u8>) -> std::io::Result<()> {
+        let dialect = &self.dialect;
+        let total_row = |row: TotalRow, total: f32| {
+            dialect.format_row(&[row.label().to_string(), dialect.format_number(total)])
+        };
+
         // record final "score" of solution - sum of affinity scores over assignments that were made
-        // note that affinity scores are negated as a result of the assignment happening, so we need
-        // to negate the total score
-        writeln!(file, "Total score:,{}",
-                 -outputs.get_cost_of_arcs_from_nodes(&self.task_names))?;
+        writeln!(out, "{}", total_row(TotalRow::TotalScore, outputs.get_total_cost()))?;
+        if let Some(staffing_cost_total) = outputs.get_staffing_cost_total() {
+            writeln!(out, "{}", total_row(TotalRow::StaffingCostTotal, staffing_cost_total))?;
+        }
+        if let Some(raw_cost_total) = outputs.get_raw_cost_total() {
+            writeln!(out, "{}", total_row(TotalRow::RawCostTotal, raw_cost_total))?;
+        }
+        if let Some(change_penalty_total) = outputs.get_change_penalty_total() {
+            writeln!(out, "{}", total_row(TotalRow::ChangePenaltyTotal, change_penalty_total))?;
+        }
+        for (criterion, total) in outputs.get_criterion_totals() {
+            writeln!(out, "{}", dialect.format_row(&[TotalRow::CriterionTotal.label().to_string(),
+                                                     criterion.get_name().to_string(),
+                                                     dialect.format_number(total)]))?;
+        }
+        if let Some(task_score_total) = outputs.get_task_score_total() {
+            writeln!(out, "{}",
+                     total_row(TotalRow::WorkerScoreTotal, outputs.get_worker_score_total()))?;
+            writeln!(out, "{}", total_row(TotalRow::TaskScoreTotal, task_score_total))?;
+        }
+        if let Some(optimality) = outputs.get_optimality() {
+            writeln!(out, "{}", dialect.format_row(&[TotalRow::Solution.label().to_string(),
+                                                     format_optimality(&optimality)]))?;
+            writeln!(out, "{}", total_row(TotalRow::LowerBound, optimality.lower_bound))?;
+            writeln!(out, "{}", total_row(TotalRow::OptimalityGap, optimality.gap))?;
+        }
+
+        match self.layout {
+            OutputLayout::ByTask => self.write_task_columns(outputs, out)?,
+            OutputLayout::ByWorker => self.write_worker_rows(outputs, out)?
+        }
+
+        // list pairs of workers and tasks that would rather be assigned to each other
+        if let Some(blocking_pairs) = outputs.get_blocking_pairs() {
+            writeln!(out)?;
+            writeln!(out, "{}", BLOCKING_PAIRS_LABEL)?;
+            for (worker, task) in blocking_pairs {
+                writeln!(out, "{}", dialect.format_row(&[worker, task]))?;
+            }
+        }
 
+        // list the workers moved from their tasks in the previous roster, and why
+        if let Some(moves) = outputs.get_moves() {
+            if !moves.is_empty() {
+                writeln!(out)?;
+                writeln!(out, "{}", MOVED_WORKERS_LABEL)?;
+                for change in moves {
+                    let to = change.to.map(|task| task.to_string()).unwrap_or_default();
+                    writeln!(out, "{}", dialect.format_row(&[change.worker.to_string(),
+                                                             change.from.to_string(), to,
+                                                             change.reason.to_string()]))?;
+                }
+            }
+        }
+
+        // list the workers who could stand in at each task, cheapest first
+        if let Some(backups) = outputs.get_backups() {
+            writeln!(out)?;
+            writeln!(out, "{}", BACKUP_WORKERS_LABEL)?;
+            for backup in backups {
+                let current_task = backup.current_task.map(|task| task.to_string())
+                    .unwrap_or_default();
+                writeln!(out, "{}", dialect.format_row(&[backup.task.to_string(),
+                                                         backup.rank.to_string(),
+                                                         backup.worker.to_string(), current_task,
+                                                         dialect.format_number(
+                                                             backup.cost_increase)]))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a column of assigned workers under each task's name, followed by the sections listing
+    /// unassigned workers and unused optional workers
+    fn write_task_columns(&self, outputs: &Network, out: &mut Vec<u8>) -> std::io::Result<()> {
         // record task names
-        writeln!(file, "{}",
-                 self.task_names.iter()
-                     .map(|tn| String::clone(tn))
-                     .collect::<Vec<String>>()
-                     .join(","))?;
+        writeln!(out, "{}", self.dialect.format_row(&self.task_names))?;
 
         // create vector of strings that shows worker assignments for each task
         let assignments = self.get_assignments(outputs);
 
         // write each line of workers assigned
         for assignment in assignments {
-            writeln!(file, "{}", assignment)?;
+            writeln!(out, "{}", assignment)?;
+        }
+
+        // list any workers left without a task
+        let unassigned = outputs.get_unassigned_workers();
+        if !unassigned.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "{}", UNASSIGNED_WORKERS_LABEL)?;
+            for worker in unassigned {
+                writeln!(out, "{}", self.dialect.format_row(&[worker]))?;
+            }
+        }
+
+        // list any optional workers who were not needed
+        let unused = outputs.get_unused_optional_workers();
+        if !unused.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "{}", UNUSED_OPTIONAL_WORKERS_LABEL)?;
+            for worker in unused {
+                writeln!(out, "{}", self.dialect.format_row(&[worker]))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a header row and then one row per worker, giving their task, its cost (and the cost
+    /// as given, if the costs were rescaled), their best available cost and the regret
+    fn write_worker_rows(&self, outputs: &Network, out: &mut Vec<u8>) -> std::io::Result<()> {
+        let dialect = &self.dialect;
+        let with_raw_costs = has_raw_costs(outputs);
+        writeln!(out, "{}", dialect.format_row(&get_worker_layout_headers(outputs)))?;
+        let to_string = |value: Option<f32>| value.map(|v| dialect.format_number(v))
+            .unwrap_or_default();
+        for row in get_worker_rows(outputs) {
+            let (task, cost) = match &row.assignment {
+                Some((task, cost)) => (task.to_string(), dialect.format_number(*cost)),
+                None => (String::new(), String::new())
+            };
+            let mut cells = vec![row.worker.to_string()];
+            cells.extend(row.attributes.iter().cloned());
+            cells.extend([task, cost]);
+            if with_raw_costs {
+                cells.push(to_string(row.raw_cost));
+            }
+            cells.extend([to_string(row.best_cost), to_string(row.get_regret())]);
+            writeln!(out, "{}", dialect.format_row(&cells))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the problem held in a Network into a buffer, in the CSV input format read by
+    /// `CsvReader`
+    fn write_input(&self, problem: &Network, out: &mut Vec<u8>) -> std::io::Result<()> {
+        let dialect = &self.dialect;
+        let tasks = problem.get_tasks();
+        let workers = problem.get_workers();
+        let attribute_names = problem.get_worker_attribute_names();
+        // every row but the task names and the workers' rows leaves the attribute columns blank
+        let write_labelled_row = |out: &mut Vec<u8>, label: &str, attributes: Vec<String>,
+                                  values: Vec<String>| {
+            let mut row = vec![label.to_string()];
+            row.extend(attributes);
+            row.extend(values);
+            writeln!(out, "{}", dialect.format_row(&row))
+        };
+        let write_row = |out: &mut Vec<u8>, label: &str, values: Vec<String>| {
+            write_labelled_row(out, label, vec![String::new(); attribute_names.len()], values)
+        };
+        let write_worker_row = |out: &mut Vec<u8>, worker: &Worker, values: Vec<String>| {
+            let attributes = (0..attribute_names.len())
+                .map(|attribute_id| worker.get_attribute(attribute_id).to_string())
+                .collect();
+            write_labelled_row(out, worker.get_name(), attributes, values)
+        };
+        let blank_row = || vec![String::new(); tasks.len()];
+
+        write_labelled_row(out, "Tasks",
+                           attribute_names.iter().map(|name| format_attribute_header(name))
+                               .collect(),
+                           tasks.iter().map(|task| task.get_name().to_string()).collect())?;
+        write_row(out, "Minimum",
+                  tasks.iter().map(|task| task.get_min_workers().to_string()).collect())?;
+        write_row(out, "Maximum",
+                  tasks.iter().map(|task| task.get_max_workers().to_string()).collect())?;
+        if tasks.iter().any(|task| task.has_staffing_costs()) {
+            write_row(out, STAFFING_COSTS_LABEL, tasks.iter()
+                .map(|task| task.get_staffing_costs().iter()
+                    .map(|cost| dialect.format_number(*cost))
+                    .collect::<Vec<String>>()
+                    .join(" "))
+                .collect())?;
+        }
+        for (label, cells) in get_task_detail_rows(&tasks, dialect) {
+            write_row(out, label, cells)?;
+        }
+
+        // fill in each task's column from a worker's (task index, value) pairs
+        let task_values = |values: &[(usize, f32)]| {
+            let mut row = blank_row();
+            for (task_id, value) in values {
+                row[*task_id] = dialect.format_number(*value);
+            }
+            row
+        };
+        // with criteria, the workers' rows hold their values for the first one, and each of the
+        // others follows in a block of its own
+        let criteria = problem.get_criteria();
+        let criterion_row = |out: &mut Vec<u8>, criterion: &Criterion| writeln!(
+            out, "{}", dialect.format_row(&[CRITERION_LABEL.to_string(),
+                                            criterion.get_name().to_string(),
+                                            dialect.format_number(criterion.get_weight())]));
+        let affinities = |worker: &Worker| if criteria.is_empty() {
+            task_values(worker.get_raw_affinities())
+        } else {
+            task_values(worker.get_criterion_values(0))
+        };
+        if let Some(criterion) = criteria.first() {
+            criterion_row(out, criterion)?;
+        }
+        for worker in workers.iter().filter(|worker| !worker.is_optional()) {
+            write_worker_row(out, worker, affinities(worker))?;
+        }
+        if workers.iter().any(|worker| worker.is_optional()) {
+            write_row(out, OPTIONAL_WORKERS_LABEL, blank_row())?;
+            for worker in workers.iter().filter(|worker| worker.is_optional()) {
+                write_worker_row(out, worker, affinities(worker))?;
+            }
+        }
+        for (criterion_id, criterion) in criteria.iter().enumerate().skip(1) {
+            criterion_row(out, criterion)?;
+            for worker in &workers {
+                write_row(out, worker.get_name(),
+                          task_values(worker.get_criterion_values(criterion_id)))?;
+            }
+        }
+        if workers.iter().any(|worker| worker.has_task_scores()) {
+            write_row(out, TASK_PREFERENCES_LABEL, blank_row())?;
+            for worker in workers.iter().filter(|worker| worker.has_task_scores()) {
+                write_row(out, worker.get_name(), task_values(worker.get_task_scores()))?;
+            }
+        }
+        let conflicts = problem.get_conflicts();
+        if !conflicts.is_empty() {
+            writeln!(out, "{}", CONFLICTS_LABEL)?;
+            for (first, second) in conflicts {
+                writeln!(out, "{}", dialect.format_row(&[first.to_string(), second.to_string()]))?;
+            }
         }
 
         Ok(())
     }
 
-    /// Create a vector of comma-delimited strings from the worker-task assignments in a network
+    /// Create a vector of delimited strings from the worker-task assignments in a network
     fn get_assignments(&self, outputs: &Network) -> Vec<String> {
         let worker_assignments = outputs.get_worker_assignments();
         let max_size = worker_assignments.values()
             .map(Vec::len)
-            .max().unwrap();
+            .max().unwrap_or(0);
         let mut assignments: Vec<Vec<String>> = vec![vec![]; max_size];
         for task in &self.task_names {
-            for (row, worker) in worker_assignments
-                .get(task).unwrap()
-                .iter().enumerate() {
-                assignments[row].push(String::clone(&worker));
-            }
-            if worker_assignments.get(task).unwrap().len() < max_size {
-                for empty_assignment in assignments.iter_mut()
-                    .take(max_size)
-                    .skip(worker_assignments.get(task).unwrap().len()) {
-                    empty_assignment.push("".to_string());
-                }
+            let task_workers = worker_assignments.get(task).map(Vec::as_slice).unwrap_or(&[]);
+            for (row, worker) in task_workers.iter().enumerate() {
+                assignments[row].push(worker.to_string());
+            }
+            for empty_assignment in assignments.iter_mut().skip(task_workers.len()) {
+                empty_assignment.push("".to_string());
             }
         }
 
         assignments.iter()
-            .map(|v| v.join(","))
+            .map(|row| self.dialect.format_row(row))
             .collect()
     }
 }
 
+/// Column headers for the by-worker layout
+pub(super) const WORKER_LAYOUT_HEADERS: [&str; 5] =
+    ["Worker", "Task", "Cost", "Best Cost", "Regret"];
+
+/// Column header for the cost as given, in the by-worker layout of a network whose costs were
+/// rescaled
+const RAW_COST_HEADER: &str = "Raw Cost";
+
+/// Check whether the network rescaled its workers' costs, so that the costs as given are written
+/// alongside the costs the solver used
+pub(super) fn has_raw_costs(outputs: &Network) -> bool {
+    outputs.get_cost_normalization() != CostNormalization::None
+}
+
+/// Get the column headers for the by-worker layout, with a column for each of the network's worker
+/// attributes after the worker's name, and a column for the cost as given after the cost if the
+/// costs were rescaled
+pub(super) fn get_worker_layout_headers(outputs: &Network) -> Vec<String> {
+    let mut headers = vec![WORKER_LAYOUT_HEADERS[0].to_string()];
+    headers.extend(outputs.get_worker_attribute_names().iter().map(|name| name.to_string()));
+    headers.extend(WORKER_LAYOUT_HEADERS[1..3].iter().map(|header| header.to_string()));
+    if has_raw_costs(outputs) {
+        headers.push(RAW_COST_HEADER.to_string());
+    }
+    headers.extend(WORKER_LAYOUT_HEADERS[3..].iter().map(|header| header.to_string()));
+    headers
+}
+
+/// A row of the by-worker layout: a worker, their values for the network's worker attributes,
+/// their assigned task and its cost if they have one (along with the cost as given), and the
+/// lowest cost of any task they could perform
+pub(super) struct WorkerRow {
+    pub worker: Rc<String>,
+    pub attributes: Vec<String>,
+    pub assignment: Option<(Rc<String>, f32)>,
+    pub raw_cost: Option<f32>,
+    pub best_cost: Option<f32>,
+}
+
+impl WorkerRow {
+    /// Get how much more the worker's assignment costs than their best available task
+    pub fn get_regret(&self) -> Option<f32> {
+        match (&self.assignment, self.best_cost) {
+            (Some((_, cost)), Some(best_cost)) => Some(cost - best_cost),
+            _ => None
+        }
+    }
+}
+
+/// Collect a row for every worker in the network, in the order they were added
+pub(super) fn get_worker_rows(outputs: &Network) -> Vec<WorkerRow> {
+    let tasks = outputs.get_tasks();
+    let num_attributes = outputs.get_worker_attribute_names().len();
+    outputs.get_workers().iter()
+        .zip(outputs.get_assignments())
+        .map(|(worker, assignment)| WorkerRow {
+            worker: Rc::clone(worker.get_name()),
+            attributes: (0..num_attributes)
+                .map(|attribute_id| worker.get_attribute(attribute_id).to_string())
+                .collect(),
+            assignment: assignment.map(|task_id| (Rc::clone(tasks[task_id].get_name()),
+                                                  worker.get_affinity(task_id).unwrap_or_default())),
+            raw_cost: assignment.and_then(|task_id| worker.get_raw_affinity(task_id)),
+            best_cost: worker.get_best_affinity(),
+        })
+        .collect()
+}
+
+/// Write a report of the statistics for a solved network to a CSV file, creating or replacing it.
+/// The report starts with the overall figures, one labelled row each, and then has two tables: one
+/// row per task giving its number of assigned workers against its minimum and maximum along with
+/// the mean, lowest and highest cost of its assignments, and a histogram of the assignments' costs
+/// with one row per distinct cost. The solve time and number of augmenting paths are only reported
+/// when the network was solved as a min cost flow.
+pub(crate) fn write_statistics_file(statistics: &Statistics, dialect: &CsvDialect,
+                                    filename: String) -> std::io::Result<()> {
+    let mut out = Vec::new();
+    let to_string = |value: Option<f32>| value.map(|v| dialect.format_number(v))
+        .unwrap_or_default();
+
+    let labelled_row = |label: &str, value: String| {
+        dialect.format_row(&[label.to_string(), value])
+    };
+
+    writeln!(out, "{}", labelled_row("Workers assigned:", statistics.num_assigned.to_string()))?;
+    writeln!(out, "{}", labelled_row("Workers:", statistics.num_workers.to_string()))?;
+    writeln!(out, "{}", labelled_row("Workers given their best option:",
+                                     statistics.num_best_option.to_string()))?;
+    if let Some(solve_time) = statistics.solve_time {
+        writeln!(out, "{}", labelled_row("Solve time (s):",
+                                         dialect.format_number(solve_time.as_secs_f64())))?;
+    }
+    if let Some(num_augmentations) = statistics.num_augmentations {
+        writeln!(out, "{}", labelled_row("Augmenting paths:", num_augmentations.to_string()))?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Tasks:")?;
+    writeln!(out, "{}", dialect.format_row(&["Task", "Assigned", "Minimum", "Maximum", "Mean Cost",
+                                            "Lowest Cost", "Highest Cost"]))?;
+    for task in &statistics.tasks {
+        writeln!(out, "{}", dialect.format_row(&[
+            task.name.to_string(), task.num_assigned.to_string(), task.min_workers.to_string(),
+            task.max_workers.to_string(), to_string(task.mean_cost), to_string(task.min_cost),
+            to_string(task.max_cost)]))?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Cost histogram:")?;
+    writeln!(out, "{}", dialect.format_row(&["Cost", "Assignments"]))?;
+    for (cost, count) in &statistics.cost_histogram {
+        writeln!(out, "{}", dialect.format_row(&[dialect.format_number(cost),
+                                                 count.to_string()]))?;
+    }
+
+    dialect.save(&out, filename)
+}
+
+/// (worker name, task name) pairs, one for each assigned worker
+pub(crate) type NamedAssignments = Vec<(Rc<String>, Rc<String>)>;
+
+/// Read the (worker name, task name) pairs of a previous roster from an output file written by
+/// `CsvWriter` in either layout, in the given dialect. The rows of totals at the top are skipped,
+/// recognized by their labels like the header rows of an input file, and the assignments end at
+/// the first blank row, so the sections after it (e.g. the unassigned workers) are ignored.
+pub(crate) fn read_previous_assignments(filename: &str, dialect: &CsvDialect)
+    -> Result<NamedAssignments, ReadError> {
+    let text = dialect.decode(&std::fs::read(filename)?)?;
+    let delimiter = dialect.get_input_delimiter(&text);
+    let mut lines = numbered_lines(&text);
+    let rows = std::iter::from_fn(|| read_record(&mut lines, delimiter))
+        .collect::<Result<Vec<Vec<String>>, ReadError>>()?;
+    let mut rows = rows.iter()
+        .skip_while(|row| get_total_row(row).is_some());
+    let Some(header) = rows.next() else {
+        // the header row was expected after the last line of the file
+        let location = SourceLocation::at(Some(text.lines().count() + 1), Some(1));
+        return Err(ParseError::malformed(location,
+                                         "No assignments found in previous roster!".to_string())
+            .into());
+    };
+    let assignment_rows = rows.take_while(|row| row.iter().any(|cell| !cell.trim().is_empty()));
+
+    let name = |cell: &String| Rc::new(cell.trim().to_string());
+    let task_column = header.iter().position(|cell| cell.trim() == WORKER_LAYOUT_HEADERS[1]);
+    let mut assignments = Vec::new();
+    match task_column {
+        // one row per worker, with their task in the "Task" column
+        Some(column) if get_label(header) == Some(WORKER_LAYOUT_HEADERS[0]) => {
+            for row in assignment_rows {
+                if let (Some(worker), Some(task)) = (row.first(), row.get(column)) {
+                    if !task.trim().is_empty() {
+                        assignments.push((name(worker), name(task)));
+                    }
+                }
+            }
+        },
+        // one column of workers under each task's name
+        _ => for row in assignment_rows {
+            for (worker, task) in zip(row, header) {
+                if !worker.trim().is_empty() {
+                    assignments.push((name(worker), name(task)));
+                }
+            }
+        }
+    }
+    Ok(assignments)
+}
+
 impl Writer for CsvWriter {
-    /// Create new file or overwrite existing file, and pass handle to the write method
+    /// Write the results to a buffer, then encode it into a new file or over an existing one
     fn write_file(&self, results: &Network, filename: String) -> std::io::Result<()> {
-        let outfile = OpenOptions::new().write(true).create(true).open(filename)?;
-        self.write(results, outfile)?;
+        let mut out = Vec::new();
+        self.write(results, &mut out)?;
+        self.dialect.save(&out, filename)
+    }
 
-        Ok(())
+    /// Write the problem to a buffer, then encode it into a new file or over an existing one
+    fn write_input_file(&self, problem: &Network, filename: String) -> std::io::Result<()> {
+        let mut out = Vec::new();
+        self.write_input(problem, &mut out)?;
+        self.dialect.save(&out, filename)
     }
 }
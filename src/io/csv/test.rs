@@ -1,32 +1,108 @@
 use crate::io::csv::*;
-use crate::ui::CurrentStatus;
+use crate::io::{DecimalSeparator, Delimiter, RankCosts, RankScheme, TextEncoding, UnrankedTasks};
+use crate::status::CurrentStatus;
+use crate::io::TestOutputDir;
 
 #[test]
 fn test_read() {
-    let mut file_reader = CsvReader::new();
+    let mut file_reader = CsvReader::new(CsvDialect::default());
     let network = Network::new();
     file_reader.read_file("src/io/csv/test-data/testInput.csv".to_string(),
                           &network).unwrap();
     network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
-    let total_cost = -network.get_cost_of_arcs_from_nodes(&file_reader.tasks.borrow());
+    let total_cost = network.get_total_cost();
     assert!((total_cost - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
 }
 
+#[test]
+fn test_read_labelled_headers() {
+    // header rows in any order, with an unknown one and a worker whose name is also a label
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    let issues: Vec<String> = file_reader.validate_file(
+        "src/io/csv/test-data/inputLabelledHeaders.csv".to_string(), &network).unwrap()
+        .iter().map(ValidationIssue::to_string).collect();
+    assert_eq!(issues, [
+        r#"Warning in row 3, column A: Ignoring header row with unknown label "Notes""#,
+    ]);
+    assert!(network.has_worker(&Rc::new("Max".to_string())));
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let total_cost = network.get_total_cost();
+    assert!((total_cost - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
+
+    // files without header labels are read by the rows' positions
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputUnlabelledHeaders.csv".to_string(),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let total_cost = network.get_total_cost();
+    assert!((total_cost - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
+}
+
+#[test]
+fn test_read_lower_case_labels() {
+    // section labels are recognized whatever their case, like the header rows' labels
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputLowerCaseLabels.csv".to_string(),
+                          &network).unwrap();
+    let workers = network.get_workers();
+    assert_eq!(workers.len(), 10);
+    assert!(!workers[7].is_optional());
+    assert!(workers[8].is_optional() && workers[9].is_optional());
+    assert!(workers[0].has_task_scores() && workers[1].has_task_scores());
+    assert_eq!(network.get_conflicts(),
+               [(Rc::new("Ellen".to_string()), Rc::new("Gina".to_string()))]);
+}
+
+#[test]
+fn test_read_european_dialect() {
+    let output = TestOutputDir::new("test_read_european_dialect");
+    let dialect = CsvDialect {
+        delimiter: Delimiter::Auto,
+        decimal_separator: DecimalSeparator::Comma,
+        encoding: TextEncoding::WINDOWS_1252,
+    };
+    let mut file_reader = CsvReader::new(dialect);
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputEuropean.csv".to_string(),
+                          &network).unwrap();
+    assert!(network.has_worker(&Rc::new("Renée".to_string())));
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let total_cost = network.get_total_cost();
+    assert!((total_cost - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
+
+    // the problem reads back the same after writing it in the same dialect
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByTask,
+                                     dialect);
+    file_writer.write_input_file(&network,
+                                 output.path("testEuropean.csv")).unwrap();
+    let written = std::fs::read(output.path("testEuropean.csv")).unwrap();
+    assert!(written.starts_with(b"Tasks;Track 1;"));
+    let mut round_trip_reader = CsvReader::new(dialect);
+    let round_trip = Network::new();
+    round_trip_reader.read_file(output.path("testEuropean.csv"),
+                                &round_trip).unwrap();
+    round_trip.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(round_trip.get_total_cost(), total_cost);
+}
+
 #[test]
 fn test_read_empty_input() {
-    let mut file_reader = CsvReader::new();
+    let mut file_reader = CsvReader::new(CsvDialect::default());
     let network = Network::new();
     let result = file_reader.read_file("src/io/csv/test-data/inputEmpty.csv".to_string(),
                                        &network);
     assert!(result.is_err());
     let net_err = result.err().unwrap();
-    assert_eq!(net_err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(matches!(net_err, ReadError::Parse(_)));
     assert_eq!(net_err.to_string(), "Empty input file!");
 }
 
 #[test]
 fn test_read_bad_task_min() {
-    let mut file_reader = CsvReader::new();
+    let mut file_reader = CsvReader::new(CsvDialect::default());
     let network = Network::new();
     let result = file_reader.read_file("src/io/csv/test-data/inputBadMin.csv".to_string(),
                                        &network);
@@ -37,7 +113,7 @@ fn test_read_bad_task_min() {
 
 #[test]
 fn test_read_bad_task_max() {
-    let mut file_reader = CsvReader::new();
+    let mut file_reader = CsvReader::new(CsvDialect::default());
     let network = Network::new();
     let result = file_reader.read_file("src/io/csv/test-data/inputBadMax.csv".to_string(),
                                        &network);
@@ -48,7 +124,7 @@ fn test_read_bad_task_max() {
 
 #[test]
 fn test_read_max_lt_min() {
-    let mut file_reader = CsvReader::new();
+    let mut file_reader = CsvReader::new(CsvDialect::default());
     let network = Network::new();
     let result = file_reader.read_file("src/io/csv/test-data/inputMaxLtMin.csv".to_string(),
                                        &network);
@@ -59,7 +135,7 @@ fn test_read_max_lt_min() {
 
 #[test]
 fn test_read_bad_worker_affinity() {
-    let mut file_reader = CsvReader::new();
+    let mut file_reader = CsvReader::new(CsvDialect::default());
     let network = Network::new();
     let result = file_reader.read_file("src/io/csv/test-data/inputBadAffinity.csv".to_string(),
                                        &network);
@@ -68,9 +144,81 @@ fn test_read_bad_worker_affinity() {
                r#"Expected numeric value for worker affinity, found "c"; error: invalid float literal"#);
 }
 
+#[test]
+fn test_parse_error_location() {
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    let result = file_reader.read_file("src/io/csv/test-data/inputBadAffinity.csv".to_string(),
+                                       &network);
+    match result {
+        Err(ReadError::Parse(err)) => match *err {
+            ParseError::InvalidValue { location, text, expected, .. } => {
+                assert_eq!(location, SourceLocation::at(Some(7), Some(4)));
+                assert_eq!(text, "c");
+                assert_eq!(expected, "numeric worker affinity");
+            },
+            other => panic!("Expected an invalid value, found {:?}", other)
+        },
+        other => panic!("Expected a parse error, found {:?}", other)
+    }
+}
+
+#[test]
+fn test_unterminated_quote_location() {
+    // the quoted field opens in the third field of line 2, and runs to the end of the file
+    let text = "a,b,c\nd,e,\"f\ng,h\n";
+    let mut lines = numbered_lines(text);
+    assert!(read_record(&mut lines, ',').unwrap().is_ok());
+    match read_record(&mut lines, ',') {
+        Some(Err(ReadError::Parse(err))) =>
+            assert_eq!(*err.get_location(), SourceLocation::at(Some(2), Some(3))),
+        other => panic!("Expected a parse error, found {:?}", other)
+    }
+}
+
+#[test]
+fn test_previous_roster_error_location() {
+    let output = TestOutputDir::new("test_previous_roster_error_location");
+    let filename = output.path("noAssignments.csv");
+    std::fs::write(&filename, "Total score:,1\nsolution:,heuristic\n").unwrap();
+    match read_previous_assignments(&filename, &CsvDialect::default()) {
+        Err(ReadError::Parse(err)) =>
+            assert_eq!(*err.get_location(), SourceLocation::at(Some(3), Some(1))),
+        other => panic!("Expected a parse error, found {:?}", other)
+    }
+}
+
+#[test]
+fn test_validate_many_problems() {
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    let issues: Vec<String> = file_reader.validate_file(
+        "src/io/csv/test-data/inputManyProblems.csv".to_string(), &network).unwrap()
+        .iter().map(ValidationIssue::to_string).collect();
+    assert_eq!(issues, [
+        "Error in row 3, column B: Maximum cannot be less than minimum!",
+        r#"Error in row 2, column C: Expected integer minimum, found "x"; error: invalid digit found in string"#,
+        "Error in row 1, column D: Duplicate task Pack!",
+        r#"Error in row 4, column C: Expected finite value for worker affinity, found "NaN"!"#,
+        r#"Error in row 5, column B: Expected numeric value for worker affinity, found "abc"; error: invalid float literal"#,
+        r#"Warning in row 5, column F: Ignoring "5" in task affinities beyond the last task's column"#,
+        "Error in row 6, column A: Duplicate worker Alice!",
+        "Error in row 11, column A: Task preferences provided for unknown worker Erin!",
+        "Error: Worker Carol has no eligible tasks!",
+        "Warning: Optional worker Dave has no eligible tasks and will never be assigned!",
+        "Error: No worker is eligible for task Pack, which needs at least 1!",
+        "Warning: No worker is eligible for task Idle!",
+    ]);
+
+    // reading stops with the first error
+    let result = CsvReader::new(CsvDialect::default()).read_file(
+        "src/io/csv/test-data/inputManyProblems.csv".to_string(), &Network::new());
+    assert_eq!(result.err().unwrap().to_string(), "Maximum cannot be less than minimum!");
+}
+
 #[test]
 fn test_read_wrong_number_of_task_data() {
-    let mut file_reader = CsvReader::new();
+    let mut file_reader = CsvReader::new(CsvDialect::default());
     let network = Network::new();
     let result = file_reader.read_file("src/io/csv/test-data/inputExtraData.csv".to_string(),
                                        &network);
@@ -82,7 +230,7 @@ fn test_read_wrong_number_of_task_data() {
 
 #[test]
 fn test_read_wrong_number_of_affinities() {
-    let mut file_reader = CsvReader::new();
+    let mut file_reader = CsvReader::new(CsvDialect::default());
     let network = Network::new();
     let result = file_reader.read_file("src/io/csv/test-data/inputExtraAffinity.csv".to_string(),
                                        &network);
@@ -93,11 +241,414 @@ fn test_read_wrong_number_of_affinities() {
 
 #[test]
 fn test_write() {
-    let mut file_reader = CsvReader::new();
+    let output = TestOutputDir::new("test_write");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/testInput.csv".to_string(),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByTask,
+                                     CsvDialect::default());
+    file_writer.write_file(&network, output.path("testOutput.csv")).unwrap();
+}
+
+#[test]
+fn test_write_by_worker() {
+    let output = TestOutputDir::new("test_write_by_worker");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
     let network = Network::new();
     file_reader.read_file("src/io/csv/test-data/testInput.csv".to_string(),
                           &network).unwrap();
     network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
-    let file_writer = CsvWriter::new(file_reader.clone_task_names());
-    file_writer.write_file(&network, "src/io/csv/test-output/testOutput.csv".to_string()).unwrap();
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByWorker,
+                                     CsvDialect::default());
+    file_writer.write_file(&network,
+                           output.path("testByWorker.csv")).unwrap();
+
+    let text = std::fs::read_to_string(output.path("testByWorker.csv")).unwrap();
+    let mut lines = numbered_lines(&text);
+    let rows: Vec<Vec<String>> = std::iter::from_fn(|| read_record(&mut lines, ','))
+        .collect::<Result<_, ReadError>>()
+        .unwrap();
+    assert_eq!(rows[1], WORKER_LAYOUT_HEADERS);
+    assert_eq!(rows.len(), 12);
+    assert_eq!(rows[2][0], "Alice");
+    // each worker's best cost adds up to 9.5, so the assignments cost 3 more than that in total
+    let regret_total: f32 = rows[2..].iter()
+        .map(|row| f32::from_str(&row[4]).unwrap())
+        .sum();
+    assert!((regret_total - 3.0).abs() < 1e-6);
+    for row in &rows[2..] {
+        let cost = f32::from_str(&row[2]).unwrap();
+        let best_cost = f32::from_str(&row[3]).unwrap();
+        assert_eq!(f32::from_str(&row[4]).unwrap(), cost - best_cost);
+    }
+}
+
+#[test]
+fn test_worker_attributes() {
+    let output = TestOutputDir::new("test_worker_attributes");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputWorkerAttributes.csv".to_string(),
+                          &network).unwrap();
+    assert_eq!(network.get_worker_attribute_names().len(), 3);
+    let workers = network.get_workers();
+    assert_eq!(workers[0].get_attribute(1), "Sales");
+    // Kim is marked optional by the "Optional" column, and too costly to be needed
+    assert!(workers[10].is_optional());
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let total_cost = network.get_total_cost();
+    assert!((total_cost - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
+
+    // the by-worker layout echoes the attributes after each worker's name
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByWorker,
+                                     CsvDialect::default());
+    file_writer.write_file(&network,
+                           output.path("testAttributes.csv")).unwrap();
+    let text = std::fs::read_to_string(output.path("testAttributes.csv")).unwrap();
+    let mut lines = numbered_lines(&text);
+    let rows: Vec<Vec<String>> = std::iter::from_fn(|| read_record(&mut lines, ','))
+        .collect::<Result<_, ReadError>>()
+        .unwrap();
+    assert_eq!(rows[1][..5], ["Worker", "Employee ID", "Department", "Optional", "Task"]);
+    assert_eq!(rows[2][..4], ["Alice", "E001", "Sales", "no"]);
+    assert_eq!(rows[12][..5], ["Kim", "E011", "Sales", "yes", ""]);
+
+    // and the input reads back with the same attributes
+    file_writer.write_input_file(
+        &network, output.path("testAttributesInput.csv")).unwrap();
+    let round_trip = Network::new();
+    CsvReader::new(CsvDialect::default()).read_file(
+        output.path("testAttributesInput.csv"), &round_trip).unwrap();
+    assert_eq!(round_trip.get_worker_attribute_names(), network.get_worker_attribute_names());
+    assert_eq!(round_trip.get_workers()[10].get_attribute(0), "E011");
+    assert!(round_trip.get_workers()[10].is_optional());
+}
+
+#[test]
+fn test_worker_groups() {
+    let output = TestOutputDir::new("test_worker_groups");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputWorkerGroups.csv".to_string(),
+                          &network).unwrap();
+    let groups = network.get_worker_groups();
+    assert_eq!(groups.len(), 2);
+    assert_eq!((groups[0].0.as_str(), groups[0].1.len()), ("Pair 1", 2));
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+
+    // Alice and Bob would rather be apart, but are assigned to the same task
+    let assignments = network.get_assignments();
+    assert!(assignments[0].is_some());
+    assert_eq!(assignments[0], assignments[1]);
+    assert_eq!(assignments[4], assignments[6]);
+
+    // the groups read back from the input written out
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByWorker,
+                                     CsvDialect::default());
+    file_writer.write_input_file(
+        &network, output.path("testGroupsInput.csv")).unwrap();
+    let round_trip = Network::new();
+    CsvReader::new(CsvDialect::default()).read_file(
+        output.path("testGroupsInput.csv"), &round_trip).unwrap();
+    assert_eq!(round_trip.get_worker_groups(), groups);
+}
+
+#[test]
+fn test_conflicts() {
+    let output = TestOutputDir::new("test_conflicts");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputConflicts.csv".to_string(),
+                          &network).unwrap();
+    // a row of three workers puts each pair of them in conflict
+    let conflicts = network.get_conflicts();
+    assert_eq!(conflicts.len(), 4);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+
+    // Ellen and Gina, and Carol and Harry, shared tasks without the conflicts, which cost 3 more
+    // to keep apart
+    let assignments = network.get_assignments();
+    assert_ne!(assignments[4], assignments[6]);
+    assert_ne!(assignments[2], assignments[7]);
+    assert_eq!(network.get_total_cost(), 15.5);
+    let optimality = network.get_optimality().unwrap();
+    assert!(optimality.proven_optimal);
+    assert_eq!(optimality.gap, 0.0);
+
+    // the conflicts read back from the input written out
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByTask,
+                                     CsvDialect::default());
+    file_writer.write_input_file(
+        &network, output.path("testConflictsInput.csv")).unwrap();
+    let round_trip = Network::new();
+    CsvReader::new(CsvDialect::default()).read_file(
+        output.path("testConflictsInput.csv"), &round_trip).unwrap();
+    assert_eq!(round_trip.get_conflicts(), conflicts);
+
+    // conflicts can only name workers listed above them, and need two of them
+    let issues: Vec<String> = CsvReader::new(CsvDialect::default())
+        .validate_file("src/io/csv/test-data/inputBadConflicts.csv".to_string(), &Network::new())
+        .unwrap()
+        .iter().map(ValidationIssue::to_string).collect();
+    assert_eq!(issues, [
+        "Error in row 7, column B: Conflict listed for unknown worker Zoe!",
+        "Warning in row 7: Ignoring conflict that lists only worker Alice",
+        "Warning in row 8: Ignoring conflict that lists only worker Bob",
+    ]);
+}
+
+#[test]
+fn test_read_ranked() {
+    let mut file_reader = CsvReader::ranked(CsvDialect::default(), RankScheme::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputRanked.csv".to_string(),
+                          &network).unwrap();
+    // Bob only ranked one task, so the rest are unacceptable for him
+    assert_eq!(network.get_workers()[1].get_affinities(), [(0, 1.0)]);
+    assert_eq!(network.get_workers()[2].get_affinities(), [(0, 3.0), (1, 2.0), (2, 1.0)]);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_total_cost(), 4.0);
+
+    let ranking = RankScheme { costs: RankCosts::Exponential, unranked: UnrankedTasks::Cost(10.0) };
+    let network = Network::new();
+    CsvReader::ranked(CsvDialect::default(), ranking).read_file(
+        "src/io/csv/test-data/inputRanked.csv".to_string(), &network).unwrap();
+    assert_eq!(network.get_workers()[1].get_affinities(), [(0, 1.0), (1, 10.0), (2, 10.0)]);
+    assert_eq!(network.get_workers()[2].get_affinities(), [(0, 4.0), (1, 2.0), (2, 1.0)]);
+
+    let issues: Vec<String> = CsvReader::ranked(CsvDialect::default(), RankScheme::default())
+        .validate_file("src/io/csv/test-data/inputBadRanks.csv".to_string(), &Network::new())
+        .unwrap()
+        .iter().map(ValidationIssue::to_string).collect();
+    assert_eq!(issues, [
+        "Error in row 5, column C: Task Ship is ranked more than once by worker Erin!",
+        "Error in row 5, column D: Unknown task Fly ranked by worker Erin!",
+        "Warning: No worker is eligible for task Sort!",
+    ]);
+}
+
+#[test]
+fn test_read_criteria() {
+    let output = TestOutputDir::new("test_read_criteria");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputCriteria.csv".to_string(),
+                          &network).unwrap();
+    let criteria = network.get_criteria();
+    assert_eq!(criteria.len(), 3);
+    assert_eq!(criteria[1].get_weight(), 0.5);
+    // Cat has no commute value for Front, so cannot be assigned to it
+    let workers = network.get_workers();
+    assert_eq!(workers[0].get_affinities(), [(0, 2.0), (1, 7.0)]);
+    assert_eq!(workers[2].get_affinities(), [(1, 2.0)]);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_total_cost(), 6.0);
+
+    // the output breaks the total down by criterion
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByTask,
+                                     CsvDialect::default());
+    file_writer.write_file(&network,
+                           output.path("testCriteria.csv")).unwrap();
+    let text = std::fs::read_to_string(output.path("testCriteria.csv")).unwrap();
+    assert!(text.contains("Criterion total:,Preference,3\nCriterion total:,Commute,3\n\
+                           Criterion total:,Training,0\n"));
+
+    // and the input reads back with the same criteria
+    file_writer.write_input_file(
+        &network, output.path("testCriteriaInput.csv")).unwrap();
+    let round_trip = Network::new();
+    CsvReader::new(CsvDialect::default()).read_file(
+        output.path("testCriteriaInput.csv"), &round_trip).unwrap();
+    assert_eq!(round_trip.get_criteria(), criteria);
+    assert_eq!(round_trip.get_workers()[2].get_affinities(), [(1, 2.0)]);
+
+    let issues: Vec<String> = CsvReader::new(CsvDialect::default())
+        .validate_file("src/io/csv/test-data/inputBadCriteria.csv".to_string(), &Network::new())
+        .unwrap()
+        .iter().map(ValidationIssue::to_string).collect();
+    assert_eq!(issues, [
+        r#"Error in row 6, column C: Expected numeric weight for criterion Commute, found "half"; error: invalid float literal"#,
+        "Error in row 8, column A: Duplicate values of criterion Commute for worker Ann!",
+        "Error in row 11, column B: Duplicate criterion Commute!",
+        "Error in row 9, column A: Values of criterion Commute provided for unknown worker Dan!",
+    ]);
+}
+
+#[test]
+fn test_read_previous_assignments() {
+    let output = TestOutputDir::new("test_read_previous_assignments");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputOptionalWorkers.csv".to_string(),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let expected: Vec<(Rc<String>, Rc<String>)> = network.get_workers().iter()
+        .zip(network.get_assignments())
+        .filter_map(|(worker, assignment)| assignment.map(|task_id|
+            (Rc::clone(worker.get_name()), Rc::clone(network.get_tasks()[task_id].get_name()))))
+        .collect();
+
+    // either layout of the output reads back as the same assignments, in some order
+    for (layout, filename) in [(OutputLayout::ByTask, "testPreviousByTask.csv"),
+                               (OutputLayout::ByWorker, "testPreviousByWorker.csv")] {
+        let filename = output.path(filename);
+        CsvWriter::new(file_reader.clone_task_names(), layout, CsvDialect::default())
+            .write_file(&network, filename.clone()).unwrap();
+        let mut assignments = read_previous_assignments(&filename, &CsvDialect::default())
+            .unwrap();
+        assignments.sort();
+        let mut sorted = expected.clone();
+        sorted.sort();
+        assert_eq!(assignments, sorted);
+    }
+
+    // only the rows of totals are skipped, so task names ending in a colon are still read
+    let filename = output.path("testPreviousColons.csv");
+    std::fs::write(&filename, "Total score:,3\nSOLUTION:,heuristic\nMorning:,Evening:\nAlice,Bob\n")
+        .unwrap();
+    assert_eq!(read_previous_assignments(&filename, &CsvDialect::default()).unwrap(),
+               [(Rc::new("Alice".to_string()), Rc::new("Morning:".to_string())),
+                (Rc::new("Bob".to_string()), Rc::new("Evening:".to_string()))]);
+
+    // re-planning from its own roster changes nothing
+    network.set_previous_assignments(expected, 1.0);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_moves(), Some(Vec::new()));
+    assert_eq!(network.get_change_penalty_total(), Some(0.0));
+}
+
+#[test]
+fn test_read_task_preferences() {
+    let output = TestOutputDir::new("test_read_task_preferences");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputTaskPreferences.csv".to_string(),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert!((network.get_total_cost() - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
+    network.record_blocking_pairs();
+    assert!(!network.get_blocking_pairs().unwrap().is_empty());
+
+    network.find_stable_matching().unwrap();
+    assert_eq!(network.record_blocking_pairs(), 0);
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByTask,
+                                     CsvDialect::default());
+    file_writer.write_file(&network,
+                           output.path("testStableOutput.csv")).unwrap();
+}
+
+#[test]
+fn test_read_staffing_costs() {
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputStaffingCosts.csv".to_string(),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let assignments = network.get_worker_assignments();
+    assert_eq!(assignments.get(&Rc::new("Track 3".to_string())).unwrap().len(), 1);
+    assert_eq!(network.get_staffing_cost_total(), Some(0.0));
+}
+
+#[test]
+fn test_read_bad_staffing_costs() {
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    let result = file_reader.read_file("src/io/csv/test-data/inputBadStaffingCosts.csv".to_string(),
+                                       &network);
+    assert!(result.is_err());
+    assert_eq!(result.err().unwrap().to_string(),
+               "Staffing costs for task Track 2 must not decrease!");
+}
+
+#[test]
+fn test_read_task_details() {
+    let output = TestOutputDir::new("test_read_task_details");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    let issues: Vec<String> = file_reader.validate_file(
+        "src/io/csv/test-data/inputTaskDetails.csv".to_string(), &network).unwrap()
+        .iter().map(ValidationIssue::to_string).collect();
+    assert_eq!(issues, [
+        r#"Error in row 4, column F: Expected integer priority, found "x"; error: invalid digit found in string"#,
+        r#"Error in row 6, column E: Expected non-negative shortage penalty, found "-1"!"#,
+    ]);
+    let tasks = network.get_tasks();
+    assert_eq!(*tasks[0].get_details(), TaskDetails {
+        priority: Some(3),
+        group: Some(Rc::new("Outdoor".to_string())),
+        shortage_penalty: Some(10.0),
+        required_skills: vec![Rc::new("First Aid".to_string()), Rc::new("Driving".to_string())],
+    });
+    assert_eq!(tasks[3].get_details().priority, Some(2));
+    assert_eq!(tasks[3].get_details().shortage_penalty, None);
+    assert_eq!(*tasks[4].get_details(), TaskDetails {
+        group: Some(Rc::new("Indoor".to_string())),
+        ..TaskDetails::default()
+    });
+
+    // the details are written back out when the problem is converted
+    CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByTask, CsvDialect::default())
+        .write_input_file(&network, output.path("taskDetailsInput.csv")).unwrap();
+    let round_trip = Network::new();
+    CsvReader::new(CsvDialect::default())
+        .read_file(output.path("taskDetailsInput.csv"), &round_trip).unwrap();
+    for (task, read_back) in zip(tasks, round_trip.get_tasks()) {
+        assert_eq!(task.get_details(), read_back.get_details());
+    }
+}
+
+#[test]
+fn test_read_optional_workers() {
+    let output = TestOutputDir::new("test_read_optional_workers");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputOptionalWorkers.csv".to_string(),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert!(network.get_unassigned_workers().is_empty());
+    assert_eq!(network.get_unused_optional_workers(),
+               vec![Rc::new("Kim".to_string()), Rc::new("John".to_string())]);
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByTask,
+                                     CsvDialect::default());
+    file_writer.write_file(&network,
+                           output.path("testOptionalOutput.csv")).unwrap();
+}
+
+#[test]
+fn test_read_quoted_fields() {
+    let output = TestOutputDir::new("test_read_quoted_fields");
+    let mut file_reader = CsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/csv/test-data/inputQuoted.csv".to_string(),
+                          &network).unwrap();
+    assert_eq!(file_reader.clone_task_names(),
+               vec![Rc::new("Pack, Ship".to_string()), Rc::new("Receive".to_string()),
+                    Rc::new("The \"Big\" Count".to_string())]);
+    let worker_names: Vec<Rc<String>> = network.get_workers().iter()
+        .map(|worker| Rc::clone(worker.get_name()))
+        .collect();
+    assert_eq!(worker_names, vec![Rc::new("Smith, John".to_string()), Rc::new("Alice".to_string()),
+                                  Rc::new("Multi\nLine".to_string())]);
+
+    // names written back out must read back the same
+    let file_writer = CsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByTask,
+                                     CsvDialect::default());
+    file_writer.write_input_file(&network,
+                                 output.path("testQuotedInput.csv")).unwrap();
+    let mut round_trip_reader = CsvReader::new(CsvDialect::default());
+    let round_trip = Network::new();
+    round_trip_reader.read_file(output.path("testQuotedInput.csv"),
+                                &round_trip).unwrap();
+    assert_eq!(round_trip_reader.clone_task_names(), file_reader.clone_task_names());
+    assert_eq!(round_trip.get_workers().iter()
+                   .map(|worker| Rc::clone(worker.get_name()))
+                   .collect::<Vec<Rc<String>>>(),
+               worker_names);
+
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_total_cost(), 3.0);
+    file_writer.write_file(&network, output.path("testQuotedOutput.csv"))
+        .unwrap();
 }
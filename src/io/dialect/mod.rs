@@ -0,0 +1,212 @@
+//! The dialect of a CSV file: the character that separates its fields, the character that
+//! separates the whole and fractional parts of its numbers, and the encoding of its text.
+//! Spreadsheet programs set up for many European locales export CSV with semicolons between fields
+//! and decimal commas, and may save it in a legacy encoding such as Windows-1252 or with a byte
+//! order mark (BOM). A `CsvDialect` applies to both reading and writing CSV files, so that results
+//! can be written in the same dialect as their input.
+
+use std::fmt::Display;
+use std::str::FromStr;
+use encoding_rs::Encoding;
+use crate::io::{ParseError, ReadError, SourceLocation};
+#[cfg(test)]
+mod test;
+
+/// The character that separates the fields of a record
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Guess the delimiter from the first few lines of the input, and write commas (or semicolons
+    /// when numbers are written with decimal commas)
+    #[default]
+    Auto,
+    Comma,
+    Semicolon,
+    Tab,
+}
+
+impl FromStr for Delimiter {
+    type Err = String;
+
+    /// Parse a delimiter from its name
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Delimiter::Auto),
+            "comma" => Ok(Delimiter::Comma),
+            "semicolon" => Ok(Delimiter::Semicolon),
+            "tab" => Ok(Delimiter::Tab),
+            _ => Err(format!("Unsupported delimiter {}!", name))
+        }
+    }
+}
+
+/// The character that separates the whole and fractional parts of a number
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DecimalSeparator {
+    #[default]
+    Dot,
+    Comma,
+}
+
+impl FromStr for DecimalSeparator {
+    type Err = String;
+
+    /// Parse a decimal separator from its name
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "dot" => Ok(DecimalSeparator::Dot),
+            "comma" => Ok(DecimalSeparator::Comma),
+            _ => Err(format!("Unsupported decimal separator {}!", name))
+        }
+    }
+}
+
+/// The encoding of a file's text. A byte order mark at the start of an input file overrides the
+/// chosen encoding and is stripped from the text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TextEncoding(&'static Encoding);
+
+impl TextEncoding {
+    pub const UTF_8: TextEncoding = TextEncoding(encoding_rs::UTF_8);
+    // named for the GUI's choice of encodings; the CLI finds it by name
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))]
+    pub const WINDOWS_1252: TextEncoding = TextEncoding(encoding_rs::WINDOWS_1252);
+}
+
+impl Default for TextEncoding {
+    fn default() -> Self {
+        TextEncoding::UTF_8
+    }
+}
+
+impl FromStr for TextEncoding {
+    type Err = String;
+
+    /// Parse an encoding from any of its standard labels, e.g. "utf-8", "windows-1252" or "latin1"
+    fn from_str(label: &str) -> Result<Self, Self::Err> {
+        Encoding::for_label(label.trim().as_bytes())
+            .map(TextEncoding)
+            .ok_or_else(|| format!("Unsupported encoding {}!", label))
+    }
+}
+
+/// How a CSV file separates its fields and decimals, and how its text is encoded
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CsvDialect {
+    pub delimiter: Delimiter,
+    pub decimal_separator: DecimalSeparator,
+    pub encoding: TextEncoding,
+}
+
+/// Delimiters that can be guessed from the input, in order of preference when they are as common
+const DELIMITER_CANDIDATES: [char; 3] = [',', ';', '\t'];
+
+/// Number of non-blank lines at the start of the input to guess the delimiter from. Long CSV files
+/// start with a line holding just a table's name, so the first line alone is not enough.
+const SNIFFED_LINES: usize = 5;
+
+impl CsvDialect {
+    /// Decode the bytes of an input file into text, stripping any byte order mark. Bytes that are
+    /// not valid in the encoding are a parse error rather than being silently replaced.
+    pub fn decode(&self, bytes: &[u8]) -> Result<String, ReadError> {
+        let (text, encoding, had_errors) = self.encoding.0.decode(bytes);
+        if had_errors {
+            return Err(ParseError::malformed(SourceLocation::default(), format!(
+                "Input is not valid {} text! Choose the encoding it was saved in.",
+                encoding.name())).into());
+        }
+        Ok(text.into_owned())
+    }
+
+    /// Encode the text written to a buffer and save it to a file, creating or replacing it
+    pub fn save(&self, buffer: &[u8], filename: String) -> std::io::Result<()> {
+        std::fs::write(filename, self.encode(&String::from_utf8_lossy(buffer)))
+    }
+
+    /// Encode text for an output file. Encodings that cannot be written (such as UTF-16) are
+    /// written as UTF-8 instead, and characters the encoding cannot represent are written as HTML
+    /// character references.
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        self.encoding.0.encode(text).0.into_owned()
+    }
+
+    /// Get the delimiter to read the input with, guessing it from the input's first few non-blank
+    /// lines if it was not chosen
+    pub fn get_input_delimiter(&self, text: &str) -> char {
+        match self.delimiter {
+            Delimiter::Auto => sniff_delimiter(text.lines()
+                .filter(|line| !line.trim().is_empty())
+                .take(SNIFFED_LINES)),
+            _ => self.get_output_delimiter()
+        }
+    }
+
+    /// Get the delimiter to write output with
+    pub fn get_output_delimiter(&self) -> char {
+        match (self.delimiter, self.decimal_separator) {
+            (Delimiter::Comma, _) | (Delimiter::Auto, DecimalSeparator::Dot) => ',',
+            (Delimiter::Semicolon, _) | (Delimiter::Auto, DecimalSeparator::Comma) => ';',
+            (Delimiter::Tab, _) => '\t'
+        }
+    }
+
+    /// Parse a number written with the dialect's decimal separator
+    pub fn parse_number<T: FromStr>(&self, text: &str) -> Result<T, T::Err> {
+        match self.decimal_separator {
+            DecimalSeparator::Dot => T::from_str(text),
+            DecimalSeparator::Comma => T::from_str(&text.replace(',', "."))
+        }
+    }
+
+    /// Write a number with the dialect's decimal separator
+    pub fn format_number<T: Display>(&self, value: T) -> String {
+        match self.decimal_separator {
+            DecimalSeparator::Dot => value.to_string(),
+            DecimalSeparator::Comma => value.to_string().replace('.', ",")
+        }
+    }
+
+    /// Join the cells of a row with the output delimiter, quoting any cell that would otherwise be
+    /// misread
+    pub fn format_row<S: ToString>(&self, cells: &[S]) -> String {
+        let delimiter = self.get_output_delimiter();
+        cells.iter()
+            .map(|cell| quote(&cell.to_string(), delimiter))
+            .collect::<Vec<String>>()
+            .join(&delimiter.to_string())
+    }
+}
+
+/// Guess the delimiter of some lines as the candidate that appears most often outside quoted
+/// fields, defaulting to a comma
+fn sniff_delimiter<'a>(lines: impl Iterator<Item = &'a str>) -> char {
+    let mut in_quotes = false;
+    let mut counts = [0_usize; DELIMITER_CANDIDATES.len()];
+    for c in lines.flat_map(str::chars) {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes {
+            if let Some(index) = DELIMITER_CANDIDATES.iter().position(|&d| d == c) {
+                counts[index] += 1;
+            }
+        }
+    }
+
+    // the first candidate wins ties, so a line without any of them is read with commas
+    let mut best = 0;
+    for (index, count) in counts.iter().enumerate() {
+        if *count > counts[best] {
+            best = index;
+        }
+    }
+    DELIMITER_CANDIDATES[best]
+}
+
+/// Quote a field for writing if it contains anything that would otherwise be misread: the
+/// delimiter, a double quote or a line break
+fn quote(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
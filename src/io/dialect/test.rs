@@ -0,0 +1,53 @@
+use crate::io::dialect::*;
+
+/// A dialect with semicolon delimiters and decimal commas, as exported in many European locales
+const EUROPEAN: CsvDialect = CsvDialect {
+    delimiter: Delimiter::Auto,
+    decimal_separator: DecimalSeparator::Comma,
+    encoding: TextEncoding::WINDOWS_1252,
+};
+
+#[test]
+fn test_sniff_delimiter() {
+    let dialect = CsvDialect::default();
+    assert_eq!(dialect.get_input_delimiter("Names,A,B\nMin,1,2\n"), ',');
+    assert_eq!(dialect.get_input_delimiter("\nNames;A;B\nAlice;1,5;2,5\n"), ';');
+    assert_eq!(dialect.get_input_delimiter("Names\tA\tB\n"), '\t');
+    // delimiters inside quoted fields do not count
+    assert_eq!(dialect.get_input_delimiter("\"Smith, Jo, Al\";A;B\n"), ';');
+    // a long CSV file starts with a table's name alone on its line
+    assert_eq!(dialect.get_input_delimiter("Tasks\nName;Min;Max\nPack;1;2\n"), ';');
+    assert_eq!(dialect.get_input_delimiter("Tasks\n"), ',');
+
+    let chosen = CsvDialect { delimiter: Delimiter::Tab, ..CsvDialect::default() };
+    assert_eq!(chosen.get_input_delimiter("Names;A;B\n"), '\t');
+}
+
+#[test]
+fn test_numbers() {
+    assert_eq!(EUROPEAN.parse_number::<f32>("1,5"), Ok(1.5));
+    assert_eq!(EUROPEAN.format_number(1.5), "1,5");
+    assert_eq!(EUROPEAN.get_output_delimiter(), ';');
+    assert_eq!(CsvDialect::default().format_number(1.5), "1.5");
+    assert!(CsvDialect::default().parse_number::<f32>("1,5").is_err());
+}
+
+#[test]
+fn test_format_row() {
+    assert_eq!(EUROPEAN.format_row(&["Smith, Jo", "1,5", "a;b"]), r#"Smith, Jo;1,5;"a;b""#);
+    assert_eq!(CsvDialect::default().format_row(&["Smith, Jo", "1.5"]), r#""Smith, Jo",1.5"#);
+}
+
+#[test]
+fn test_encodings() {
+    // Windows-1252 text, and a byte order mark that overrides the chosen encoding
+    assert_eq!(EUROPEAN.decode(b"Ren\xe9e").unwrap(), "Renée");
+    assert_eq!(EUROPEAN.decode(b"\xef\xbb\xbfRen\xc3\xa9e").unwrap(), "Renée");
+    assert_eq!(EUROPEAN.encode("Renée"), b"Ren\xe9e");
+    assert_eq!(CsvDialect::default().decode(b"\xef\xbb\xbfNames").unwrap(), "Names");
+    assert_eq!(CsvDialect::default().decode(b"Ren\xe9e").err().unwrap().to_string(),
+               "Input is not valid UTF-8 text! Choose the encoding it was saved in.");
+
+    assert_eq!(TextEncoding::from_str("latin1"), Ok(TextEncoding::WINDOWS_1252));
+    assert!(TextEncoding::from_str("klingon").is_err());
+}
@@ -0,0 +1,190 @@
+//! Errors from reading input files. A file that cannot be opened or read at all is an I/O error,
+//! while a file whose contents cannot be understood is a parse error that records where in the
+//! file the problem was found, so that it can be pointed out to the user.
+
+use std::fmt::{Display, Formatter};
+
+/// Where in an input file a problem was found. Lines (or rows of a sheet) and columns are counted
+/// from 1, and any part may be unknown. The sheet is only known in a workbook.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: Option<String>,
+    pub sheet: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl SourceLocation {
+    /// Create a location at the given line and column of a file not yet named
+    pub fn at(line: Option<usize>, column: Option<usize>) -> SourceLocation {
+        SourceLocation { file: None, sheet: None, line, column }
+    }
+
+    /// Check whether no part of the location is known
+    pub fn is_unknown(&self) -> bool {
+        self.file.is_none() && self.sheet.is_none() && self.line.is_none() && self.column.is_none()
+    }
+}
+
+impl Display for SourceLocation {
+    /// Describe the known parts of the location, e.g. "input.csv, line 4, column 2" or
+    /// "input.xlsx, sheet Tasks, row 4, column 2"
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(file) = &self.file {
+            parts.push(file.clone());
+        }
+        if let Some(sheet) = &self.sheet {
+            parts.push(format!("sheet {}", sheet));
+        }
+        match (self.line, &self.sheet) {
+            (Some(row), Some(_)) => parts.push(format!("row {}", row)),
+            (Some(line), None) => parts.push(format!("line {}", line)),
+            (None, _) => {}
+        }
+        if let Some(column) = self.column {
+            parts.push(format!("column {}", column));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// A problem with the contents of an input file
+#[derive(Clone, Debug)]
+pub enum ParseError {
+    /// The text of a field is not a valid value of the kind expected there
+    InvalidValue {
+        location: SourceLocation,
+        /// The offending text
+        text: String,
+        /// The kind of value expected, e.g. "integer minimum"
+        expected: String,
+        message: String,
+    },
+    /// The file is not laid out as expected, e.g. it is missing rows or fields, or it names tasks
+    /// or workers that do not match up
+    Malformed {
+        location: SourceLocation,
+        message: String,
+    },
+}
+
+impl ParseError {
+    /// Create an error for a field whose text is not a valid value of the expected kind
+    pub fn invalid_value(location: SourceLocation, text: &str, expected: &str, message: String)
+        -> ParseError {
+        ParseError::InvalidValue {
+            location, text: text.to_string(), expected: expected.to_string(), message
+        }
+    }
+
+    /// Create an error for a file that is not laid out as expected
+    pub fn malformed(location: SourceLocation, message: String) -> ParseError {
+        ParseError::Malformed { location, message }
+    }
+
+    /// Get where in the file the problem was found
+    pub fn get_location(&self) -> &SourceLocation {
+        match self {
+            ParseError::InvalidValue { location, .. } | ParseError::Malformed { location, .. } =>
+                location
+        }
+    }
+
+    /// Get the description of the problem
+    pub fn get_message(&self) -> &str {
+        match self {
+            ParseError::InvalidValue { message, .. } | ParseError::Malformed { message, .. } =>
+                message
+        }
+    }
+
+    /// Record the name of the file the problem was found in
+    pub fn in_file(mut self, filename: &str) -> ParseError {
+        self.get_location_mut().file = Some(filename.to_string());
+        self
+    }
+
+    /// Record the sheet of a workbook the problem was found in, and its row there in place of the
+    /// line it was given
+    pub fn in_sheet(mut self, sheet: &str, row: usize) -> ParseError {
+        let location = self.get_location_mut();
+        location.sheet = Some(sheet.to_string());
+        location.line = Some(row);
+        self
+    }
+
+    /// Get where in the file the problem was found, to change it
+    fn get_location_mut(&mut self) -> &mut SourceLocation {
+        match self {
+            ParseError::InvalidValue { location, .. } | ParseError::Malformed { location, .. } =>
+                location
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_message())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// An error reading an input file
+#[derive(Debug)]
+pub enum ReadError {
+    /// The file could not be opened or read
+    Io(std::io::Error),
+    /// The file's contents could not be understood, boxed to keep results small
+    Parse(Box<ParseError>),
+}
+
+impl ReadError {
+    /// Record the name of the file a parse error was found in
+    pub fn in_file(self, filename: &str) -> ReadError {
+        match self {
+            ReadError::Parse(err) => ReadError::Parse(Box::new(err.in_file(filename))),
+            other => other
+        }
+    }
+
+    /// Describe where a parse error was found and, for an invalid value, what was expected and
+    /// found there (e.g. `input.csv, line 4, column 2: expected integer minimum, found "abc"`), if
+    /// any of that is known
+    pub fn describe_location(&self) -> Option<String> {
+        match self {
+            ReadError::Parse(err) => match err.as_ref() {
+                ParseError::InvalidValue { location, text, expected, .. } =>
+                    Some(format!(r#"{}: expected {}, found "{}""#, location, expected, text)),
+                ParseError::Malformed { location, .. } if !location.is_unknown() =>
+                    Some(location.to_string()),
+                _ => None
+            },
+            ReadError::Io(_) => None
+        }
+    }
+}
+
+impl From<std::io::Error> for ReadError {
+    fn from(err: std::io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(err: ParseError) -> Self {
+        ReadError::Parse(Box::new(err))
+    }
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "{}", err),
+            ReadError::Parse(err) => write!(f, "{}", err)
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
@@ -0,0 +1,493 @@
+//! Structs that implement the Reader and Writer traits for JSON-formatted files. Both directions use
+//! a versioned schema: every document carries a `"version"` field, and readers reject versions they
+//! do not know, so the schema can change without old files being misread.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use crate::io::{ParseError, ReadError, Reader, SourceLocation, Writer};
+use crate::io::csv::add_worker_groups;
+use crate::network::{Network, Worker};
+#[cfg(test)]
+mod test;
+
+/// Version of the input and output schemas written by this module, and the only version read
+const SCHEMA_VERSION: u32 = 1;
+
+/// A JSON input document, structured as follows:
+/// ```json
+/// {
+///     "version": 1,
+///     "metadata": { "source": "HR export", "exported": "2024-01-31" },
+///     "tasks": [
+///         { "name": "Track 1", "min": 1, "max": 2 },
+///         { "name": "Track 2", "min": 0, "max": 3, "staffing_costs": [0, 0, 1] }
+///     ],
+///     "workers": [
+///         { "name": "Alice", "affinities": { "Track 1": 3, "Track 2": 1.5 } },
+///         { "name": "Bob", "affinities": { "Track 2": 2 }, "optional": true,
+///           "task_scores": { "Track 2": 1 }, "attributes": { "Department": "Sales" } }
+///     ],
+///     "conflicts": [["Alice", "Bob"]]
+/// }
+/// ```
+/// Each worker's affinities map the names of the tasks they can perform to the cost of assigning
+/// them there; tasks left out of the map are unacceptable assignments. `staffing_costs`,
+/// `optional`, `task_scores` and `attributes` are optional and mean the same as in the CSV format,
+/// with a worker's attributes mapping each attribute's name to their value, so that workers with
+/// the same "Group" attribute must share a task. `conflicts` is optional too, and lists the
+/// workers in each conflict, no two of whom may share a task. `metadata` is also optional and can
+/// hold anything; it is accepted but not used by the solver. Costs that were weighed together
+/// from several criteria are written as the combined costs.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InputDocument {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<serde_json::Value>,
+    tasks: Vec<TaskEntry>,
+    workers: Vec<WorkerEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    conflicts: Vec<Vec<String>>,
+}
+
+/// A task in a JSON input document
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskEntry {
+    name: String,
+    min: usize,
+    max: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    staffing_costs: Vec<f32>,
+}
+
+/// A worker in a JSON input document
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkerEntry {
+    name: String,
+    affinities: BTreeMap<String, f32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    optional: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    task_scores: BTreeMap<String, f32>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<String, String>,
+}
+
+/// A JSON output document, structured as follows:
+/// ```json
+/// {
+///     "version": 1,
+///     "total_score": 12.5,
+///     "assignments": [
+///         { "worker": "Alice", "task": "Track 3", "cost": 1.5 }
+///     ],
+///     "unassigned_workers": [],
+///     "unused_optional_workers": [],
+///     "statistics": { "num_tasks": 5, "num_workers": 10, "num_assigned": 10 }
+/// }
+/// ```
+/// As in the CSV output, `staffing_cost_total` is included if any task had a schedule of staffing
+/// costs, `raw_cost_total` is included if the workers' costs were rescaled (in which case each
+/// assignment also has the worker's `raw_cost` as given), `worker_score_total` and
+/// `task_score_total` are included if tasks scored the workers (in which case each assignment also
+/// has the task's `task_score` for the worker, if it gave one), `criterion_totals` is included if
+/// the costs weighed together several criteria (giving each criterion's weight and its weighted
+/// share of the workers' costs), `blocking_pairs` is included if they were recorded in the
+/// network, `change_penalty_total` and `moves` are included if a previous roster was given (in
+/// which case each move gives the worker, their previous task `from`, their new task `to` if they
+/// have one, and the `reason` they were moved), and `backups` is included if backup workers were
+/// asked for (giving each task's backups with their `rank`, their `current_task` if they have one,
+/// and the `cost_increase` of moving them there, leaving out workers in groups), and `optimality`
+/// is included if groups had to be kept together or conflicting workers apart (giving whether the
+/// solution is `proven_optimal`, a `lower_bound` on the total score, the `gap` between the two
+/// and, for a heuristic solution, the `heuristic_limit` that kept it from being proven optimal).
+/// Each assignment also has the worker's `attributes`, if they have any.
+#[derive(Serialize)]
+struct OutputDocument {
+    version: u32,
+    total_score: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    staffing_cost_total: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_cost_total: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    change_penalty_total: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worker_score_total: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_score_total: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    criterion_totals: Vec<CriterionTotalEntry>,
+    assignments: Vec<AssignmentEntry>,
+    unassigned_workers: Vec<String>,
+    unused_optional_workers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocking_pairs: Option<Vec<BlockingPairEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    moves: Option<Vec<MoveEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backups: Option<Vec<BackupEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    optimality: Option<OptimalityEntry>,
+    statistics: Statistics,
+}
+
+/// One worker's assignment in a JSON output document
+#[derive(Serialize)]
+struct AssignmentEntry {
+    worker: String,
+    task: String,
+    cost: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_cost: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_score: Option<f32>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<String, String>,
+}
+
+/// One criterion's share of the workers' costs for the tasks they were assigned to
+#[derive(Serialize)]
+struct CriterionTotalEntry {
+    criterion: String,
+    weight: f32,
+    total: f32,
+}
+
+/// A worker and a task that would both rather be assigned to each other
+#[derive(Serialize)]
+struct BlockingPairEntry {
+    worker: String,
+    task: String,
+}
+
+/// A worker whose assignment differs from the previous roster, and why
+#[derive(Serialize)]
+struct MoveEntry {
+    worker: String,
+    from: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+    reason: String,
+}
+
+/// A worker who could stand in at a task they were not assigned to
+#[derive(Serialize)]
+struct BackupEntry {
+    task: String,
+    rank: usize,
+    worker: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_task: Option<String>,
+    cost_increase: f32,
+}
+
+/// How close the solution is known to be to the cheapest possible one
+#[derive(Serialize)]
+struct OptimalityEntry {
+    proven_optimal: bool,
+    lower_bound: f32,
+    gap: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    heuristic_limit: Option<String>,
+}
+
+/// Figures describing the problem that was solved
+#[derive(Serialize)]
+struct Statistics {
+    num_tasks: usize,
+    num_workers: usize,
+    num_assigned: usize,
+}
+
+/// A reader for JSON-formatted input data, in the format documented on `InputDocument`
+pub(super) struct JsonReader {
+    // keep list of task names in the order they were added to the network
+    tasks: RefCell<Vec<Rc<String>>>,
+}
+
+impl JsonReader {
+    /// Create a new `JsonReader` struct
+    pub fn new() -> JsonReader {
+        JsonReader { tasks: RefCell::new(Vec::new()) }
+    }
+
+    /// Parse a JSON document and pass its contents into the network
+    fn process_file<R>(&mut self, reader: R, network: &Network) -> Result<(), ReadError>
+        where R: Read {
+        let document: InputDocument = serde_json::from_reader(reader).map_err(|err| {
+            if err.is_io() {
+                ReadError::Io(err.into())
+            } else {
+                let location = SourceLocation::at(Some(err.line()), Some(err.column()));
+                ParseError::malformed(location, format!("Invalid JSON input: {}", err)).into()
+            }
+        })?;
+        if document.version != SCHEMA_VERSION {
+            return Err(invalid_data(format!("Unsupported JSON schema version {}!",
+                                            document.version)));
+        }
+
+        let mut task_names: HashMap<String, Rc<String>> = HashMap::new();
+        for task in document.tasks {
+            if task_names.contains_key(&task.name) {
+                return Err(invalid_data(format!("Duplicate task {}!", task.name)));
+            }
+            if task.max < task.min {
+                return Err(invalid_data("Maximum cannot be less than minimum!".to_string()));
+            }
+            if task.staffing_costs.windows(2).any(|pair| pair[1] < pair[0]) {
+                return Err(invalid_data(format!("Staffing costs for task {} must not decrease!",
+                                                task.name)));
+            }
+
+            let task_name = Rc::new(task.name.clone());
+            self.tasks.borrow_mut().push(Rc::clone(&task_name));
+            network.add_task(Rc::clone(&task_name), task.min, task.max);
+            if !task.staffing_costs.is_empty() {
+                network.set_staffing_costs(&task_name, task.staffing_costs);
+            }
+            task_names.insert(task.name, task_name);
+        }
+
+        // every attribute any worker has becomes one of the network's attributes
+        let attribute_names: Vec<Rc<String>> = document.workers.iter()
+            .flat_map(|worker| worker.attributes.keys())
+            .collect::<BTreeSet<&String>>()
+            .into_iter()
+            .map(|name| Rc::new(name.clone()))
+            .collect();
+        network.set_worker_attribute_names(attribute_names.clone());
+
+        for worker in document.workers {
+            let worker_name = Rc::new(worker.name);
+            if network.has_worker(&worker_name) {
+                return Err(invalid_data(format!("Duplicate worker {}!", worker_name)));
+            }
+            let affinities = lookup_tasks(&task_names, &worker_name, &worker.affinities,
+                                          "affinities")?;
+            if worker.optional {
+                network.add_optional_worker(Rc::clone(&worker_name), &affinities);
+            } else {
+                network.add_worker(Rc::clone(&worker_name), &affinities);
+            }
+
+            if !worker.task_scores.is_empty() {
+                let scores = lookup_tasks(&task_names, &worker_name, &worker.task_scores,
+                                          "task scores")?;
+                network.add_task_preferences(&worker_name, &scores);
+            }
+            if !worker.attributes.is_empty() {
+                network.set_worker_attributes(&worker_name, attribute_names.iter()
+                    .map(|name| worker.attributes.get(name.as_str()).cloned().unwrap_or_default())
+                    .collect());
+            }
+        }
+        for conflict in document.conflicts {
+            let worker_names: Vec<Rc<String>> = conflict.into_iter().map(Rc::new).collect();
+            if let Some(unknown) = worker_names.iter().find(|name| !network.has_worker(name)) {
+                return Err(invalid_data(format!("Conflict listed for unknown worker {}!",
+                                                unknown)));
+            }
+            for (position, first) in worker_names.iter().enumerate() {
+                for second in &worker_names[position + 1..] {
+                    network.add_conflict(first, second);
+                }
+            }
+        }
+        add_worker_groups(network)
+    }
+}
+
+/// Create an error for a problem with the contents of the document. Once the document has been
+/// parsed, the positions of its values are no longer known, so the error has no location.
+fn invalid_data(message: String) -> ReadError {
+    ParseError::malformed(SourceLocation::default(), message).into()
+}
+
+/// Replace the task names in a worker's map of values with the network's names for those tasks,
+/// reporting any name that is not a known task. The description names the map in error messages.
+fn lookup_tasks<'a>(task_names: &'a HashMap<String, Rc<String>>, worker_name: &str,
+                    values: &BTreeMap<String, f32>, description: &str)
+    -> Result<Vec<(&'a Rc<String>, f32)>, ReadError> {
+    values.iter()
+        .map(|(task_name, value)| match task_names.get(task_name) {
+            Some(name) => Ok((name, *value)),
+            None => Err(invalid_data(format!("Unknown task {} in {} for worker {}!",
+                                             task_name, description, worker_name)))
+        })
+        .collect()
+}
+
+impl Reader for JsonReader {
+    /// Create file handle and pass it to the `process_file` method for reading
+    fn read_file(&mut self, filename: String, network: &Network) -> Result<(), ReadError> {
+        let f = File::open(filename)?;
+        self.process_file(BufReader::new(f), network)
+    }
+
+    fn clone_task_names(&self) -> Vec<Rc<String>> {
+        self.tasks.borrow().clone()
+    }
+}
+
+/// A writer for JSON-formatted output data, in the format documented on `OutputDocument`. It can
+/// also write the problem back out in the format documented on `InputDocument`.
+pub(super) struct JsonWriter {}
+
+impl JsonWriter {
+    /// Create a new `JsonWriter`
+    pub fn new() -> JsonWriter {
+        JsonWriter {}
+    }
+
+    /// Collect the outputs of a solved Network into an output document
+    fn get_output_document(&self, outputs: &Network) -> OutputDocument {
+        let tasks = outputs.get_tasks();
+        let workers = outputs.get_workers();
+        let attribute_names = outputs.get_worker_attribute_names();
+        let raw_cost_total = outputs.get_raw_cost_total();
+        let assignments: Vec<AssignmentEntry> = workers.iter()
+            .zip(outputs.get_assignments())
+            .filter_map(|(worker, assignment)| assignment.map(|task_id| AssignmentEntry {
+                worker: String::clone(worker.get_name()),
+                task: String::clone(tasks[task_id].get_name()),
+                cost: worker.get_affinity(task_id).unwrap_or_default(),
+                raw_cost: raw_cost_total.and(worker.get_raw_affinity(task_id)),
+                task_score: worker.get_task_score(task_id),
+                attributes: get_attributes(&attribute_names, worker),
+            }))
+            .collect();
+        let task_score_total = outputs.get_task_score_total();
+
+        OutputDocument {
+            version: SCHEMA_VERSION,
+            total_score: outputs.get_total_cost(),
+            staffing_cost_total: outputs.get_staffing_cost_total(),
+            raw_cost_total,
+            change_penalty_total: outputs.get_change_penalty_total(),
+            worker_score_total: task_score_total.map(|_| outputs.get_worker_score_total()),
+            task_score_total,
+            criterion_totals: outputs.get_criterion_totals().into_iter()
+                .map(|(criterion, total)| CriterionTotalEntry {
+                    criterion: String::clone(criterion.get_name()),
+                    weight: criterion.get_weight(),
+                    total,
+                })
+                .collect(),
+            statistics: Statistics {
+                num_tasks: tasks.len(),
+                num_workers: workers.len(),
+                num_assigned: assignments.len(),
+            },
+            assignments,
+            unassigned_workers: to_strings(outputs.get_unassigned_workers()),
+            unused_optional_workers: to_strings(outputs.get_unused_optional_workers()),
+            blocking_pairs: outputs.get_blocking_pairs().map(|pairs| pairs.into_iter()
+                .map(|(worker, task)| BlockingPairEntry {
+                    worker: String::clone(&worker),
+                    task: String::clone(&task),
+                })
+                .collect()),
+            moves: outputs.get_moves().map(|moves| moves.into_iter()
+                .map(|change| MoveEntry {
+                    worker: String::clone(&change.worker),
+                    from: String::clone(&change.from),
+                    to: change.to.map(|task| String::clone(&task)),
+                    reason: change.reason.to_string(),
+                })
+                .collect()),
+            backups: outputs.get_backups().map(|backups| backups.into_iter()
+                .map(|backup| BackupEntry {
+                    task: String::clone(&backup.task),
+                    rank: backup.rank,
+                    worker: String::clone(&backup.worker),
+                    current_task: backup.current_task.map(|task| String::clone(&task)),
+                    cost_increase: backup.cost_increase,
+                })
+                .collect()),
+            optimality: outputs.get_optimality().map(|optimality| OptimalityEntry {
+                proven_optimal: optimality.proven_optimal,
+                lower_bound: optimality.lower_bound,
+                gap: optimality.gap,
+                heuristic_limit: optimality.limit.map(|limit| limit.to_string()),
+            }),
+        }
+    }
+
+    /// Collect the problem held in a Network into an input document
+    fn get_input_document(&self, problem: &Network) -> InputDocument {
+        let tasks = problem.get_tasks();
+        let attribute_names = problem.get_worker_attribute_names();
+        let task_values = |values: &[(usize, f32)]| values.iter()
+            .map(|(task_id, value)| (String::clone(tasks[*task_id].get_name()), *value))
+            .collect();
+
+        InputDocument {
+            version: SCHEMA_VERSION,
+            metadata: None,
+            tasks: tasks.iter()
+                .map(|task| TaskEntry {
+                    name: String::clone(task.get_name()),
+                    min: task.get_min_workers(),
+                    max: task.get_max_workers(),
+                    staffing_costs: task.get_staffing_costs().to_vec(),
+                })
+                .collect(),
+            workers: problem.get_workers().iter()
+                .map(|worker| WorkerEntry {
+                    name: String::clone(worker.get_name()),
+                    affinities: task_values(worker.get_raw_affinities()),
+                    optional: worker.is_optional(),
+                    task_scores: task_values(worker.get_task_scores()),
+                    attributes: get_attributes(&attribute_names, worker),
+                })
+                .collect(),
+            conflicts: problem.get_conflicts().into_iter()
+                .map(|(first, second)| vec![String::clone(&first), String::clone(&second)])
+                .collect(),
+        }
+    }
+}
+
+/// Map the names of the network's worker attributes to a worker's values for them, leaving out
+/// blank values
+fn get_attributes(attribute_names: &[Rc<String>], worker: &Worker) -> BTreeMap<String, String> {
+    attribute_names.iter()
+        .enumerate()
+        .map(|(attribute_id, name)| (String::clone(name), worker.get_attribute(attribute_id)))
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| (name, value.to_string()))
+        .collect()
+}
+
+/// Copy a list of shared names into a list of owned names for serialization
+fn to_strings(names: Vec<Rc<String>>) -> Vec<String> {
+    names.iter().map(|name| String::clone(name)).collect()
+}
+
+/// Serialize a document into a file as indented JSON
+fn write_document<T: Serialize>(document: &T, filename: String) -> std::io::Result<()> {
+    let mut file = BufWriter::new(File::create(filename)?);
+    serde_json::to_writer_pretty(&mut file, document)?;
+    writeln!(file)?;
+    file.flush()
+}
+
+impl Writer for JsonWriter {
+    /// Create new file or replace existing file, and write the output document into it
+    fn write_file(&self, results: &Network, filename: String) -> std::io::Result<()> {
+        write_document(&self.get_output_document(results), filename)
+    }
+
+    /// Create new file or replace existing file, and write the input document into it
+    fn write_input_file(&self, problem: &Network, filename: String) -> std::io::Result<()> {
+        write_document(&self.get_input_document(problem), filename)
+    }
+}
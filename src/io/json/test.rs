@@ -0,0 +1,127 @@
+use crate::io::json::*;
+use crate::status::CurrentStatus;
+use crate::io::TestOutputDir;
+
+#[test]
+fn test_read() {
+    let mut file_reader = JsonReader::new();
+    let network = Network::new();
+    file_reader.read_file("src/io/json/test-data/testInput.json".to_string(),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let total_cost = network.get_total_cost();
+    assert!((total_cost - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
+}
+
+#[test]
+fn test_read_bad_version() {
+    let mut file_reader = JsonReader::new();
+    let network = Network::new();
+    let result = file_reader.read_file("src/io/json/test-data/inputBadVersion.json".to_string(),
+                                       &network);
+    assert!(result.is_err());
+    assert_eq!(result.err().unwrap().to_string(), "Unsupported JSON schema version 2!");
+}
+
+#[test]
+fn test_read_malformed() {
+    let mut file_reader = JsonReader::new();
+    let network = Network::new();
+    let result = file_reader.read_file("src/io/json/test-data/inputMalformed.json".to_string(),
+                                       &network);
+    match result {
+        Err(ReadError::Parse(err)) => {
+            // the missing comma is noticed at the next key
+            assert_eq!(err.get_location().line, Some(7));
+            assert!(err.to_string().starts_with("Invalid JSON input: "));
+        },
+        other => panic!("Expected a parse error, found {:?}", other)
+    }
+}
+
+#[test]
+fn test_read_unknown_task() {
+    let mut file_reader = JsonReader::new();
+    let network = Network::new();
+    let result = file_reader.read_file("src/io/json/test-data/inputUnknownTask.json".to_string(),
+                                       &network);
+    assert!(result.is_err());
+    assert_eq!(result.err().unwrap().to_string(),
+               "Unknown task Track 9 in affinities for worker Alice!");
+}
+
+#[test]
+fn test_read_duplicate_task() {
+    let mut file_reader = JsonReader::new();
+    let network = Network::new();
+    let result = file_reader.read_file("src/io/json/test-data/inputDuplicateTask.json".to_string(),
+                                       &network);
+    match result {
+        Err(ReadError::Parse(err)) => assert_eq!(err.to_string(), "Duplicate task Track 1!"),
+        other => panic!("Expected a parse error, found {:?}", other)
+    }
+}
+
+#[test]
+fn test_read_duplicate_worker() {
+    let mut file_reader = JsonReader::new();
+    let network = Network::new();
+    let result = file_reader.read_file(
+        "src/io/json/test-data/inputDuplicateWorker.json".to_string(), &network);
+    match result {
+        Err(ReadError::Parse(err)) => assert_eq!(err.to_string(), "Duplicate worker Alice!"),
+        other => panic!("Expected a parse error, found {:?}", other)
+    }
+}
+
+#[test]
+fn test_read_duplicate_grouped_worker() {
+    let mut file_reader = JsonReader::new();
+    let network = Network::new();
+    let result = file_reader.read_file(
+        "src/io/json/test-data/inputDuplicateGroupedWorker.json".to_string(), &network);
+    match result {
+        Err(ReadError::Parse(err)) => assert_eq!(err.to_string(), "Duplicate worker A!"),
+        other => panic!("Expected a parse error, found {:?}", other)
+    }
+}
+
+#[test]
+fn test_write() {
+    let output = TestOutputDir::new("test_write");
+    let mut file_reader = JsonReader::new();
+    let network = Network::new();
+    file_reader.read_file("src/io/json/test-data/inputTaskPreferences.json".to_string(),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    network.record_blocking_pairs();
+    let document = JsonWriter::new().get_output_document(&network);
+    assert_eq!(document.version, SCHEMA_VERSION);
+    assert_eq!(document.assignments.len(), 10);
+    assert_eq!(document.statistics.num_assigned, 10);
+    assert!(document.task_score_total.is_some());
+    assert!(!document.blocking_pairs.unwrap().is_empty());
+
+    JsonWriter::new().write_file(&network,
+                                 output.path("testOutput.json")).unwrap();
+}
+
+#[test]
+fn test_write_input() {
+    let output = TestOutputDir::new("test_write_input");
+    let mut file_reader = JsonReader::new();
+    let network = Network::new();
+    file_reader.read_file("src/io/json/test-data/inputTaskPreferences.json".to_string(),
+                          &network).unwrap();
+    JsonWriter::new().write_input_file(&network,
+                                       output.path("testInput.json"))
+        .unwrap();
+
+    let mut round_trip_reader = JsonReader::new();
+    let round_trip = Network::new();
+    round_trip_reader.read_file(output.path("testInput.json"),
+                                &round_trip).unwrap();
+    assert_eq!(round_trip_reader.clone_task_names(), file_reader.clone_task_names());
+    assert_eq!(round_trip.get_workers().len(), network.get_workers().len());
+    assert!(round_trip.get_workers().iter().all(|worker| worker.has_task_scores()));
+}
@@ -0,0 +1,307 @@
+//! Structs that implement the Reader and Writer traits for long (or "tidy") CSV files, which list
+//! each eligible worker-task pair on its own row instead of filling in a whole matrix. This suits
+//! problems where each worker can only perform a few of many tasks.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::Write;
+use std::rc::Rc;
+use std::str::FromStr;
+use crate::io::{CsvDialect, OutputLayout, ParseError, ReadError, Reader, SourceLocation, Writer};
+use crate::io::csv::{CsvWriter, numbered_lines, read_record};
+use crate::network::Network;
+#[cfg(test)]
+mod test;
+
+/// Name of the table of tasks
+const TASKS_TABLE: &str = "Tasks";
+
+/// Name of the optional table of workers
+const WORKERS_TABLE: &str = "Workers";
+
+/// Name of the table of worker-task pairs
+const EDGES_TABLE: &str = "Edges";
+
+/// The table whose rows are being read
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Table {
+    Tasks,
+    Workers,
+    Edges,
+}
+
+/// A reader for long CSV input data, structured as a series of tables. Each table starts with a
+/// row holding just its name, followed by a header row naming its columns (which is skipped) and
+/// then one row per entry:
+/// ```text
+/// Tasks
+/// Name,Min,Max
+/// Pack,1,2
+/// Ship,0,3
+///
+/// Workers
+/// Name
+/// Alice
+/// Bob
+///
+/// Edges
+/// Worker,Task,Cost
+/// Alice,Pack,3
+/// Bob,Ship,1.5
+/// ```
+/// Every worker-task pair missing from the edges table is an unacceptable assignment. The workers
+/// table is optional: when it comes before the edges table, the edges may only name the workers it
+/// lists (which are added to the network in its order), and otherwise workers are added in the
+/// order they first appear in the edges. Blank rows are skipped, and fields can be quoted and the
+/// file's dialect chosen as in `CsvReader`. Errors give the line of the file where the problem was
+/// found, both in their message and as a `ParseError` location.
+pub(super) struct LongCsvReader {
+    // keep list of task names in the order they were added to the network
+    tasks: RefCell<Vec<Rc<String>>>,
+    dialect: CsvDialect,
+}
+
+impl LongCsvReader {
+    /// Create a new `LongCsvReader` struct that reads files in the given dialect
+    pub fn new(dialect: CsvDialect) -> LongCsvReader {
+        LongCsvReader { tasks: RefCell::new(Vec::new()), dialect }
+    }
+
+    /// Read the decoded text of a file record by record to construct a Network from it
+    fn process_file(&mut self, text: &str, network: &Network) -> Result<(), ReadError> {
+        let delimiter = self.dialect.get_input_delimiter(text);
+        // count lines as they are read, so that records can be traced back to where they start
+        let lines_read = Cell::new(0);
+        let mut lines = numbered_lines(text)
+            .inspect(|_| lines_read.set(lines_read.get() + 1));
+
+        let mut task_ids: HashMap<Rc<String>, usize> = HashMap::new();
+        let mut worker_names: Vec<Rc<String>> = Vec::new();
+        let mut has_workers_table = false;
+        // (task index, cost) pairs for each worker, by name
+        let mut edges: HashMap<Rc<String>, Vec<(usize, f32)>> = HashMap::new();
+
+        let mut table: Option<Table> = None;
+        let mut expecting_header = false;
+        loop {
+            let line_number = lines_read.get() + 1;
+            let record = match read_record(&mut lines, delimiter) {
+                Some(record) => record?,
+                None => break
+            };
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+            if expecting_header {
+                expecting_header = false;
+                continue;
+            }
+
+            // a row holding just a table's name starts that table; within the workers table, any
+            // other single field is a worker's name
+            if record.len() == 1 {
+                let name = record[0].trim();
+                let next_table = match name {
+                    TASKS_TABLE => Some(Table::Tasks),
+                    WORKERS_TABLE => Some(Table::Workers),
+                    EDGES_TABLE => Some(Table::Edges),
+                    _ if table == Some(Table::Workers) => None,
+                    _ => return Err(invalid_data(line_number, Some(1),
+                        format!("Unknown table {} on line {}!", name, line_number)))
+                };
+                if next_table.is_some() {
+                    table = next_table;
+                    has_workers_table |= table == Some(Table::Workers);
+                    expecting_header = true;
+                    continue;
+                }
+            }
+
+            match table {
+                Some(Table::Tasks) => {
+                    let [name, minimum, maximum] = get_fields(&record, line_number)?;
+                    let lower = parse_field::<usize>(&self.dialect, minimum, "integer minimum",
+                                                     line_number, 2)?;
+                    let upper = parse_field::<usize>(&self.dialect, maximum, "integer maximum",
+                                                     line_number, 3)?;
+                    if upper < lower {
+                        return Err(invalid_data(line_number, None, format!(
+                            "Maximum cannot be less than minimum on line {}!", line_number)));
+                    }
+                    let task_name = Rc::new(name.trim().to_string());
+                    if task_ids.contains_key(&task_name) {
+                        return Err(invalid_data(line_number, None, format!(
+                            "Duplicate task {} on line {}!", task_name, line_number)));
+                    }
+                    task_ids.insert(Rc::clone(&task_name), task_ids.len());
+                    self.tasks.borrow_mut().push(Rc::clone(&task_name));
+                    network.add_task(task_name, lower, upper);
+                },
+                Some(Table::Workers) => {
+                    let [name] = get_fields(&record, line_number)?;
+                    let worker_name = Rc::new(name.trim().to_string());
+                    if edges.contains_key(&worker_name) {
+                        return Err(invalid_data(line_number, None, format!(
+                            "Duplicate worker {} on line {}!", worker_name, line_number)));
+                    }
+                    edges.insert(Rc::clone(&worker_name), Vec::new());
+                    worker_names.push(worker_name);
+                },
+                Some(Table::Edges) => {
+                    let [worker, task, cost] = get_fields(&record, line_number)?;
+                    let worker_name = Rc::new(worker.trim().to_string());
+                    let task_name = task.trim().to_string();
+                    let task_id = match task_ids.get(&task_name) {
+                        Some(task_id) => *task_id,
+                        None => return Err(invalid_data(line_number, Some(2), format!(
+                            "Unknown task {} on line {}!", task_name, line_number)))
+                    };
+                    let cost_text = cost;
+                    let cost = parse_field::<f32>(&self.dialect, cost_text, "numeric cost",
+                                                  line_number, 3)?;
+                    if !cost.is_finite() {
+                        return Err(ParseError::invalid_value(
+                            SourceLocation::at(Some(line_number), Some(3)), cost_text,
+                            "finite numeric cost", format!(
+                                r#"Expected finite cost on line {}, found "{}"!"#, line_number,
+                                cost_text)).into());
+                    }
+
+                    if !edges.contains_key(&worker_name) {
+                        if has_workers_table {
+                            return Err(invalid_data(line_number, Some(1), format!(
+                                "Unknown worker {} on line {}!", worker_name, line_number)));
+                        }
+                        edges.insert(Rc::clone(&worker_name), Vec::new());
+                        worker_names.push(Rc::clone(&worker_name));
+                    }
+                    let worker_edges = edges.get_mut(&worker_name).unwrap();
+                    if worker_edges.iter().any(|(t, _)| *t == task_id) {
+                        return Err(invalid_data(line_number, None, format!(
+                            "Duplicate edge for worker {} and task {} on line {}!",
+                            worker_name, task_name, line_number)));
+                    }
+                    worker_edges.push((task_id, cost));
+                },
+                None => return Err(invalid_data(line_number, None, format!(
+                    "Expected a table name on line {}!", line_number)))
+            }
+        }
+
+        if self.tasks.borrow().is_empty() {
+            return Err(ParseError::malformed(SourceLocation::default(),
+                                             "No tasks found in input file!".to_string()).into());
+        }
+        let tasks = self.tasks.borrow();
+        for worker_name in worker_names {
+            let affinities: Vec<(&Rc<String>, f32)> = edges[&worker_name].iter()
+                .map(|(task_id, cost)| (&tasks[*task_id], *cost))
+                .collect();
+            network.add_worker(worker_name, &affinities);
+        }
+
+        Ok(())
+    }
+}
+
+/// Create an error for a problem with the contents of the file at the given line and column
+fn invalid_data(line_number: usize, column: Option<usize>, message: String) -> ReadError {
+    ParseError::malformed(SourceLocation::at(Some(line_number), column), message).into()
+}
+
+/// Get the fields of a record that should have exactly `N` of them
+fn get_fields<const N: usize>(record: &[String], line_number: usize)
+    -> Result<[&String; N], ReadError> {
+    let fields: Vec<&String> = record.iter().collect();
+    fields.try_into().map_err(|fields: Vec<&String>| invalid_data(line_number, None, format!(
+        "Expected {} fields on line {}, found {}!", N, line_number, fields.len())))
+}
+
+/// Parse the field in the given column, written with the dialect's decimal separator, naming the
+/// expected value in the error message if it cannot be parsed
+fn parse_field<T>(dialect: &CsvDialect, field: &str, description: &str, line_number: usize,
+                  column: usize) -> Result<T, ReadError>
+    where T: FromStr, T::Err: std::fmt::Display {
+    dialect.parse_number::<T>(field.trim()).map_err(|err| ParseError::invalid_value(
+        SourceLocation::at(Some(line_number), Some(column)), field, description, format!(
+            r#"Expected {} on line {}, found "{}"; error: {}"#, description, line_number, field,
+            err)).into())
+}
+
+impl Reader for LongCsvReader {
+    /// Read and decode the file, and pass its text to the `process_file` method for reading
+    fn read_file(&mut self, filename: String, network: &Network) -> Result<(), ReadError> {
+        let text = self.dialect.decode(&std::fs::read(filename)?)?;
+        self.process_file(&text, network)
+    }
+
+    fn clone_task_names(&self) -> Vec<Rc<String>> {
+        self.tasks.borrow().clone()
+    }
+}
+
+/// A writer for problems read from long CSV files. Results are written in the same table as
+/// `CsvWriter`, while input files are written in the long format read by `LongCsvReader`. Staffing
+/// costs, optional workers and task preferences have no place in the long format and are left out.
+pub(super) struct LongCsvWriter {
+    results: CsvWriter,
+    dialect: CsvDialect,
+}
+
+impl LongCsvWriter {
+    /// Create a new `LongCsvWriter` that writes in the given dialect
+    pub fn new(task_names: Vec<Rc<String>>, layout: OutputLayout, dialect: CsvDialect)
+        -> LongCsvWriter {
+        LongCsvWriter { results: CsvWriter::new(task_names, layout, dialect), dialect }
+    }
+
+    /// Write the problem held in a Network into a buffer, in the long CSV input format. Costs that
+    /// were weighed together from several criteria are written as the combined costs.
+    fn write_input(&self, problem: &Network, out: &mut Vec<u8>) -> std::io::Result<()> {
+        let dialect = &self.dialect;
+        let tasks = problem.get_tasks();
+        let workers = problem.get_workers();
+
+        writeln!(out, "{}", TASKS_TABLE)?;
+        writeln!(out, "{}", dialect.format_row(&["Name", "Min", "Max"]))?;
+        for task in &tasks {
+            writeln!(out, "{}", dialect.format_row(&[task.get_name().to_string(),
+                                                     task.get_min_workers().to_string(),
+                                                     task.get_max_workers().to_string()]))?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "{}", WORKERS_TABLE)?;
+        writeln!(out, "Name")?;
+        for worker in &workers {
+            writeln!(out, "{}", dialect.format_row(&[worker.get_name()]))?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "{}", EDGES_TABLE)?;
+        writeln!(out, "{}", dialect.format_row(&["Worker", "Task", "Cost"]))?;
+        for worker in &workers {
+            for (task_id, cost) in worker.get_raw_affinities() {
+                writeln!(out, "{}", dialect.format_row(&[worker.get_name().to_string(),
+                                                         tasks[*task_id].get_name().to_string(),
+                                                         dialect.format_number(cost)]))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Writer for LongCsvWriter {
+    /// Write the results in the same table as `CsvWriter`
+    fn write_file(&self, results: &Network, filename: String) -> std::io::Result<()> {
+        self.results.write_file(results, filename)
+    }
+
+    /// Write the problem to a buffer, then encode it into a new file or over an existing one
+    fn write_input_file(&self, problem: &Network, filename: String) -> std::io::Result<()> {
+        let mut out = Vec::new();
+        self.write_input(problem, &mut out)?;
+        self.dialect.save(&out, filename)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::io::long_csv::*;
+use crate::status::CurrentStatus;
+use crate::io::TestOutputDir;
+
+#[test]
+fn test_read() {
+    let mut file_reader = LongCsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/long_csv/test-data/testInput.csv".to_string(),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let total_cost = network.get_total_cost();
+    assert!((total_cost - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
+}
+
+#[test]
+fn test_read_unknown_task() {
+    let mut file_reader = LongCsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    let result = file_reader.read_file("src/io/long_csv/test-data/inputUnknownTask.csv".to_string(),
+                                       &network);
+    assert!(result.is_err());
+    let err = result.err().unwrap();
+    assert_eq!(err.to_string(), "Unknown task Receive on line 10!");
+    assert_eq!(err.describe_location(), Some("line 10, column 2".to_string()));
+}
+
+#[test]
+fn test_read_unknown_worker() {
+    let mut file_reader = LongCsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    let result = file_reader.read_file(
+        "src/io/long_csv/test-data/inputUnknownWorker.csv".to_string(), &network);
+    assert!(result.is_err());
+    assert_eq!(result.err().unwrap().to_string(), "Unknown worker Carol on line 15!");
+}
+
+#[test]
+fn test_read_non_finite_cost() {
+    let mut file_reader = LongCsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    let result = file_reader.read_file(
+        "src/io/long_csv/test-data/inputNonFiniteCost.csv".to_string(), &network);
+    assert!(result.is_err());
+    let err = result.err().unwrap();
+    assert_eq!(err.to_string(), r#"Expected finite cost on line 8, found "NaN"!"#);
+    assert_eq!(err.describe_location(),
+               Some(r#"line 8, column 3: expected finite numeric cost, found "NaN""#.to_string()));
+}
+
+#[test]
+fn test_write_input() {
+    let output = TestOutputDir::new("test_write_input");
+    let mut file_reader = LongCsvReader::new(CsvDialect::default());
+    let network = Network::new();
+    file_reader.read_file("src/io/long_csv/test-data/testInput.csv".to_string(),
+                          &network).unwrap();
+    let file_writer = LongCsvWriter::new(file_reader.clone_task_names(), OutputLayout::ByTask,
+                                         CsvDialect::default());
+    file_writer.write_input_file(&network,
+                                 output.path("testInput.csv")).unwrap();
+
+    let mut round_trip_reader = LongCsvReader::new(CsvDialect::default());
+    let round_trip = Network::new();
+    round_trip_reader.read_file(output.path("testInput.csv"),
+                                &round_trip).unwrap();
+    assert_eq!(round_trip_reader.clone_task_names(), file_reader.clone_task_names());
+    round_trip.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert!((round_trip.get_total_cost() - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
+}
@@ -7,44 +7,165 @@
 //! on a chosen item in the `FileType` enum. The enum should have one entry for every filetype
 //! supported by implementations of the Reader and Writer traits.
 
+use std::path::Path;
 use std::rc::Rc;
+use std::str::FromStr;
 use crate::io::csv::{CsvReader, CsvWriter};
-use crate::network::Network;
+use crate::io::json::{JsonReader, JsonWriter};
+use crate::io::long_csv::{LongCsvReader, LongCsvWriter};
+use crate::io::spreadsheet::{SpreadsheetFormat, SpreadsheetReader, SpreadsheetWriter};
+use crate::network::{Network, Severity};
+pub(crate) use crate::io::csv::{read_previous_assignments, write_statistics_file};
+pub use crate::io::dialect::{CsvDialect, DecimalSeparator, Delimiter, TextEncoding};
+pub use crate::io::error::{ParseError, ReadError, SourceLocation};
+pub use crate::io::overwrite::OverwritePolicy;
+pub use crate::io::ranking::{RankCosts, RankScheme, UnrankedTasks};
+pub(crate) use crate::io::overwrite::save_atomically;
+pub(crate) use crate::io::validation::{get_network_issues, ValidationIssue};
+#[cfg(test)]
+pub(crate) use crate::io::test_output::TestOutputDir;
 
 mod csv;
+mod dialect;
+mod error;
+mod json;
+mod long_csv;
+mod overwrite;
+mod ranking;
+mod validation;
+mod spreadsheet;
+#[cfg(test)]
+mod test_output;
 
 /// Supported file types
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum FileType {
-    Csv
+    Csv,
+    /// CSV listing one worker, task and cost per row, which has no file extension of its own
+    LongCsv,
+    /// CSV listing each worker's tasks in order of preference, which has no file extension of its
+    /// own
+    RankedCsv,
+    Json,
+    Xlsx,
+    Ods
+}
+
+impl FileType {
+    /// Guess the file type from a file name's extension, if it is one of the supported types
+    pub fn from_path(filename: &str) -> Option<FileType> {
+        Path::new(filename).extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| FileType::from_str(extension).ok())
+    }
+}
+
+/// Supported layouts for the assignments in an output file
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OutputLayout {
+    /// One column per task, listing the workers assigned to it
+    #[default]
+    ByTask,
+    /// One row per worker, giving their task, its cost, their best available cost and the regret
+    /// (the difference between the two)
+    ByWorker
+}
+
+impl FromStr for OutputLayout {
+    type Err = String;
+
+    /// Parse an output layout from its name
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "by-task" => Ok(OutputLayout::ByTask),
+            "by-worker" => Ok(OutputLayout::ByWorker),
+            _ => Err(format!("Unsupported output layout {}!", name))
+        }
+    }
+}
+
+impl FromStr for FileType {
+    type Err = String;
+
+    /// Parse a file type from its name, which is also its usual file extension (e.g. "csv")
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Ok(FileType::Csv),
+            "long-csv" => Ok(FileType::LongCsv),
+            "ranked-csv" => Ok(FileType::RankedCsv),
+            "json" => Ok(FileType::Json),
+            "xlsx" => Ok(FileType::Xlsx),
+            "ods" => Ok(FileType::Ods),
+            _ => Err(format!("Unsupported file type {}!", name))
+        }
+    }
 }
 
 /// A Reader will attempt to construct a Network from an input file, returning a Result that
-/// indicates whether it had any issues parsing the input file or, if not, a Network struct.
+/// indicates whether it had any issues parsing the input file or, if not, a Network struct. A file
+/// whose contents cannot be understood fails with a `ReadError::Parse` that gives where in the
+/// file the problem was found; Readers must not panic on any input.
+/// A Reader can also validate an input file, collecting every issue it finds rather than stopping
+/// at the first error. Readers that cannot carry on past an error only report that one, along with
+/// the issues apparent in the network once the file has been read.
 pub(crate) trait Reader {
-    fn read_file(&mut self, filename: String, network: &Network) -> std::io::Result<()>;
+    fn read_file(&mut self, filename: String, network: &Network) -> Result<(), ReadError>;
 
     fn clone_task_names(&self) -> Vec<Rc<String>>;
+
+    /// Read the file into the network, returning every issue found with the file and the network.
+    /// Only a file that cannot be opened or read at all is an error.
+    fn validate_file(&mut self, filename: String, network: &Network)
+        -> std::io::Result<Vec<ValidationIssue>> {
+        match self.read_file(filename, network) {
+            Ok(()) => Ok(get_network_issues(network)),
+            Err(ReadError::Parse(err)) =>
+                Ok(vec![ValidationIssue::from_error(Severity::Error, *err)]),
+            Err(ReadError::Io(err)) => Err(err)
+        }
+    }
 }
 
 /// A Writer takes a Network struct, extracts its worker-task assignments, and attempts to write the
-/// assignments to an output file, returning a Result that indicates whether it was successful.
+/// assignments to an output file, returning a Result that indicates whether it was successful. It
+/// can also write the problem itself back out in the input format for its file type, so that
+/// inputs can be converted from one file type to another.
 pub(crate) trait Writer {
     fn write_file(&self, results: &Network, filename: String) -> std::io::Result<()>;
+
+    fn write_input_file(&self, problem: &Network, filename: String) -> std::io::Result<()>;
 }
 
 /// Create a struct that implements the Reader trait based on the selected file type from the
-/// `FileType` enum
-pub(crate) fn reader_factory(file_type: FileType) -> impl Reader {
+/// `FileType` enum. CSV files are read in the given dialect, which other file types ignore, and
+/// ranked CSV files turn their ranks into costs with the given scheme.
+pub(crate) fn reader_factory(file_type: FileType, dialect: CsvDialect, ranking: &RankScheme)
+    -> Box<dyn Reader> {
     match file_type {
-        FileType::Csv => CsvReader::new()
+        FileType::Csv => Box::new(CsvReader::new(dialect)),
+        FileType::RankedCsv => Box::new(CsvReader::ranked(dialect, ranking.clone())),
+        FileType::LongCsv => Box::new(LongCsvReader::new(dialect)),
+        FileType::Json => Box::new(JsonReader::new()),
+        FileType::Xlsx => Box::new(SpreadsheetReader::new(SpreadsheetFormat::Xlsx)),
+        FileType::Ods => Box::new(SpreadsheetReader::new(SpreadsheetFormat::Ods))
     }
 }
 
 /// Create a struct that implements the Writer trait based on the selected file type from the
-/// `FileType` enum
-pub(crate) fn writer_factory(file_type: FileType, task_names: Vec<Rc<String>>) -> impl Writer {
+/// `FileType` enum, laying out its assignments as chosen from the `OutputLayout` enum. JSON output
+/// always lists one entry per assignment, so it has no choice of layout. CSV files are written in
+/// the given dialect, which other file types ignore. Ranks cannot be written back, so ranked CSV
+/// output is written as plain CSV, with the costs the ranks were turned into.
+pub(crate) fn writer_factory(file_type: FileType, task_names: Vec<Rc<String>>,
+                             layout: OutputLayout, dialect: CsvDialect) -> Box<dyn Writer> {
     match file_type {
-        FileType::Csv => CsvWriter::new(task_names)
+        FileType::Csv | FileType::RankedCsv =>
+            Box::new(CsvWriter::new(task_names, layout, dialect)),
+        FileType::LongCsv => Box::new(LongCsvWriter::new(task_names, layout, dialect)),
+        FileType::Json => Box::new(JsonWriter::new()),
+        FileType::Xlsx =>
+            Box::new(SpreadsheetWriter::new(SpreadsheetFormat::Xlsx, task_names, layout)),
+        FileType::Ods =>
+            Box::new(SpreadsheetWriter::new(SpreadsheetFormat::Ods, task_names, layout))
     }
 }
@@ -0,0 +1,145 @@
+//! Safe replacement of output files. Output is first written to a temporary file in the same
+//! directory as the output file, flushed to disk, and only renamed into place once it is complete,
+//! so a failure part way through never leaves a truncated or half-written output behind. What
+//! happens to an existing file at the output path is chosen with an `OverwritePolicy`.
+
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(test)]
+mod test;
+
+/// What to do when the output file already exists
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Replace the existing file
+    #[default]
+    Replace,
+    /// Leave the existing file alone and report an error
+    Refuse,
+    /// Copy the existing file to a backup named with the current time (and a count, if an earlier
+    /// backup was made in the same second), then replace it
+    Backup,
+}
+
+impl FromStr for OverwritePolicy {
+    type Err = String;
+
+    /// Parse an overwrite policy from its name
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "replace" => Ok(OverwritePolicy::Replace),
+            "refuse" => Ok(OverwritePolicy::Refuse),
+            "backup" => Ok(OverwritePolicy::Backup),
+            _ => Err(format!("Unsupported overwrite policy {}!", name))
+        }
+    }
+}
+
+/// Write an output file through a temporary file, which `write` is given the path of, and move
+/// the temporary file into place once it has been written and flushed to disk, following the
+/// policy for any file already at that path. The temporary file is removed if anything goes wrong.
+pub(crate) fn save_atomically<F>(filename: &str, policy: OverwritePolicy, write: F)
+    -> std::io::Result<()>
+    where F: FnOnce(String) -> std::io::Result<()> {
+    let path = Path::new(filename);
+    let already_exists = || std::io::Error::new(
+        ErrorKind::AlreadyExists, format!("Output file {} already exists!", filename));
+    if policy == OverwritePolicy::Refuse && path.exists() {
+        return Err(already_exists());
+    }
+
+    let temp_path = get_temp_path(path);
+    let result = write(temp_path.display().to_string())
+        .and_then(|_| {
+            // otherwise a crash soon after the rename could leave an empty file in place
+            OpenOptions::new().write(true).open(&temp_path)?.sync_all()?;
+            match policy {
+                // unlike renaming, linking fails if a file has been created at the path since it
+                // was checked, rather than replacing it
+                OverwritePolicy::Refuse => {
+                    std::fs::hard_link(&temp_path, path).map_err(|err| match err.kind() {
+                        ErrorKind::AlreadyExists => already_exists(),
+                        _ => err
+                    })?;
+                    std::fs::remove_file(&temp_path)
+                },
+                OverwritePolicy::Backup if path.exists() => {
+                    back_up(path)?;
+                    std::fs::rename(&temp_path, path)
+                },
+                _ => std::fs::rename(&temp_path, path)
+            }
+        });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+/// Get a hidden path beside the output file to write to first. The process ID keeps concurrent
+/// runs apart, and the output's own name is kept at the end so its extension is unchanged.
+fn get_temp_path(path: &Path) -> PathBuf {
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("output");
+    path.with_file_name(format!(".tmp-{}-{}", std::process::id(), file_name))
+}
+
+/// Copy an existing output file to a new backup named with the current time, counting up from the
+/// second backup made in the same second. The backup is only ever created, never replaced.
+fn back_up(path: &Path) -> std::io::Result<()> {
+    let time = SystemTime::now();
+    let mut existing = File::open(path)?;
+    let mut count = 1;
+    loop {
+        match OpenOptions::new().write(true).create_new(true)
+            .open(get_backup_path(path, time, count)) {
+            Ok(mut backup) => {
+                std::io::copy(&mut existing, &mut backup)?;
+                return backup.sync_all();
+            },
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => count += 1,
+            Err(err) => return Err(err)
+        }
+    }
+}
+
+/// Get the path to back an existing output file up to, e.g. "out-20261018-093000.csv" for
+/// "out.csv", using the given time (in UTC), or "out-20261018-093000-2.csv" for the second backup
+/// made in that second
+fn get_backup_path(path: &Path, time: SystemTime, count: usize) -> PathBuf {
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("output");
+    let timestamp = match count {
+        1 => format_timestamp(time),
+        count => format!("{}-{}", format_timestamp(time), count)
+    };
+    let backup_name = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => format!("{}-{}.{}", stem, timestamp, extension),
+        None => format!("{}-{}", stem, timestamp)
+    };
+    path.with_file_name(backup_name)
+}
+
+/// Format a time (in UTC) as "YYYYMMDD-HHMMSS"
+fn format_timestamp(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+
+    // convert days since 1970-01-01 into a civil date, counting years from March so that leap
+    // days fall at the end of each year
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524
+        - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, seconds_of_day / 3_600,
+            seconds_of_day % 3_600 / 60, seconds_of_day % 60)
+}
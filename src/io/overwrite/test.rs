@@ -0,0 +1,77 @@
+use std::time::Duration;
+use crate::io::overwrite::*;
+use crate::io::TestOutputDir;
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(UNIX_EPOCH), "19700101-000000");
+    // 2024-02-29 23:59:59 UTC, a leap day
+    assert_eq!(format_timestamp(UNIX_EPOCH + Duration::from_secs(1_709_251_199)),
+               "20240229-235959");
+}
+
+#[test]
+fn test_backup_path() {
+    assert_eq!(get_backup_path(Path::new("out/results.csv"), UNIX_EPOCH, 1),
+               Path::new("out/results-19700101-000000.csv"));
+    assert_eq!(get_backup_path(Path::new("results"), UNIX_EPOCH, 1),
+               Path::new("results-19700101-000000"));
+    assert_eq!(get_backup_path(Path::new("out/results.csv"), UNIX_EPOCH, 2),
+               Path::new("out/results-19700101-000000-2.csv"));
+}
+
+#[test]
+fn test_save_atomically() {
+    let output = TestOutputDir::new("test_save_atomically");
+    let filename = &output.path("atomicOutput.csv");
+    let write = |contents: &'static str| move |temp: String| std::fs::write(temp, contents);
+    save_atomically(filename, OverwritePolicy::Replace, write("a longer first version"))
+        .unwrap();
+    save_atomically(filename, OverwritePolicy::Replace, write("shorter")).unwrap();
+    assert_eq!(std::fs::read_to_string(filename).unwrap(), "shorter");
+
+    let result = save_atomically(filename, OverwritePolicy::Refuse, write("refused"));
+    assert_eq!(result.err().unwrap().kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(std::fs::read_to_string(filename).unwrap(), "shorter");
+
+    // a failed write leaves the existing file and no temporary file behind
+    let result = save_atomically(filename, OverwritePolicy::Replace, |temp: String| {
+        std::fs::write(temp, "partial")?;
+        Err(std::io::Error::other("Failed part way through"))
+    });
+    assert!(result.is_err());
+    assert_eq!(std::fs::read_to_string(filename).unwrap(), "shorter");
+    assert!(!get_temp_path(Path::new(filename)).exists());
+
+    // backups made in the same second are kept apart
+    save_atomically(filename, OverwritePolicy::Backup, write("second")).unwrap();
+    save_atomically(filename, OverwritePolicy::Backup, write("third")).unwrap();
+    assert_eq!(std::fs::read_to_string(filename).unwrap(), "third");
+    let mut backups: Vec<String> = std::fs::read_dir(Path::new(filename).parent().unwrap())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|backup| backup.as_path() != Path::new(filename))
+        .map(|backup| std::fs::read_to_string(backup).unwrap())
+        .collect();
+    backups.sort();
+    assert_eq!(backups, ["second", "shorter"]);
+}
+
+#[test]
+fn test_refuse_created_while_writing() {
+    let output = TestOutputDir::new("test_refuse_created_while_writing");
+    let filename = &output.path("refusedOutput.csv");
+    save_atomically(filename, OverwritePolicy::Refuse, |temp: String| std::fs::write(temp, "new"))
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(filename).unwrap(), "new");
+
+    // a file created at the path after it was checked is still left alone
+    let other = &output.path("otherOutput.csv");
+    let result = save_atomically(other, OverwritePolicy::Refuse, |temp: String| {
+        std::fs::write(other, "created meanwhile")?;
+        std::fs::write(temp, "refused")
+    });
+    assert_eq!(result.err().unwrap().kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(std::fs::read_to_string(other).unwrap(), "created meanwhile");
+    assert!(!get_temp_path(Path::new(other)).exists());
+}
@@ -0,0 +1,109 @@
+//! Schemes for turning ranked lists of tasks into costs. Surveys often ask workers to list their
+//! top few tasks in order rather than to score every task, so a `RankScheme` gives each rank a
+//! cost (the first choice has rank 1) and decides what happens to the tasks a worker left out.
+
+use std::str::FromStr;
+#[cfg(test)]
+mod test;
+
+/// The cost of each rank in a worker's list of tasks
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum RankCosts {
+    /// Each rank costs its number: 1 for the first choice, 2 for the second and so on
+    #[default]
+    Linear,
+    /// Each rank costs twice as much as the one before it, starting from 1, so that a worker's
+    /// first choice weighs far more than their later ones
+    Exponential,
+    /// Each rank costs the value listed for it, and ranks beyond the end of the list are treated
+    /// as unranked
+    Custom(Vec<f32>),
+}
+
+impl FromStr for RankCosts {
+    type Err = String;
+
+    /// Parse rank costs from the name of a scheme, or from the cost of each rank separated by
+    /// spaces (e.g. "0 1 3 6")
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "linear" => Ok(RankCosts::Linear),
+            "exponential" => Ok(RankCosts::Exponential),
+            _ => {
+                let costs = name.split_whitespace()
+                    .map(|cost| f32::from_str(cost).ok().filter(|c| c.is_finite()))
+                    .collect::<Option<Vec<f32>>>()
+                    .filter(|costs| !costs.is_empty());
+                costs.map(RankCosts::Custom)
+                    .ok_or_else(|| format!("Unsupported rank costs {}!", name))
+            }
+        }
+    }
+}
+
+/// What happens to the tasks a worker did not rank
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum UnrankedTasks {
+    /// The worker cannot be assigned to a task they did not rank
+    #[default]
+    Ineligible,
+    /// The worker can be assigned to a task they did not rank, at the given cost
+    Cost(f32),
+}
+
+impl FromStr for UnrankedTasks {
+    type Err = String;
+
+    /// Parse the treatment of unranked tasks from "ineligible" or a cost
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "ineligible" => Ok(UnrankedTasks::Ineligible),
+            _ => f32::from_str(name).ok()
+                .filter(|cost| cost.is_finite())
+                .map(UnrankedTasks::Cost)
+                .ok_or_else(|| format!("Unsupported cost for unranked tasks {}!", name))
+        }
+    }
+}
+
+/// How to turn a worker's ranked list of tasks into the cost of assigning them to each task
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RankScheme {
+    pub costs: RankCosts,
+    pub unranked: UnrankedTasks,
+}
+
+impl RankScheme {
+    /// Get the cost of assigning a worker to the task they ranked at the given position (1 for
+    /// their first choice), or `None` if they cannot be assigned to it
+    pub fn get_cost(&self, rank: usize) -> Option<f32> {
+        let ranked_cost = match &self.costs {
+            RankCosts::Linear => Some(rank as f32),
+            RankCosts::Exponential => Some(2_f32.powf(rank as f32 - 1.0)),
+            RankCosts::Custom(costs) => costs.get(rank - 1).copied()
+        };
+        ranked_cost.or_else(|| self.get_unranked_cost())
+    }
+
+    /// Get the cost of assigning a worker to a task they did not rank, or `None` if they cannot be
+    /// assigned to it
+    pub fn get_unranked_cost(&self) -> Option<f32> {
+        match self.unranked {
+            UnrankedTasks::Ineligible => None,
+            UnrankedTasks::Cost(cost) => Some(cost)
+        }
+    }
+
+    /// Turn a worker's ranked list of task indices, first choice first, into (task index, cost)
+    /// pairs for every task out of `num_tasks` that the worker can be assigned to
+    pub fn get_affinities(&self, ranked_tasks: &[usize], num_tasks: usize) -> Vec<(usize, f32)> {
+        let mut costs = vec![self.get_unranked_cost(); num_tasks];
+        for (rank, task_id) in (1..).zip(ranked_tasks) {
+            costs[*task_id] = self.get_cost(rank);
+        }
+        costs.into_iter()
+            .enumerate()
+            .filter_map(|(task_id, cost)| cost.map(|c| (task_id, c)))
+            .collect()
+    }
+}
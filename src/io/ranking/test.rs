@@ -0,0 +1,41 @@
+use crate::io::ranking::*;
+
+#[test]
+fn test_rank_costs() {
+    let linear = RankScheme::default();
+    assert_eq!(linear.get_cost(1), Some(1.0));
+    assert_eq!(linear.get_cost(3), Some(3.0));
+
+    let exponential = RankScheme { costs: RankCosts::Exponential, ..RankScheme::default() };
+    assert_eq!(exponential.get_cost(1), Some(1.0));
+    assert_eq!(exponential.get_cost(4), Some(8.0));
+
+    // ranks beyond a custom list are treated as unranked
+    let custom = RankScheme {
+        costs: RankCosts::Custom(vec![0.0, 1.0, 3.0]),
+        unranked: UnrankedTasks::Cost(10.0),
+    };
+    assert_eq!(custom.get_cost(2), Some(1.0));
+    assert_eq!(custom.get_cost(4), Some(10.0));
+    assert_eq!(RankScheme { unranked: UnrankedTasks::Ineligible, ..custom }.get_cost(4), None);
+}
+
+#[test]
+fn test_get_affinities() {
+    let ineligible = RankScheme::default();
+    assert_eq!(ineligible.get_affinities(&[2, 0], 4), [(0, 2.0), (2, 1.0)]);
+
+    let with_cost = RankScheme { unranked: UnrankedTasks::Cost(5.0), ..RankScheme::default() };
+    assert_eq!(with_cost.get_affinities(&[2, 0], 4), [(0, 2.0), (1, 5.0), (2, 1.0), (3, 5.0)]);
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(RankCosts::from_str("Exponential"), Ok(RankCosts::Exponential));
+    assert_eq!(RankCosts::from_str("0 1 3 6"), Ok(RankCosts::Custom(vec![0.0, 1.0, 3.0, 6.0])));
+    assert!(RankCosts::from_str("0 one").is_err());
+    assert!(RankCosts::from_str("").is_err());
+    assert_eq!(UnrankedTasks::from_str("ineligible"), Ok(UnrankedTasks::Ineligible));
+    assert_eq!(UnrankedTasks::from_str("12.5"), Ok(UnrankedTasks::Cost(12.5)));
+    assert!(UnrankedTasks::from_str("NaN").is_err());
+}
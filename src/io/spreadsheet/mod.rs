@@ -0,0 +1,487 @@
+//! Structs that implement the Reader and Writer traits for spreadsheet files: Excel (.xlsx) and
+//! OpenDocument (.ods). Spreadsheets hold the same layout as CSV files (see `CsvReader`), either
+//! split across a sheet named "Tasks" and a sheet named "Affinities" or, failing that, all on the
+//! first sheet. Reading numbers straight from the cells avoids the locale problems of exporting
+//! to CSV first.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::rc::Rc;
+use calamine::{Data, Ods, Range, Xlsx};
+use calamine::Reader as CalamineReader;
+use crate::io::{CsvDialect, OutputLayout, ParseError, ReadError, Reader, SourceLocation,
+                ValidationIssue, Writer};
+use crate::io::csv::{BACKUP_WORKERS_LABEL, BLOCKING_PAIRS_LABEL, CONFLICTS_LABEL, CRITERION_LABEL,
+                     CsvReader, format_attribute_header, format_optimality, get_task_detail_rows,
+                     get_worker_layout_headers, get_worker_rows, has_label, has_raw_costs,
+                     MOVED_WORKERS_LABEL, OPTIONAL_WORKERS_LABEL, STAFFING_COSTS_LABEL,
+                     TASK_PREFERENCES_LABEL, TotalRow, UNASSIGNED_WORKERS_LABEL,
+                     UNUSED_OPTIONAL_WORKERS_LABEL};
+use crate::network::{Criterion, Network, Severity, Worker};
+mod ods;
+mod xlsx;
+#[cfg(test)]
+mod test;
+
+/// Name of the sheet that holds the task rows: names, minimums, maximums and staffing costs
+const TASKS_SHEET: &str = "Tasks";
+
+/// Name of the sheet that holds the worker rows: affinities, optional workers, preferences and
+/// conflicts
+const AFFINITIES_SHEET: &str = "Affinities";
+
+/// Name of the sheet that output is written to
+const RESULTS_SHEET: &str = "Results";
+
+/// The spreadsheet formats that can be read and written
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum SpreadsheetFormat {
+    Xlsx,
+    Ods
+}
+
+/// A reader for spreadsheet input data. The "Tasks" sheet holds the rows that start a CSV input
+/// file (task names, minimums, maximums and the optional staffing costs) and the "Affinities"
+/// sheet holds the rest (workers, optional workers, task preferences and conflicts). The
+/// "Affinities" sheet can start with a header row of task names, which is skipped. Each further
+/// criterion can either follow in a block on the "Affinities" sheet or have a sheet of its own
+/// that starts with the row naming it. If the workbook does not have both sheets, the first sheet
+/// is read as a whole CSV input file instead. Blank rows are skipped.
+pub(super) struct SpreadsheetReader {
+    format: SpreadsheetFormat,
+    // the rows are processed exactly as if they came from a CSV file
+    layout: CsvReader,
+}
+
+impl SpreadsheetReader {
+    /// Create a new `SpreadsheetReader` for the given format
+    pub fn new(format: SpreadsheetFormat) -> SpreadsheetReader {
+        // cells hold numbers rather than text, so the CSV dialect does not apply
+        SpreadsheetReader { format, layout: CsvReader::new(CsvDialect::default()) }
+    }
+}
+
+/// A non-blank row of a sheet, with the sheet's name and the row's number there (counted from 1),
+/// so that problems found in it can be reported where it is in the workbook
+struct SheetRow {
+    sheet: Rc<String>,
+    row: usize,
+    cells: Vec<String>,
+}
+
+/// Collect the rows of a workbook in CSV layout, taking the task rows from the "Tasks" sheet, the
+/// worker rows from the "Affinities" sheet and the blocks of further criteria from any other sheets
+/// that start with a criterion row if both exist, or every row from the first sheet
+fn get_workbook_rows<W, RS>(mut workbook: W) -> Result<Vec<SheetRow>, ReadError>
+    where W: CalamineReader<RS>, W::Error: std::fmt::Display, RS: Read + Seek {
+    let sheet_names = workbook.sheet_names();
+    let has_sheet = |name: &str| sheet_names.iter().any(|sheet| sheet == name);
+    if has_sheet(TASKS_SHEET) && has_sheet(AFFINITIES_SHEET) {
+        let mut rows = get_sheet_rows(&mut workbook, TASKS_SHEET)?;
+        let mut worker_rows = get_sheet_rows(&mut workbook, AFFINITIES_SHEET)?;
+        if let (Some(task_names), Some(header)) = (rows.first(), worker_rows.first()) {
+            if get_names(&header.cells) == get_names(&task_names.cells) {
+                worker_rows.remove(0);
+            }
+        }
+        rows.append(&mut worker_rows);
+
+        // any other sheet that starts with a criterion row holds that criterion's block
+        for name in &sheet_names {
+            if name == TASKS_SHEET || name == AFFINITIES_SHEET {
+                continue;
+            }
+            let mut criterion_rows = get_sheet_rows(&mut workbook, name)?;
+            if criterion_rows.first().is_some_and(|row| has_label(&row.cells, CRITERION_LABEL)) {
+                rows.append(&mut criterion_rows);
+            }
+        }
+        Ok(rows)
+    } else {
+        match sheet_names.first() {
+            Some(name) => get_sheet_rows(&mut workbook, name),
+            None => Ok(Vec::new())
+        }
+    }
+}
+
+/// Get the text of every non-blank row of a sheet, with every row starting from column A
+fn get_sheet_rows<W, RS>(workbook: &mut W, name: &str) -> Result<Vec<SheetRow>, ReadError>
+    where W: CalamineReader<RS>, W::Error: std::fmt::Display, RS: Read + Seek {
+    let range: Range<Data> = workbook.worksheet_range(name)
+        .map_err(|err| ParseError::malformed(SourceLocation::default(),
+                                             format!("Unable to read sheet {}: {}", name, err)))?;
+    let (last_row, last_column) = match range.end() {
+        Some(end) => end,
+        None => return Ok(Vec::new())
+    };
+
+    // the range starts at the first cell in use, so look cells up by their absolute positions
+    let sheet = Rc::new(name.to_string());
+    Ok((0..=last_row)
+        .map(|row| SheetRow {
+            sheet: Rc::clone(&sheet),
+            row: row as usize + 1,
+            cells: (0..=last_column)
+                .map(|column| range.get_value((row, column))
+                    .map(|value| value.to_string())
+                    .unwrap_or_default())
+                .collect()
+        })
+        .filter(|row| row.cells.iter().any(|cell| !cell.trim().is_empty()))
+        .collect())
+}
+
+/// Find the row of the workbook a problem was found in, from the row of the collected rows it was
+/// reported at, counted from 1 as the CSV layout counts them
+fn find_sheet_row<'a>(error: &ParseError, rows: &'a [SheetRow]) -> Option<&'a SheetRow> {
+    error.get_location().line.and_then(|line| rows.get(line.checked_sub(1)?))
+}
+
+/// Get the non-blank names after the first column of a row
+fn get_names(row: &[String]) -> Vec<&str> {
+    row.iter().skip(1).map(|cell| cell.trim()).filter(|cell| !cell.is_empty()).collect()
+}
+
+impl SpreadsheetReader {
+    /// Open the workbook and collect its rows in CSV layout
+    fn get_rows(&self, filename: String) -> Result<Vec<SheetRow>, ReadError> {
+        let f = BufReader::new(File::open(filename)?);
+        let to_parse_error = |err: &dyn std::fmt::Display| ParseError::malformed(
+            SourceLocation::default(), format!("Unable to read spreadsheet: {}", err));
+        match self.format {
+            SpreadsheetFormat::Xlsx => get_workbook_rows(
+                Xlsx::new(f).map_err(|err| to_parse_error(&err))?),
+            SpreadsheetFormat::Ods => get_workbook_rows(
+                Ods::new(f).map_err(|err| to_parse_error(&err))?)
+        }
+    }
+}
+
+impl Reader for SpreadsheetReader {
+    /// Open the workbook and pass its rows to the CSV layout for processing
+    fn read_file(&mut self, filename: String, network: &Network) -> Result<(), ReadError> {
+        let rows = self.get_rows(filename)?;
+        let cells = rows.iter().map(|row| Ok(row.cells.clone()));
+        match self.layout.process_rows(cells, network) {
+            Err(ReadError::Parse(err)) => Err(match find_sheet_row(&err, &rows) {
+                Some(row) => err.in_sheet(&row.sheet, row.row),
+                None => *err
+            }.into()),
+            result => result
+        }
+    }
+
+    fn clone_task_names(&self) -> Vec<Rc<String>> {
+        self.layout.clone_task_names()
+    }
+
+    /// Pass the workbook's rows to the CSV layout, collecting every issue at its sheet and row
+    fn validate_file(&mut self, filename: String, network: &Network)
+        -> std::io::Result<Vec<ValidationIssue>> {
+        let rows = match self.get_rows(filename) {
+            Ok(rows) => rows,
+            Err(ReadError::Parse(err)) =>
+                return Ok(vec![ValidationIssue::from_error(Severity::Error, *err)]),
+            Err(ReadError::Io(err)) => return Err(err)
+        };
+        let cells = rows.iter().map(|row| Ok(row.cells.clone()));
+        Ok(self.layout.validate_rows(cells, network)?.into_iter()
+            .map(|issue| match find_sheet_row(issue.get_error(), &rows) {
+                Some(row) => issue.in_sheet(&row.sheet, row.row),
+                None => issue
+            })
+            .collect())
+    }
+}
+
+/// A cell to write to a sheet
+#[derive(Clone, Debug, PartialEq)]
+enum Cell {
+    Empty,
+    Text(String),
+    Number(f64),
+    /// Text that labels other cells, written in bold
+    Heading(String),
+}
+
+/// A named sheet to write to a workbook
+struct Sheet {
+    name: &'static str,
+    rows: Vec<Vec<Cell>>,
+}
+
+/// A writer for spreadsheet output data. Given a network that contains its min cost max flow, it
+/// writes a "Results" sheet laid out like the CSV output (see `CsvWriter`), with the labels and
+/// task names in bold and the totals as numbers, or with one row per worker for the by-worker
+/// layout. It writes input files as a "Tasks" sheet and an
+/// "Affinities" sheet, as read by `SpreadsheetReader`.
+pub(super) struct SpreadsheetWriter {
+    format: SpreadsheetFormat,
+    task_names: Vec<Rc<String>>,
+    layout: OutputLayout,
+}
+
+impl SpreadsheetWriter {
+    /// Create a new `SpreadsheetWriter` for the given format and layout of its assignments
+    pub fn new(format: SpreadsheetFormat, task_names: Vec<Rc<String>>, layout: OutputLayout)
+        -> SpreadsheetWriter {
+        SpreadsheetWriter { format, task_names, layout }
+    }
+
+    /// Lay out the outputs collected from a Network on the results sheet
+    fn get_results_sheet(&self, outputs: &Network) -> Sheet {
+        let mut rows = vec![vec![Cell::Heading(TotalRow::TotalScore.label().to_string()),
+                                 Cell::Number(outputs.get_total_cost() as f64)]];
+        if let Some(staffing_cost_total) = outputs.get_staffing_cost_total() {
+            rows.push(vec![Cell::Heading(TotalRow::StaffingCostTotal.label().to_string()),
+                           Cell::Number(staffing_cost_total as f64)]);
+        }
+        if let Some(raw_cost_total) = outputs.get_raw_cost_total() {
+            rows.push(vec![Cell::Heading(TotalRow::RawCostTotal.label().to_string()),
+                           Cell::Number(raw_cost_total as f64)]);
+        }
+        if let Some(change_penalty_total) = outputs.get_change_penalty_total() {
+            rows.push(vec![Cell::Heading(TotalRow::ChangePenaltyTotal.label().to_string()),
+                           Cell::Number(change_penalty_total as f64)]);
+        }
+        for (criterion, total) in outputs.get_criterion_totals() {
+            rows.push(vec![Cell::Heading(TotalRow::CriterionTotal.label().to_string()),
+                           Cell::Text(String::clone(criterion.get_name())),
+                           Cell::Number(total as f64)]);
+        }
+        if let Some(task_score_total) = outputs.get_task_score_total() {
+            rows.push(vec![Cell::Heading(TotalRow::WorkerScoreTotal.label().to_string()),
+                           Cell::Number(outputs.get_worker_score_total() as f64)]);
+            rows.push(vec![Cell::Heading(TotalRow::TaskScoreTotal.label().to_string()),
+                           Cell::Number(task_score_total as f64)]);
+        }
+        if let Some(optimality) = outputs.get_optimality() {
+            rows.push(vec![Cell::Heading(TotalRow::Solution.label().to_string()),
+                           Cell::Text(format_optimality(&optimality))]);
+            rows.push(vec![Cell::Heading(TotalRow::LowerBound.label().to_string()),
+                           Cell::Number(optimality.lower_bound as f64)]);
+            rows.push(vec![Cell::Heading(TotalRow::OptimalityGap.label().to_string()),
+                           Cell::Number(optimality.gap as f64)]);
+        }
+
+        let name_rows = |names: Vec<Rc<String>>| names.iter()
+            .map(|name| vec![Cell::Text(String::clone(name))])
+            .collect::<Vec<Vec<Cell>>>();
+        let mut sections = Vec::new();
+        match self.layout {
+            OutputLayout::ByTask => {
+                // one column of assigned workers per task, under the task's name
+                rows.push(self.task_names.iter()
+                    .map(|task| Cell::Heading(String::clone(task)))
+                    .collect());
+                let worker_assignments = outputs.get_worker_assignments();
+                let num_rows = worker_assignments.values().map(Vec::len).max().unwrap_or(0);
+                for row in 0..num_rows {
+                    rows.push(self.task_names.iter()
+                        .map(|task| match worker_assignments.get(task).and_then(|w| w.get(row)) {
+                            Some(worker) => Cell::Text(String::clone(worker)),
+                            None => Cell::Empty
+                        })
+                        .collect());
+                }
+
+                let unassigned = outputs.get_unassigned_workers();
+                if !unassigned.is_empty() {
+                    sections.push((UNASSIGNED_WORKERS_LABEL, name_rows(unassigned)));
+                }
+                let unused = outputs.get_unused_optional_workers();
+                if !unused.is_empty() {
+                    sections.push((UNUSED_OPTIONAL_WORKERS_LABEL, name_rows(unused)));
+                }
+            },
+            OutputLayout::ByWorker => {
+                // one row per worker, leaving the task, cost and regret blank if they have no task
+                let number = |value: Option<f32>| value.map_or(Cell::Empty,
+                                                               |v| Cell::Number(v as f64));
+                let with_raw_costs = has_raw_costs(outputs);
+                rows.push(get_worker_layout_headers(outputs).into_iter()
+                    .map(Cell::Heading)
+                    .collect());
+                for row in get_worker_rows(outputs) {
+                    let (task, cost) = match &row.assignment {
+                        Some((task, cost)) => (Cell::Text(String::clone(task)), Some(*cost)),
+                        None => (Cell::Empty, None)
+                    };
+                    let mut cells = vec![Cell::Text(String::clone(&row.worker))];
+                    cells.extend(row.attributes.iter().map(|value| Cell::Text(value.clone())));
+                    cells.extend([task, number(cost)]);
+                    if with_raw_costs {
+                        cells.push(number(row.raw_cost));
+                    }
+                    cells.extend([number(row.best_cost), number(row.get_regret())]);
+                    rows.push(cells);
+                }
+            }
+        }
+
+        let mut add_section = |label: &str, section_rows: Vec<Vec<Cell>>| {
+            rows.push(Vec::new());
+            rows.push(vec![Cell::Heading(label.to_string())]);
+            rows.extend(section_rows);
+        };
+        for (label, section_rows) in sections {
+            add_section(label, section_rows);
+        }
+        if let Some(blocking_pairs) = outputs.get_blocking_pairs() {
+            add_section(BLOCKING_PAIRS_LABEL, blocking_pairs.iter()
+                .map(|(worker, task)| vec![Cell::Text(String::clone(worker)),
+                                           Cell::Text(String::clone(task))])
+                .collect());
+        }
+        if let Some(moves) = outputs.get_moves().filter(|moves| !moves.is_empty()) {
+            add_section(MOVED_WORKERS_LABEL, moves.into_iter()
+                .map(|change| vec![Cell::Text(String::clone(&change.worker)),
+                                   Cell::Text(String::clone(&change.from)),
+                                   change.to.map_or(Cell::Empty,
+                                                    |task| Cell::Text(String::clone(&task))),
+                                   Cell::Text(change.reason.to_string())])
+                .collect());
+        }
+        if let Some(backups) = outputs.get_backups() {
+            add_section(BACKUP_WORKERS_LABEL, backups.into_iter()
+                .map(|backup| vec![Cell::Text(String::clone(&backup.task)),
+                                   Cell::Number(backup.rank as f64),
+                                   Cell::Text(String::clone(&backup.worker)),
+                                   backup.current_task.map_or(Cell::Empty, |task|
+                                       Cell::Text(String::clone(&task))),
+                                   Cell::Number(backup.cost_increase as f64)])
+                .collect());
+        }
+
+        Sheet { name: RESULTS_SHEET, rows }
+    }
+
+    /// Lay out the problem held in a Network on the tasks and affinities sheets
+    fn get_input_sheets(&self, problem: &Network) -> Vec<Sheet> {
+        let tasks = problem.get_tasks();
+        let workers = problem.get_workers();
+        let attribute_names = problem.get_worker_attribute_names();
+        // the attribute columns are left blank in every row but the task names and the workers'
+        let labelled_row = |label: &str, cells: Vec<Cell>| {
+            let mut row = vec![Cell::Heading(label.to_string())];
+            row.extend(vec![Cell::Empty; attribute_names.len()]);
+            row.extend(cells);
+            row
+        };
+
+        let names_row = |label: &str| {
+            let mut row = vec![Cell::Heading(label.to_string())];
+            row.extend(attribute_names.iter()
+                .map(|name| Cell::Heading(format_attribute_header(name))));
+            row.extend(tasks.iter().map(|task| Cell::Heading(String::clone(task.get_name()))));
+            row
+        };
+        let mut task_rows = vec![
+            names_row("Tasks"),
+            labelled_row("Minimum", tasks.iter()
+                .map(|task| Cell::Number(task.get_min_workers() as f64))
+                .collect()),
+            labelled_row("Maximum", tasks.iter()
+                .map(|task| Cell::Number(task.get_max_workers() as f64))
+                .collect()),
+        ];
+        if tasks.iter().any(|task| task.has_staffing_costs()) {
+            task_rows.push(labelled_row(STAFFING_COSTS_LABEL, tasks.iter()
+                .map(|task| Cell::Text(task.get_staffing_costs().iter()
+                    .map(f32::to_string)
+                    .collect::<Vec<String>>()
+                    .join(" ")))
+                .collect()));
+        }
+        for (label, cells) in get_task_detail_rows(&tasks, &CsvDialect::default()) {
+            task_rows.push(labelled_row(label, cells.into_iter().map(Cell::Text).collect()));
+        }
+
+        // fill in each task's column from a worker's (task index, value) pairs
+        // the workers' attribute values go in their affinity rows, ahead of the tasks' columns
+        let first_column = attribute_names.len() + 1;
+        let worker_row = |worker: &Worker, values: &[(usize, f32)], with_attributes: bool| {
+            let mut row = vec![Cell::Empty; tasks.len() + first_column];
+            row[0] = Cell::Text(String::clone(worker.get_name()));
+            if with_attributes {
+                for (attribute_id, cell) in row[1..first_column].iter_mut().enumerate() {
+                    *cell = Cell::Text(worker.get_attribute(attribute_id).to_string());
+                }
+            }
+            for (task_id, value) in values {
+                row[task_id + first_column] = Cell::Number(*value as f64);
+            }
+            row
+        };
+        // with criteria, the workers' rows hold their values for the first one, and each of the
+        // others follows in a block of its own
+        let criteria = problem.get_criteria();
+        let criterion_row = |criterion: &Criterion| vec![
+            Cell::Heading(CRITERION_LABEL.to_string()),
+            Cell::Text(String::clone(criterion.get_name())),
+            Cell::Number(criterion.get_weight() as f64)];
+        let affinities = |worker: &Worker| if criteria.is_empty() {
+            worker_row(worker, worker.get_raw_affinities(), true)
+        } else {
+            worker_row(worker, worker.get_criterion_values(0), true)
+        };
+        let mut worker_rows = vec![names_row("Workers")];
+        if let Some(criterion) = criteria.first() {
+            worker_rows.push(criterion_row(criterion));
+        }
+        for worker in workers.iter().filter(|worker| !worker.is_optional()) {
+            worker_rows.push(affinities(worker));
+        }
+        if workers.iter().any(|worker| worker.is_optional()) {
+            worker_rows.push(vec![Cell::Heading(OPTIONAL_WORKERS_LABEL.to_string())]);
+            for worker in workers.iter().filter(|worker| worker.is_optional()) {
+                worker_rows.push(affinities(worker));
+            }
+        }
+        for (criterion_id, criterion) in criteria.iter().enumerate().skip(1) {
+            worker_rows.push(criterion_row(criterion));
+            for worker in &workers {
+                worker_rows.push(worker_row(worker, worker.get_criterion_values(criterion_id),
+                                            false));
+            }
+        }
+        if workers.iter().any(|worker| worker.has_task_scores()) {
+            worker_rows.push(vec![Cell::Heading(TASK_PREFERENCES_LABEL.to_string())]);
+            for worker in workers.iter().filter(|worker| worker.has_task_scores()) {
+                worker_rows.push(worker_row(worker, worker.get_task_scores(), false));
+            }
+        }
+        let conflicts = problem.get_conflicts();
+        if !conflicts.is_empty() {
+            worker_rows.push(vec![Cell::Heading(CONFLICTS_LABEL.to_string())]);
+            for (first, second) in conflicts {
+                worker_rows.push(vec![Cell::Text(String::clone(&first)),
+                                      Cell::Text(String::clone(&second))]);
+            }
+        }
+
+        vec![Sheet { name: TASKS_SHEET, rows: task_rows },
+             Sheet { name: AFFINITIES_SHEET, rows: worker_rows }]
+    }
+
+    /// Save the sheets to a workbook in this writer's format
+    fn save(&self, sheets: &[Sheet], filename: String) -> std::io::Result<()> {
+        match self.format {
+            SpreadsheetFormat::Xlsx => xlsx::save(sheets, &filename),
+            SpreadsheetFormat::Ods => ods::save(sheets, &filename)
+        }
+    }
+}
+
+impl Writer for SpreadsheetWriter {
+    /// Create new file or replace existing file, and write the results sheet into it
+    fn write_file(&self, results: &Network, filename: String) -> std::io::Result<()> {
+        self.save(&[self.get_results_sheet(results)], filename)
+    }
+
+    /// Create new file or replace existing file, and write the tasks and affinities sheets into it
+    fn write_input_file(&self, problem: &Network, filename: String) -> std::io::Result<()> {
+        self.save(&self.get_input_sheets(problem), filename)
+    }
+}
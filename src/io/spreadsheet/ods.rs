@@ -0,0 +1,92 @@
+//! Saving sheets to OpenDocument (.ods) spreadsheets. An .ods file is a zip archive holding its
+//! media type, a manifest and the sheets' contents as XML; nothing more is needed for a
+//! spreadsheet of plain values.
+
+use std::fs::File;
+use std::io::Write;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+use crate::io::spreadsheet::{Cell, Sheet};
+
+const MIME_TYPE: &str = "application/vnd.oasis.opendocument.spreadsheet";
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2">
+ <manifest:file-entry manifest:full-path="/" manifest:version="1.2" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/>
+ <manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>
+</manifest:manifest>
+"#;
+
+const CONTENT_START: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" office:version="1.2">
+<office:automatic-styles>
+<style:style style:name="heading" style:family="table-cell"><style:text-properties fo:font-weight="bold"/></style:style>
+</office:automatic-styles>
+<office:body>
+<office:spreadsheet>
+"#;
+
+const CONTENT_END: &str = "</office:spreadsheet>
+</office:body>
+</office:document-content>
+";
+
+/// Save the sheets to an OpenDocument spreadsheet, creating or replacing the file
+pub(super) fn save(sheets: &[Sheet], filename: &str) -> std::io::Result<()> {
+    let mut archive = ZipWriter::new(File::create(filename)?);
+    // the media type must come first and be stored uncompressed so that it can be sniffed
+    archive.start_file("mimetype",
+                       SimpleFileOptions::default().compression_method(CompressionMethod::Stored))?;
+    archive.write_all(MIME_TYPE.as_bytes())?;
+    archive.start_file("META-INF/manifest.xml", SimpleFileOptions::default())?;
+    archive.write_all(MANIFEST.as_bytes())?;
+    archive.start_file("content.xml", SimpleFileOptions::default())?;
+    archive.write_all(get_content(sheets).as_bytes())?;
+    archive.finish()?;
+
+    Ok(())
+}
+
+/// Write the sheets out as the XML of the spreadsheet's contents
+fn get_content(sheets: &[Sheet]) -> String {
+    let mut content = CONTENT_START.to_string();
+    for sheet in sheets {
+        content += &format!("<table:table table:name=\"{}\">\n", escape(sheet.name));
+        for row in &sheet.rows {
+            content += "<table:table-row>";
+            if row.is_empty() {
+                content += "<table:table-cell/>";
+            }
+            for cell in row {
+                content += &match cell {
+                    Cell::Empty => "<table:table-cell/>".to_string(),
+                    Cell::Text(text) => format!(
+                        "<table:table-cell office:value-type=\"string\"><text:p>{}</text:p></table:table-cell>",
+                        escape(text)),
+                    Cell::Number(number) if number.is_finite() => format!(
+                        "<table:table-cell office:value-type=\"float\" office:value=\"{0}\"><text:p>{0}</text:p></table:table-cell>",
+                        number),
+                    Cell::Number(number) => format!(
+                        "<table:table-cell office:value-type=\"string\"><text:p>{}</text:p></table:table-cell>",
+                        number),
+                    Cell::Heading(text) => format!(
+                        "<table:table-cell table:style-name=\"heading\" office:value-type=\"string\"><text:p>{}</text:p></table:table-cell>",
+                        escape(text))
+                };
+            }
+            content += "</table:table-row>\n";
+        }
+        content += "</table:table>\n";
+    }
+    content += CONTENT_END;
+    content
+}
+
+/// Escape the characters that have special meanings in XML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
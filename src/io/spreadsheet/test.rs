@@ -0,0 +1,188 @@
+use std::fs::File;
+use std::io::BufReader;
+use crate::io::spreadsheet::*;
+use crate::status::CurrentStatus;
+use crate::io::TestOutputDir;
+
+/// Read a spreadsheet input file, solve it and check the total cost
+fn read_and_solve(format: SpreadsheetFormat, filename: &str) -> (SpreadsheetReader, Network) {
+    let mut file_reader = SpreadsheetReader::new(format);
+    let network = Network::new();
+    file_reader.read_file(filename.to_string(), &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert!((network.get_total_cost() - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
+    (file_reader, network)
+}
+
+#[test]
+fn test_read_xlsx() {
+    let (_, network) = read_and_solve(SpreadsheetFormat::Xlsx,
+                                      "src/io/spreadsheet/test-data/inputTaskPreferences.xlsx");
+    assert_eq!(network.get_workers().len(), 10);
+    assert!(network.get_workers().iter().all(|worker| worker.has_task_scores()));
+}
+
+#[test]
+fn test_read_ods() {
+    let (_, network) = read_and_solve(SpreadsheetFormat::Ods,
+                                      "src/io/spreadsheet/test-data/inputTaskPreferences.ods");
+    assert_eq!(network.get_workers().len(), 10);
+    assert!(network.get_workers().iter().all(|worker| worker.has_task_scores()));
+}
+
+#[test]
+fn test_read_single_sheet() {
+    let output = TestOutputDir::new("test_read_single_sheet");
+    // without "Tasks" and "Affinities" sheets, the first sheet holds the whole CSV layout
+    let text = |cells: &[&str]| cells.iter()
+        .map(|cell| if cell.is_empty() { Cell::Empty } else { Cell::Text(cell.to_string()) })
+        .collect::<Vec<Cell>>();
+    let sheet = Sheet {
+        name: "Sheet1",
+        rows: vec![text(&["", "Task 1", "Task 2"]),
+                   vec![Cell::Empty, Cell::Number(1.0), Cell::Number(0.0)],
+                   vec![Cell::Empty, Cell::Number(1.0), Cell::Number(1.0)],
+                   vec![Cell::Text("Alice".to_string()), Cell::Number(1.0), Cell::Number(2.5)],
+                   vec![Cell::Text("Bob".to_string()), Cell::Empty, Cell::Number(0.5)]],
+    };
+    xlsx::save(&[sheet], &output.path("singleSheet.xlsx")).unwrap();
+
+    let mut file_reader = SpreadsheetReader::new(SpreadsheetFormat::Xlsx);
+    let network = Network::new();
+    file_reader.read_file(output.path("singleSheet.xlsx"),
+                          &network).unwrap();
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_total_cost(), 1.5);
+}
+
+#[test]
+fn test_read_error_location() {
+    let output = TestOutputDir::new("test_read_error_location");
+    let text = |cells: &[&str]| cells.iter()
+        .map(|cell| if cell.is_empty() { Cell::Empty } else { Cell::Text(cell.to_string()) })
+        .collect::<Vec<Cell>>();
+    let tasks = Sheet {
+        name: TASKS_SHEET,
+        rows: vec![text(&["", "Task 1", "Task 2"]),
+                   text(&["Minimum", "1", "0"]),
+                   text(&["Maximum", "1", "1"])],
+    };
+    // the header row is skipped and the blank row dropped, but the error is still found at the
+    // row and sheet it is on
+    let affinities = Sheet {
+        name: AFFINITIES_SHEET,
+        rows: vec![text(&["", "Task 1", "Task 2"]),
+                   text(&["Alice", "1", "2.5"]),
+                   text(&["", "", ""]),
+                   text(&["Bob", "abc", "0.5"])],
+    };
+    xlsx::save(&[tasks, affinities], &output.path("badValue.xlsx")).unwrap();
+
+    let mut file_reader = SpreadsheetReader::new(SpreadsheetFormat::Xlsx);
+    let network = Network::new();
+    match file_reader.read_file(output.path("badValue.xlsx"), &network) {
+        Err(ReadError::Parse(err)) => {
+            let location = err.get_location();
+            assert_eq!(location.sheet.as_deref(), Some(AFFINITIES_SHEET));
+            assert_eq!((location.line, location.column), (Some(4), Some(2)));
+        },
+        other => panic!("Expected a parse error, found {:?}", other)
+    }
+}
+
+#[test]
+fn test_validate() {
+    let output = TestOutputDir::new("test_validate");
+    let text = |cells: &[&str]| cells.iter()
+        .map(|cell| if cell.is_empty() { Cell::Empty } else { Cell::Text(cell.to_string()) })
+        .collect::<Vec<Cell>>();
+    let tasks = Sheet {
+        name: TASKS_SHEET,
+        rows: vec![text(&["", "Task 1", "Task 2"]),
+                   text(&["Minimum", "1", "x"]),
+                   text(&["Maximum", "1", "1"])],
+    };
+    let affinities = Sheet {
+        name: AFFINITIES_SHEET,
+        rows: vec![text(&["Alice", "1", "2.5"]),
+                   text(&["Bob", "abc", "0.5"])],
+    };
+    xlsx::save(&[tasks, affinities], &output.path("badValues.xlsx")).unwrap();
+
+    // every issue is reported, each at its own sheet and row
+    let mut file_reader = SpreadsheetReader::new(SpreadsheetFormat::Xlsx);
+    let network = Network::new();
+    let issues: Vec<String> = file_reader.validate_file(output.path("badValues.xlsx"), &network)
+        .unwrap().iter()
+        .filter(|issue| issue.is_error())
+        .map(|issue| issue.to_string())
+        .collect();
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+    assert!(issues[0].starts_with("Error in sheet Tasks, row 2, column C: "), "{:?}", issues);
+    assert!(issues[1].starts_with("Error in sheet Affinities, row 2, column B: "), "{:?}", issues);
+}
+
+#[test]
+fn test_write() {
+    let output = TestOutputDir::new("test_write");
+    for (format, filename) in
+        [(SpreadsheetFormat::Xlsx, &output.path("testOutput.xlsx")),
+         (SpreadsheetFormat::Ods, &output.path("testOutput.ods"))] {
+        let (file_reader, network) = read_and_solve(
+            SpreadsheetFormat::Xlsx, "src/io/spreadsheet/test-data/inputTaskPreferences.xlsx");
+        let file_writer = SpreadsheetWriter::new(format, file_reader.clone_task_names(),
+                                                 OutputLayout::ByTask);
+        file_writer.write_file(&network, filename.to_string()).unwrap();
+
+        let f = BufReader::new(File::open(filename).unwrap());
+        let rows = match format {
+            SpreadsheetFormat::Xlsx => get_sheet_rows(&mut Xlsx::new(f).unwrap(), RESULTS_SHEET),
+            SpreadsheetFormat::Ods => get_sheet_rows(&mut Ods::new(f).unwrap(), RESULTS_SHEET)
+        }.unwrap();
+        assert_eq!(rows[0].cells[..2], ["Total score:".to_string(), "12.5".to_string()]);
+        assert_eq!(rows[3].cells[0], "Track 1");
+    }
+}
+
+#[test]
+fn test_write_by_worker() {
+    let output = TestOutputDir::new("test_write_by_worker");
+    let (file_reader, network) = read_and_solve(
+        SpreadsheetFormat::Xlsx, "src/io/spreadsheet/test-data/inputTaskPreferences.xlsx");
+    let file_writer = SpreadsheetWriter::new(SpreadsheetFormat::Xlsx,
+                                             file_reader.clone_task_names(),
+                                             OutputLayout::ByWorker);
+    let sheet = file_writer.get_results_sheet(&network);
+    let header = sheet.rows.iter()
+        .position(|row| matches!(row.first(), Some(Cell::Heading(text)) if text == "Worker"))
+        .unwrap();
+    let worker_rows = &sheet.rows[header + 1..header + 11];
+    assert!(worker_rows.iter().all(|row| row.len() == 5));
+    let regret_total: f64 = worker_rows.iter()
+        .map(|row| match row[4] {
+            Cell::Number(regret) => regret,
+            _ => panic!("Every worker should have a regret")
+        })
+        .sum();
+    assert!((regret_total - 3.0).abs() < 1e-6);
+    file_writer.write_file(&network, output.path("testByWorker.xlsx"))
+        .unwrap();
+}
+
+#[test]
+fn test_write_input() {
+    let output = TestOutputDir::new("test_write_input");
+    for (format, filename) in
+        [(SpreadsheetFormat::Xlsx, &output.path("testInput.xlsx")),
+         (SpreadsheetFormat::Ods, &output.path("testInput.ods"))] {
+        let (file_reader, network) = read_and_solve(
+            SpreadsheetFormat::Ods, "src/io/spreadsheet/test-data/inputTaskPreferences.ods");
+        let file_writer = SpreadsheetWriter::new(format, file_reader.clone_task_names(),
+                                                 OutputLayout::ByTask);
+        file_writer.write_input_file(&network, filename.to_string()).unwrap();
+
+        let (round_trip_reader, round_trip) = read_and_solve(format, filename);
+        assert_eq!(round_trip_reader.clone_task_names(), file_reader.clone_task_names());
+        assert_eq!(round_trip.get_workers().len(), network.get_workers().len());
+    }
+}
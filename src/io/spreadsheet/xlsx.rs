@@ -0,0 +1,37 @@
+//! Saving sheets to Excel (.xlsx) workbooks.
+
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use crate::io::spreadsheet::{Cell, Sheet};
+
+/// Save the sheets to an Excel workbook, creating or replacing the file
+pub(super) fn save(sheets: &[Sheet], filename: &str) -> std::io::Result<()> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    for sheet in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(sheet.name).map_err(to_io_error)?;
+        for (row, cells) in sheet.rows.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                let (row, column) = (row as u32, column as u16);
+                match cell {
+                    Cell::Empty => continue,
+                    Cell::Text(text) => worksheet.write_string(row, column, text),
+                    Cell::Number(number) => worksheet.write_number(row, column, *number),
+                    Cell::Heading(text) =>
+                        worksheet.write_string_with_format(row, column, text, &bold)
+                }.map_err(to_io_error)?;
+            }
+        }
+        worksheet.autofit();
+    }
+
+    workbook.save(filename).map_err(to_io_error)
+}
+
+/// Convert an error from the workbook into an I/O error, keeping the original if it was one
+fn to_io_error(err: XlsxError) -> std::io::Error {
+    match err {
+        XlsxError::IoError(e) => e,
+        other => std::io::Error::other(other.to_string())
+    }
+}
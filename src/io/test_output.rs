@@ -0,0 +1,32 @@
+//! A scratch directory for the files a test writes, so that tests do not depend on directories in
+//! the source tree or on files written by other tests.
+
+use std::path::PathBuf;
+
+/// A directory for one test's output files, created empty in the system's temporary directory and
+/// removed again when it is dropped at the end of the test
+pub(crate) struct TestOutputDir(PathBuf);
+
+impl TestOutputDir {
+    /// Create an empty directory named after the test, and the process so that concurrent runs of
+    /// the tests do not share it
+    pub fn new(test_name: &str) -> TestOutputDir {
+        let path = std::env::temp_dir()
+            .join(format!("assignment-solver-{}-{}", test_name, std::process::id()));
+        // a directory left behind by an earlier run that was killed may still hold its files
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        TestOutputDir(path)
+    }
+
+    /// Get the path of a file in the directory
+    pub fn path(&self, filename: &str) -> String {
+        self.0.join(filename).to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for TestOutputDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
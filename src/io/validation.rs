@@ -0,0 +1,93 @@
+//! Issues found while validating an input file, each with the row and column of the file where it
+//! was found if the reader can tell.
+
+use std::fmt::{Display, Formatter};
+use crate::io::error::{ParseError, SourceLocation};
+use crate::network::{InputProblem, Network, Severity};
+
+/// A problem with an input file, located by its row and column (both counted from 1) where known
+#[derive(Clone, Debug)]
+pub(crate) struct ValidationIssue {
+    severity: Severity,
+    error: ParseError,
+}
+
+impl ValidationIssue {
+    /// Create an issue at the given row and column, if known
+    pub fn new(severity: Severity, row: Option<usize>, column: Option<usize>, message: String)
+        -> ValidationIssue {
+        ValidationIssue::from_error(severity, ParseError::malformed(
+            SourceLocation::at(row, column), message))
+    }
+
+    /// Create an issue from a parse error, which gives its location
+    pub fn from_error(severity: Severity, error: ParseError) -> ValidationIssue {
+        ValidationIssue { severity, error }
+    }
+
+    /// Check whether the issue stops the file from being read or solved
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Get the issue as a parse error, e.g. to fail reading with it
+    pub fn get_error(&self) -> &ParseError {
+        &self.error
+    }
+
+    /// Move the issue from a row of the collected rows of a workbook to the given sheet and row
+    pub fn in_sheet(self, sheet: &str, row: usize) -> ValidationIssue {
+        ValidationIssue { error: self.error.in_sheet(sheet, row), ..self }
+    }
+}
+
+impl From<InputProblem> for ValidationIssue {
+    fn from(problem: InputProblem) -> Self {
+        ValidationIssue::new(problem.severity, None, None, problem.message)
+    }
+}
+
+impl Display for ValidationIssue {
+    /// Describe the issue on one line, giving columns as spreadsheet letters (e.g. "row 4,
+    /// column C", or "sheet Tasks, row 4, column C" in a workbook)
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning"
+        };
+        let location = self.error.get_location();
+        let mut place = Vec::new();
+        if let Some(sheet) = &location.sheet {
+            place.push(format!("sheet {}", sheet));
+        }
+        if let Some(row) = location.line {
+            place.push(format!("row {}", row));
+            if let Some(column) = location.column {
+                place.push(format!("column {}", get_column_letters(column)));
+            }
+        }
+        let message = self.error.get_message();
+        if place.is_empty() {
+            write!(f, "{}: {}", severity, message)
+        } else {
+            write!(f, "{} in {}: {}", severity, place.join(", "), message)
+        }
+    }
+}
+
+/// Get the issues with a network that can be found without solving it
+pub(crate) fn get_network_issues(network: &Network) -> Vec<ValidationIssue> {
+    network.find_problems().into_iter().map(ValidationIssue::from).collect()
+}
+
+/// Get the letters a spreadsheet uses for a column counted from 1, e.g. "A" for 1 and "AA" for 27
+fn get_column_letters(column: usize) -> String {
+    let mut letters = Vec::new();
+    let mut remaining = column;
+    while remaining > 0 {
+        remaining -= 1;
+        letters.push((b'A' + (remaining % 26) as u8) as char);
+        remaining /= 26;
+    }
+    letters.iter().rev().collect()
+}
@@ -0,0 +1,16 @@
+//! # Assignment Solver
+//!
+//! A min cost/max flow network solver for the assignment problem. The `network` module holds the
+//! assignment model along with the general min cost flow solver it is built on, which can be used
+//! on its own for other min cost flow problems such as transportation problems. The `status`
+//! module reports the solver's progress. The `cli` module provides the headless command-line
+//! interface used by the `assignment-solver-cli` binary, and the `ui` module, built only with the
+//! `gui` feature (on by default), the graphical interface used by the `assignment-solver` binary.
+
+pub mod cli;
+pub mod network;
+pub mod status;
+mod io;
+mod solver;
+#[cfg(feature = "gui")]
+pub mod ui;
@@ -1,11 +1,10 @@
 #![windows_subsystem = "windows"]
 
 use std::sync::Arc;
-mod network;
-mod io;
-mod ui;
+use assignment_solver::status::CurrentStatus;
+use assignment_solver::ui;
 
 fn main() {
-    let cur_status = Arc::new(ui::CurrentStatus::new());
+    let cur_status = Arc::new(CurrentStatus::new());
     ui::launch_ui(cur_status);
 }
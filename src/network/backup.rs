@@ -0,0 +1,16 @@
+use std::rc::Rc;
+
+/// A worker who could stand in at a task they were not assigned to, e.g. if one of its workers
+/// calls in sick
+#[derive(Clone, Debug, PartialEq)]
+pub struct Backup {
+    pub task: Rc<String>,
+    /// The backup's place in the task's list, 1 for the cheapest
+    pub rank: usize,
+    pub worker: Rc<String>,
+    /// The task the worker was assigned to, if any
+    pub current_task: Option<Rc<String>>,
+    /// How much the total cost rises when the worker is moved to the task, including the cost of
+    /// any other moves needed to keep every task's minimum and maximum
+    pub cost_increase: f32,
+}
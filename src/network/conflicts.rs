@@ -0,0 +1,181 @@
+//! Keeping apart workers who must not share a task (e.g. because their shifts overlap). A flow
+//! network cannot express such a rule, so the flow solution is repaired by a depth-first branch
+//! and bound: whenever two workers in conflict share a task, the search tries both ways of
+//! separating them, by barring one or the other from that task, and solves the flow network again
+//! for each. A branch is abandoned once its flow costs at least as much as the best conflict-free
+//! solution found so far, as barring more tasks can only raise the cost. The search ends when no
+//! branch is left, proving the best solution optimal, or after a fixed number of flows have been
+//! solved, in which case the cheapest flow among the branches left over bounds how much better a
+//! solution could be.
+
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use crate::network::{FeasibilityError, Network, UnitSolution};
+use crate::status::StatusTracker;
+
+/// How far the total cost may be above the lower bound, relative to the cost, for the solution to
+/// still count as optimal, allowing for rounding in the costs
+pub(super) const GAP_TOLERANCE: f32 = 1e-5;
+
+/// The most flow networks the search solves before settling for the best solution found so far
+const MAX_SEARCH_NODES: usize = 1000;
+
+/// How close a solution is known to be to the cheapest possible one
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Optimality {
+    /// Whether no solution can cost less, or the solution was only found heuristically
+    pub proven_optimal: bool,
+    /// A total cost that no solution can beat
+    pub lower_bound: f32,
+    /// The total cost less the lower bound: the most any solution could save
+    pub gap: f32,
+    /// What kept the solver from proving the solution optimal, if it is not
+    pub limit: Option<HeuristicLimit>,
+}
+
+impl Optimality {
+    /// Create a new Optimality for a solution with the given total cost, which the given limit
+    /// may have kept from the lower bound
+    pub(super) fn new(cost: f32, lower_bound: f32, limit: HeuristicLimit) -> Optimality {
+        let lower_bound = lower_bound.min(cost);
+        let gap = cost - lower_bound;
+        let proven_optimal = gap <= GAP_TOLERANCE * cost.abs().max(1.0);
+        Optimality {
+            proven_optimal,
+            lower_bound,
+            gap,
+            limit: (!proven_optimal).then_some(limit),
+        }
+    }
+}
+
+/// The shortcuts that can keep a solution from being proven optimal
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HeuristicLimit {
+    /// Each group that the flow split was held to its cheapest task in turn, and the choice was
+    /// never revisited, so placing the groups together may have missed a cheaper solution
+    GroupPlacement,
+    /// The branch and bound search for keeping conflicting workers apart stopped after solving
+    /// its maximum number of flow networks
+    SearchLimit,
+}
+
+impl Display for HeuristicLimit {
+    /// Describe the limit in words, for the output
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let limit = match self {
+            HeuristicLimit::GroupPlacement => "groups placed one at a time",
+            HeuristicLimit::SearchLimit => "search limit reached",
+        };
+        write!(f, "{}", limit)
+    }
+}
+
+impl Network {
+    /// Declare that the two named workers must not be assigned to the same task
+    pub fn add_conflict(&self, first: &Rc<String>, second: &Rc<String>) {
+        let worker_ids = self.worker_ids.borrow();
+        let get_id = |name: &Rc<String>| *worker_ids.get(name)
+            .unwrap_or_else(|| panic!("Unknown worker {} in conflict", name));
+        self.conflicts.borrow_mut().push((get_id(first), get_id(second)));
+    }
+
+    /// Get the names of every pair of workers declared to be in conflict with `add_conflict`, in
+    /// the order they were declared
+    pub fn get_conflicts(&self) -> Vec<(Rc<String>, Rc<String>)> {
+        let workers = self.workers.borrow();
+        self.conflicts.borrow().iter()
+            .map(|(first, second)| (Rc::clone(workers[*first].get_name()),
+                                    Rc::clone(workers[*second].get_name())))
+            .collect()
+    }
+
+    /// Get how close the solution is known to be to the cheapest possible one, if it had to keep
+    /// groups together or conflicting workers apart. Otherwise, the min cost flow is optimal, and
+    /// None is returned, as it is after finding a stable matching.
+    pub fn get_optimality(&self) -> Option<Optimality> {
+        self.optimality.get()
+    }
+
+    /// Assign the units of workers to tasks so that no two workers in conflict share a task, by
+    /// branch and bound over the tasks each unit may be assigned to
+    pub(super) fn find_conflict_free(&self, units: &[Vec<usize>],
+                                     unit_costs: Vec<Vec<(usize, f32)>>,
+                                     status_tracker: &dyn StatusTracker,
+                                     num_augmentations: &Cell<usize>)
+        -> Result<(UnitSolution, Optimality), FeasibilityError> {
+        let mut worker_units = vec![0; self.workers.borrow().len()];
+        for (unit_id, unit) in units.iter().enumerate() {
+            for worker_id in unit {
+                worker_units[*worker_id] = unit_id;
+            }
+        }
+        let unit_conflicts: Vec<(usize, usize)> = self.conflicts.borrow().iter()
+            .map(|(first, second)| (worker_units[*first], worker_units[*second]))
+            .collect();
+        let unable = || FeasibilityError {
+            message: "Unable to keep conflicting workers apart!".to_string()
+        };
+        if unit_conflicts.iter().any(|(first, second)| first == second) {
+            return Err(unable());
+        }
+
+        // each node is the tasks every unit may still be assigned to, with a lower bound on the
+        // cost of any solution in its branch
+        let mut nodes = vec![(unit_costs, f32::NEG_INFINITY)];
+        let mut best: Option<UnitSolution> = None;
+        let mut unresolved_bounds = Vec::new();
+        let mut num_searched = 0;
+        let mut limit = HeuristicLimit::GroupPlacement;
+        let beats_best = |bound: f32, best: &Option<UnitSolution>| best.as_ref()
+            .is_none_or(|best| bound < best.get_cost() - GAP_TOLERANCE * best.get_cost().abs());
+        while let Some((unit_costs, parent_bound)) = nodes.pop() {
+            if !beats_best(parent_bound, &best) {
+                continue;
+            }
+            if num_searched == MAX_SEARCH_NODES {
+                unresolved_bounds.push(parent_bound);
+                limit = HeuristicLimit::SearchLimit;
+                continue;
+            }
+            num_searched += 1;
+            let Ok(solution) = self.solve_units(units, unit_costs, status_tracker,
+                                                num_augmentations) else {
+                continue;
+            };
+            let bound = parent_bound.max(solution.lower_bound);
+            if !beats_best(bound, &best) {
+                continue;
+            }
+            let unit_tasks = solution.get_unit_tasks()?;
+            let violated = unit_conflicts.iter()
+                .find(|(first, second)| unit_tasks[*first].is_some()
+                    && unit_tasks[*first] == unit_tasks[*second]);
+            match violated {
+                None => {
+                    // keeping groups together may have missed a cheaper solution in this branch
+                    if solution.get_cost() > bound {
+                        unresolved_bounds.push(bound);
+                    }
+                    if beats_best(solution.get_cost(), &best) {
+                        best = Some(solution);
+                    }
+                },
+                Some((first, second)) => {
+                    let task_id = unit_tasks[*first].unwrap();
+                    for unit_id in [*second, *first] {
+                        let mut child_costs = solution.unit_costs.clone();
+                        child_costs[unit_id].retain(|(other_id, _)| *other_id != task_id);
+                        nodes.push((child_costs, bound));
+                    }
+                }
+            }
+        }
+
+        let best = best.ok_or_else(unable)?;
+        let lower_bound = unresolved_bounds.into_iter().fold(best.get_cost(), f32::min);
+        let optimality = Optimality::new(best.get_cost(), lower_bound, limit);
+        Ok((best, optimality))
+    }
+}
@@ -0,0 +1,68 @@
+//! Ways to put every worker's costs on the same scale before solving. Workers who give every task
+//! a high cost would otherwise count for more in the total cost than workers who give every task a
+//! low one, even when they like their tasks just as much.
+
+/// How to rescale each worker's costs, using only the costs of the tasks they can perform. Every
+/// choice keeps the order of a worker's costs, so their preferences are unchanged.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum CostNormalization {
+    /// Use the costs as given
+    #[default]
+    None,
+    /// Subtract the mean of the worker's costs and divide by their standard deviation
+    ZScore,
+    /// Rescale the worker's costs so the lowest is 0 and the highest is 1
+    MinMax,
+    /// Replace each cost by its rank among the worker's costs, 1 for the lowest, with tied costs
+    /// sharing the mean of their ranks
+    Rank,
+    /// Scale the worker's costs so they add up to the given total. Costs that add up to 0 or less
+    /// cannot be scaled without changing their order, so they are left as given.
+    SumTo(f32),
+}
+
+impl CostNormalization {
+    /// Rescale one worker's costs, returning the new costs in the same order. Costs that are all
+    /// the same are rescaled to 0 by the z-score and min-max choices, as there is no spread to
+    /// rescale by.
+    pub fn normalize(&self, costs: &[f32]) -> Vec<f32> {
+        if costs.is_empty() {
+            return Vec::new();
+        }
+        let count = costs.len() as f32;
+        match self {
+            CostNormalization::None => costs.to_vec(),
+            CostNormalization::ZScore => {
+                let mean = costs.iter().sum::<f32>() / count;
+                let variance = costs.iter().map(|cost| (cost - mean).powi(2)).sum::<f32>() / count;
+                let deviation = variance.sqrt();
+                costs.iter()
+                    .map(|cost| if deviation > 0.0 { (cost - mean) / deviation } else { 0.0 })
+                    .collect()
+            },
+            CostNormalization::MinMax => {
+                let min = costs.iter().copied().fold(f32::INFINITY, f32::min);
+                let max = costs.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                costs.iter()
+                    .map(|cost| if max > min { (cost - min) / (max - min) } else { 0.0 })
+                    .collect()
+            },
+            CostNormalization::Rank => costs.iter()
+                .map(|cost| {
+                    // ties share the mean of the ranks they span
+                    let below = costs.iter().filter(|other| *other < cost).count() as f32;
+                    let tied = costs.iter().filter(|other| *other == cost).count() as f32;
+                    below + (tied + 1.0) / 2.0
+                })
+                .collect(),
+            CostNormalization::SumTo(total) => {
+                let sum: f32 = costs.iter().sum();
+                if sum > 0.0 {
+                    costs.iter().map(|cost| cost * total / sum).collect()
+                } else {
+                    costs.to_vec()
+                }
+            }
+        }
+    }
+}
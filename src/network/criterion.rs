@@ -0,0 +1,27 @@
+use std::rc::Rc;
+
+/// One of several named measures (e.g. the worker's preference, their commute or their need for
+/// training) that are weighed together into the cost of assigning a worker to a task. Each
+/// criterion's values are multiplied by its weight and added up to give the cost the solver uses.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Criterion {
+    name: Rc<String>,
+    weight: f32,
+}
+
+impl Criterion {
+    /// Create a new Criterion
+    pub fn new(name: Rc<String>, weight: f32) -> Criterion {
+        Criterion { name, weight }
+    }
+
+    /// Get the criterion's name
+    pub fn get_name(&self) -> &Rc<String> {
+        &self.name
+    }
+
+    /// Get the weight the criterion's values are multiplied by
+    pub fn get_weight(&self) -> f32 {
+        self.weight
+    }
+}
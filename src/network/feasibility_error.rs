@@ -14,3 +14,5 @@ impl fmt::Display for FeasibilityError {
         write!(f, "{}", self.message)
     }
 }
+
+impl std::error::Error for FeasibilityError {}
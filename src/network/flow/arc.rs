@@ -0,0 +1,130 @@
+use std::cell::Cell;
+use std::fmt;
+
+/// An arc that connects two nodes in the network.
+/// Each arc tracks the ID numbers of the nodes where it starts and ends, the cost associated with
+/// pushing a single unit of flow down the arc, the lower and upper bounds on flow that must/can be
+/// pushed down the arc, and the current amount of flow down the arc. The residual network is not
+/// stored separately: an arc can carry more flow forward while its flow is below the upper bound,
+/// and can have flow pushed back (at the negated cost) while its flow is above the lower bound.
+#[derive(Debug)]
+pub(super) struct Arc {
+    start_node: usize,
+    end_node: usize,
+    cost: f32,
+    min_flow: usize,
+    max_flow: usize,
+    current_flow: Cell<usize>,
+}
+
+impl Arc {
+    /// Create a new Arc
+    pub fn new(start_node_id: usize, end_node_id: usize, cost: f32, min_flow: usize,
+               max_flow: usize) -> Arc {
+        Arc { start_node: start_node_id, end_node: end_node_id, cost, min_flow, max_flow,
+              current_flow: Cell::new(0) }
+    }
+
+    /// Set the flow this arc carries before augmentation starts. Arcs with negative cost start
+    /// saturated so that the residual network contains no negative cycles; all others start at
+    /// their lower bound.
+    pub fn reset_flow(&self) -> usize {
+        let initial_flow = if self.cost < 0.0 { self.max_flow } else { self.min_flow };
+        self.current_flow.set(initial_flow);
+        initial_flow
+    }
+
+    /// Amount of flow that can still be pushed through this arc when leaving the given node: spare
+    /// capacity when leaving the start node, or flow above the lower bound when leaving the end
+    /// node (i.e. pushing flow back along the arc).
+    pub fn get_residual_capacity(&self, from_node_id: usize) -> usize {
+        #[cfg(feature = "profiling")]
+        {
+            puffin::profile_function!();
+        }
+
+        if from_node_id == self.start_node {
+            self.max_flow - self.current_flow.get()
+        } else {
+            self.current_flow.get() - self.min_flow
+        }
+    }
+
+    /// Cost of pushing one unit of flow through this arc when leaving the given node. Pushing flow
+    /// back along the arc refunds its cost, so the residual cost is negated.
+    pub fn get_residual_cost(&self, from_node_id: usize) -> f32 {
+        if from_node_id == self.start_node {
+            self.cost
+        } else {
+            -self.cost
+        }
+    }
+
+    /// Get the ID of the node at the other end of this arc from the given node
+    pub fn get_opposite_node_id(&self, node_id: usize) -> usize {
+        if node_id == self.start_node {
+            self.end_node
+        } else {
+            self.start_node
+        }
+    }
+
+    /// Push flow through this arc when leaving the given node, either adding flow in the arc's
+    /// direction or cancelling existing flow if leaving its end node.
+    pub fn push_flow(&self, from_node_id: usize, amount: usize) {
+        #[cfg(feature = "profiling")]
+        {
+            puffin::profile_function!();
+        }
+
+        if from_node_id == self.start_node {
+            self.current_flow.set(self.current_flow.get() + amount);
+        } else {
+            self.current_flow.set(self.current_flow.get() - amount);
+        }
+    }
+
+    /// Get the arc's cost
+    pub fn get_cost(&self) -> f32 {
+        self.cost
+    }
+
+    /// Get the arc's start node id
+    pub fn get_start_node_id(&self) -> usize {
+        self.start_node
+    }
+
+    /// Get the arc's end node id
+    pub fn get_end_node_id(&self) -> usize {
+        self.end_node
+    }
+
+    /// Change the arc's flow bounds, leaving its flow as it is
+    pub fn set_bounds(&mut self, min_flow: usize, max_flow: usize) {
+        self.min_flow = min_flow;
+        self.max_flow = max_flow;
+    }
+
+    /// Get the arc's lower flow bound
+    pub fn get_min_flow(&self) -> usize {
+        self.min_flow
+    }
+
+    /// Get the arc's upper flow bound
+    pub fn get_max_flow(&self) -> usize {
+        self.max_flow
+    }
+
+    /// Get the flow currently carried by the arc
+    pub fn get_flow(&self) -> usize {
+        self.current_flow.get()
+    }
+}
+
+impl fmt::Display for Arc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "start: {} end: {} cost: {} min: {} max: {} flow: {}",
+               self.start_node, self.end_node, self.cost,
+               self.min_flow, self.max_flow, self.current_flow.get())
+    }
+}
@@ -0,0 +1,38 @@
+//! Error type for the flow solver: a node or arc that is not part of the network, a problem with
+//! no feasible flow, or a residual network that the solver cannot search because it holds a cycle
+//! of negative cost.
+
+use std::fmt;
+use crate::network::feasibility_error::FeasibilityError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlowError {
+    /// A node ID that does not belong to the network it was passed to
+    UnknownNode,
+    /// An arc ID that does not belong to the network it was passed to
+    UnknownArc,
+    /// No flow meets every supply and demand within the arcs' bounds, for the reason given
+    Infeasible(String),
+    /// The residual network has a cycle of negative cost, e.g. from rounding in fractional
+    /// costs, so shortest paths are not defined
+    NegativeCycle,
+}
+
+impl fmt::Display for FlowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            FlowError::UnknownNode => write!(f, "Node is not part of this network!"),
+            FlowError::UnknownArc => write!(f, "Arc is not part of this network!"),
+            FlowError::Infeasible(message) => write!(f, "{}", message),
+            FlowError::NegativeCycle => write!(f, "Residual network has a negative cycle!"),
+        }
+    }
+}
+
+impl std::error::Error for FlowError {}
+
+impl From<FlowError> for FeasibilityError {
+    fn from(err: FlowError) -> FeasibilityError {
+        FeasibilityError { message: err.to_string() }
+    }
+}
@@ -0,0 +1,431 @@
+//! # Flow
+//!
+//! A general-purpose min cost flow solver. A `FlowNetwork` is built by adding nodes, each with a
+//! supply of flow (positive), a demand for flow (negative), or neither (zero), and then connecting
+//! them with directed arcs. Every arc has a cost per unit of flow and lower and upper bounds on the
+//! amount of flow it carries. Solving the network finds the cheapest flow that meets every supply
+//! and demand while respecting every arc's bounds, or reports that no such flow exists.
+//!
+//! For example, a transportation problem with two warehouses and two stores:
+//!
+//! ```
+//! use assignment_solver::network::flow::{FlowError, FlowNetwork};
+//!
+//! # fn main() -> Result<(), FlowError> {
+//! let network = FlowNetwork::new();
+//! let north = network.add_node(3);
+//! let south = network.add_node(2);
+//! let downtown = network.add_node(-4);
+//! let airport = network.add_node(-1);
+//! let north_downtown = network.add_arc(north, downtown, 1.0, 0, 5)?;
+//! let north_airport = network.add_arc(north, airport, 4.0, 0, 5)?;
+//! let south_downtown = network.add_arc(south, downtown, 3.0, 0, 5)?;
+//! let south_airport = network.add_arc(south, airport, 1.0, 0, 5)?;
+//!
+//! network.solve()?;
+//! assert_eq!(network.get_flow(north_downtown)?, 3);
+//! assert_eq!(network.get_flow(north_airport)?, 0);
+//! assert_eq!(network.get_flow(south_downtown)?, 1);
+//! assert_eq!(network.get_flow(south_airport)?, 1);
+//! assert_eq!(network.get_total_cost(), 7.0);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The solver uses successive shortest paths: it repeatedly finds the cheapest path in the residual
+//! network from a node with unsent supply to a node with unmet demand and pushes as much flow down
+//! that path as it can. Lower bounds are handled by starting each arc at its lower bound, and arcs
+//! with negative cost start saturated so that the residual network never contains a negative cycle.
+//! Should rounding in fractional costs create one anyway, solving returns an error rather than
+//! looping.
+
+mod node;
+mod arc;
+mod flow_error;
+#[cfg(test)]
+mod test;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+pub use crate::network::flow::flow_error::FlowError;
+
+/// Identifies a node within the `FlowNetwork` that created it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// Identifies an arc within the `FlowNetwork` that created it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArcId(usize);
+
+/// A FlowNetwork is a collection of nodes with supplies and demands, and the arcs that connect
+/// those nodes.
+pub struct FlowNetwork {
+    nodes: RefCell<Vec<node::Node>>,
+    arcs: RefCell<Vec<arc::Arc>>,
+    num_augmentations: Cell<usize>,
+}
+
+impl FlowNetwork {
+    /// Create a new, empty FlowNetwork.
+    pub fn new() -> FlowNetwork {
+        // DO NOT allow the vectors stored in self.nodes and self.arcs to be reordered. Indices are
+        // used in unchecked slicing and therefore must be correct to avoid out-of-bounds accesses.
+        FlowNetwork {
+            nodes: RefCell::new(Vec::new()),
+            arcs: RefCell::new(Vec::new()),
+            num_augmentations: Cell::new(0),
+        }
+    }
+
+    /// Add a node to the network. A positive supply is flow that must leave the node, a negative
+    /// supply is a demand for flow that must arrive at the node, and a node with zero supply only
+    /// passes flow along.
+    pub fn add_node(&self, supply: i64) -> NodeId {
+        let mut nodes = self.nodes.borrow_mut();
+        let node_id = nodes.len();
+        nodes.push(node::Node::new(supply));
+        NodeId(node_id)
+    }
+
+    /// Change the supply of an existing node.
+    pub fn set_supply(&self, node: NodeId, supply: i64) -> Result<(), FlowError> {
+        self.nodes.borrow().get(node.0).ok_or(FlowError::UnknownNode)?.set_supply(supply);
+        Ok(())
+    }
+
+    /// Get the supply of an existing node.
+    pub fn get_supply(&self, node: NodeId) -> Result<i64, FlowError> {
+        Ok(self.nodes.borrow().get(node.0).ok_or(FlowError::UnknownNode)?.get_supply())
+    }
+
+    /// Add a directed arc from `start` to `end` that costs `cost` per unit of flow and must carry
+    /// at least `min_flow` and at most `max_flow` units. Arcs with negative cost must have a finite
+    /// `max_flow` so that the problem is bounded. Fails if either node does not belong to this
+    /// network.
+    pub fn add_arc(&self, start: NodeId, end: NodeId, cost: f32, min_flow: usize,
+                   max_flow: usize) -> Result<ArcId, FlowError> {
+        let nodes = self.nodes.borrow();
+        if start.0 >= nodes.len() || end.0 >= nodes.len() {
+            return Err(FlowError::UnknownNode);
+        }
+
+        let mut arcs = self.arcs.borrow_mut();
+        let arc_id = arcs.len();
+        arcs.push(arc::Arc::new(start.0, end.0, cost, min_flow, max_flow));
+        nodes[start.0].add_connection(arc_id);
+        if start != end {
+            nodes[end.0].add_connection(arc_id);
+        }
+        Ok(ArcId(arc_id))
+    }
+
+    /// Change the bounds of an existing arc without changing the flow it carries, e.g. to release
+    /// an arc that held a solved flow in place so that the residual network allows the flow to
+    /// be rerouted. Fails if the arc's flow is outside the new bounds, or if the arc does not
+    /// belong to this network.
+    pub fn set_bounds(&self, arc: ArcId, min_flow: usize, max_flow: usize)
+        -> Result<(), FlowError> {
+        let mut arcs = self.arcs.borrow_mut();
+        let arc = arcs.get_mut(arc.0).ok_or(FlowError::UnknownArc)?;
+        let flow = arc.get_flow();
+        if flow < min_flow || flow > max_flow {
+            return Err(FlowError::Infeasible(format!(
+                "Arc carries {} units of flow, outside the bounds {} to {}!", flow, min_flow,
+                max_flow)));
+        }
+        arc.set_bounds(min_flow, max_flow);
+        Ok(())
+    }
+
+    /// Get the number of nodes in the network.
+    pub fn get_num_nodes(&self) -> usize {
+        self.nodes.borrow().len()
+    }
+
+    /// Get the number of arcs in the network.
+    pub fn get_num_arcs(&self) -> usize {
+        self.arcs.borrow().len()
+    }
+
+    /// Find a min cost flow for the network. On success, the flow on each arc can be read with
+    /// `get_flow`; on failure, the error explains why no feasible flow exists.
+    pub fn solve(&self) -> Result<(), FlowError> {
+        self.solve_with_progress(|_| {})
+    }
+
+    /// Find a min cost flow for the network, calling `on_progress` with the fraction of supply that
+    /// has been sent each time flow is pushed.
+    pub fn solve_with_progress<F>(&self, mut on_progress: F) -> Result<(), FlowError>
+        where F: FnMut(f32) {
+        #[cfg(feature = "profiling")] {
+            puffin::profile_function!();
+        }
+
+        let mut excess = self.initialize_flow()?;
+        let total_supply: i64 = excess.iter().filter(|e| **e > 0).sum();
+        let mut flow_sent = 0_i64;
+        let mut num_paths = 0_usize;
+        self.num_augmentations.set(0);
+
+        while excess.iter().any(|e| *e > 0) {
+            // find the cheapest way to move flow from any node with supply left to send to any
+            // node with demand left to meet - if there is none, the problem is infeasible
+            let path = self.find_shortest_path(&excess)?.ok_or_else(|| FlowError::Infeasible(
+                "No flow can meet every supply and demand!".to_string()))?;
+
+            let amount = self.push_flow_down_path(&path, &mut excess);
+            flow_sent += amount;
+            on_progress(flow_sent as f32 / total_supply as f32);
+            num_paths += 1;
+            self.num_augmentations.set(num_paths);
+
+            #[cfg(feature = "profiling")]
+            {
+                if num_paths % 100 == 0 {
+                    puffin::GlobalProfiler::lock().new_frame();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the number of paths that flow was pushed down by the last call to `solve`.
+    pub fn get_num_augmentations(&self) -> usize {
+        self.num_augmentations.get()
+    }
+
+    /// Get the flow an arc carries.
+    pub fn get_flow(&self, arc: ArcId) -> Result<usize, FlowError> {
+        Ok(self.arcs.borrow().get(arc.0).ok_or(FlowError::UnknownArc)?.get_flow())
+    }
+
+    /// Get the cost of an arc.
+    pub fn get_cost(&self, arc: ArcId) -> Result<f32, FlowError> {
+        Ok(self.arcs.borrow().get(arc.0).ok_or(FlowError::UnknownArc)?.get_cost())
+    }
+
+    /// Get the total cost of the flow in the network: the sum over all arcs of cost times flow.
+    pub fn get_total_cost(&self) -> f32 {
+        self.arcs.borrow().iter()
+            .map(|arc| arc.get_cost() * arc.get_flow() as f32)
+            .sum()
+    }
+
+    /// Get the extra cost of sending one more unit of flow along each of the given arcs once the
+    /// network has been solved, rerouting other flow so that every supply, demand and bound is
+    /// still met. This is the arc's cost plus the cost of the cheapest path back from its end to
+    /// its start in the residual network (without simply cancelling the arc's own flow), or `None`
+    /// if the arc is full or no such path exists. Arcs that carry no flow and end at the same node
+    /// share a single search. Fails if the residual network has a negative cycle, or if any of the
+    /// arcs does not belong to this network.
+    pub fn get_reroute_costs(&self, arcs: &[ArcId]) -> Result<Vec<Option<f32>>, FlowError> {
+        let mut distances_from: HashMap<(usize, Option<usize>), Vec<f32>> = HashMap::new();
+        arcs.iter()
+            .map(|arc_id| {
+                let (start, end, cost, residual_capacity, cancellable) = {
+                    let arcs = self.arcs.borrow();
+                    let arc = arcs.get(arc_id.0).ok_or(FlowError::UnknownArc)?;
+                    let (start, end) = (arc.get_start_node_id(), arc.get_end_node_id());
+                    (start, end, arc.get_cost(), arc.get_residual_capacity(start),
+                     arc.get_residual_capacity(end) > 0)
+                };
+                if residual_capacity == 0 {
+                    return Ok(None);
+                }
+                let ignored_arc = Some(arc_id.0).filter(|_| cancellable);
+                let distances = match distances_from.entry((end, ignored_arc)) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(self.find_distances(&[end],
+                                                                             ignored_arc)?.0)
+                };
+                Ok(Some(cost + distances[start]).filter(|reroute_cost| reroute_cost.is_finite()))
+            })
+            .collect()
+    }
+
+    /// Put every arc at its starting flow and return the resulting excess at each node: supply not
+    /// yet sent if positive, demand not yet met if negative.
+    fn initialize_flow(&self) -> Result<Vec<i64>, FlowError> {
+        let nodes = self.nodes.borrow();
+        let arcs = self.arcs.borrow();
+
+        let mut excess: Vec<i64> = nodes.iter().map(node::Node::get_supply).collect();
+        if excess.iter().sum::<i64>() != 0 {
+            return Err(FlowError::Infeasible(
+                "Total supply does not match total demand!".to_string()));
+        }
+
+        for arc in arcs.iter() {
+            if arc.get_min_flow() > arc.get_max_flow() {
+                return Err(FlowError::Infeasible(format!(
+                    "Arc has lower bound {} above upper bound {}!",
+                    arc.get_min_flow(), arc.get_max_flow())));
+            }
+            let initial_flow = i64::try_from(arc.reset_flow()).map_err(|_| FlowError::Infeasible(
+                "Arcs with negative cost must have bounded capacity!".to_string()))?;
+            excess[arc.get_start_node_id()] -= initial_flow;
+            excess[arc.get_end_node_id()] += initial_flow;
+        }
+
+        Ok(excess)
+    }
+
+    /// Find the shortest path in the residual network from any node with excess supply to any node
+    /// with unmet demand, using an adaptation of the Bellman-Ford algorithm. The path is returned
+    /// as a list of (node, arc) pairs in order, where flow leaves each node along its paired arc,
+    /// or `None` if no node that needs flow can be reached.
+    fn find_shortest_path(&self, excess: &[i64]) -> Result<Option<Vec<(usize, usize)>>, FlowError> {
+        #[cfg(feature = "profiling")]
+        {
+            puffin::profile_function!();
+        }
+
+        let sources: Vec<usize> = excess.iter().enumerate()
+            .filter(|(_, node_excess)| **node_excess > 0)
+            .map(|(node_id, _)| node_id)
+            .collect();
+        let (distances, predecessors) = self.find_distances(&sources, None)?;
+        let arcs = self.arcs.borrow();
+        let num_nodes = distances.len();
+
+        // pick the closest node that still needs flow
+        let Some(destination) = excess.iter().enumerate()
+            .filter(|(node_id, node_excess)| **node_excess < 0 && distances[*node_id].is_finite())
+            .min_by(|a, b| distances[a.0].total_cmp(&distances[b.0]))
+            .map(|(node_id, _)| node_id) else {
+            return Ok(None);
+        };
+
+        // construct path backwards from the destination until reaching a source - a path that
+        // revisits a node or starts elsewhere can only come from a negative cycle
+        let mut path = Vec::new();
+        let mut node_id = destination;
+        while let Some(arc_id) = predecessors[node_id] {
+            node_id = arcs[arc_id].get_opposite_node_id(node_id);
+            path.push((node_id, arc_id));
+            if path.len() >= num_nodes {
+                return Err(FlowError::NegativeCycle);
+            }
+        }
+        path.reverse();
+        if excess[node_id] <= 0 {
+            return Err(FlowError::NegativeCycle);
+        }
+
+        Ok(Some(path))
+    }
+
+    /// Find the cost of the shortest path in the residual network from any of the given sources to
+    /// every node, using an adaptation of the Bellman-Ford algorithm. Returns the distance to each
+    /// node (infinite if no path reaches it) and the ID of the arc each path arrives along. Paths
+    /// never use the ignored arc, if one is given. Fails if the search does not settle, which
+    /// means the residual network has a negative cycle.
+    fn find_distances(&self, sources: &[usize], ignored_arc: Option<usize>)
+        -> Result<(Vec<f32>, Vec<Option<usize>>), FlowError> {
+        let nodes = self.nodes.borrow();
+        let arcs = self.arcs.borrow();
+        let num_nodes = nodes.len();
+
+        // Initialize vectors that represent the paths found so far - at start, we have found no
+        // paths, so no node has a found predecessor and all nodes are considered infinite distance
+        // from the sources, except for the sources themselves. Node IDs are sequential usize that
+        // start from zero to enable using them as indices in these vectors.
+        let mut distances = vec![f32::INFINITY; num_nodes];
+        let mut predecessors: Vec<Option<usize>> = vec![None; num_nodes]; // stores arc IDs
+        let mut nodes_updated = Vec::new(); // stores node ID numbers
+        for node_id in sources {
+            distances[*node_id] = 0.0;
+            nodes_updated.push(*node_id);
+        }
+
+        // Search for shortest paths, starting from the sources. A shortest path visits each node
+        // at most once, so the search must settle within one round per node.
+        let mut num_iterations = 0_usize;
+        while !nodes_updated.is_empty() && num_iterations <= num_nodes {
+            let nodes_to_search_from = nodes_updated.clone();
+            nodes_updated.clear();
+
+            // for each node updated in the last iteration, see if any of its residual arcs result
+            // in a shorter path to any other node than what's been found so far
+            for node_id in &nodes_to_search_from {
+                let node = unsafe {
+                    nodes.get_unchecked(*node_id)
+                };
+                let dist_to_here = unsafe {
+                    *distances.get_unchecked(*node_id)
+                };
+                for connected_arc_id in node.get_connections().iter() {
+                    let connected_arc = unsafe {
+                        arcs.get_unchecked(*connected_arc_id)
+                    };
+                    if connected_arc.get_residual_capacity(*node_id) == 0
+                        || ignored_arc == Some(*connected_arc_id) {
+                        continue;
+                    }
+
+                    let connected_node_id = connected_arc.get_opposite_node_id(*node_id);
+                    let cur_dist = unsafe {
+                        *distances.get_unchecked(connected_node_id)
+                    };
+                    let dist_from_here = connected_arc.get_residual_cost(*node_id);
+
+                    if dist_to_here + dist_from_here < cur_dist {
+                        // found a shorter path to the connected node
+                        distances[connected_node_id] = dist_to_here + dist_from_here;
+                        predecessors[connected_node_id] = Some(*connected_arc_id);
+                        nodes_updated.push(connected_node_id);
+                    }
+                }
+            }
+
+            num_iterations += 1;
+            // eliminate duplicated entries to make sure we only search once before an update
+            nodes_updated.sort_unstable();
+            nodes_updated.dedup();
+        }
+
+        if !nodes_updated.is_empty() {
+            return Err(FlowError::NegativeCycle);
+        }
+
+        Ok((distances, predecessors))
+    }
+
+    /// Push as much flow as possible down a path, limited by the supply at its start, the demand at
+    /// its end, and the residual capacity of each arc along the way. Updates the excess at the ends
+    /// of the path and returns the amount of flow pushed.
+    fn push_flow_down_path(&self, path: &[(usize, usize)], excess: &mut [i64]) -> i64 {
+        #[cfg(feature = "profiling")]
+        {
+            puffin::profile_function!();
+        }
+
+        let arcs = self.arcs.borrow();
+        let (first_node_id, _) = path[0];
+        let (last_node_id, last_arc_id) = path[path.len() - 1];
+        let destination_id = arcs[last_arc_id].get_opposite_node_id(last_node_id);
+
+        let bottleneck = path.iter()
+            .map(|(node_id, arc_id)| arcs[*arc_id].get_residual_capacity(*node_id))
+            .min()
+            .unwrap_or(0);
+        let amount = excess[first_node_id]
+            .min(-excess[destination_id])
+            .min(i64::try_from(bottleneck).unwrap_or(i64::MAX));
+
+        for (node_id, arc_id) in path {
+            arcs[*arc_id].push_flow(*node_id, amount as usize);
+        }
+        excess[first_node_id] -= amount;
+        excess[destination_id] += amount;
+
+        amount
+    }
+}
+
+impl Default for FlowNetwork {
+    fn default() -> Self {
+        FlowNetwork::new()
+    }
+}
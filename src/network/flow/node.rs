@@ -0,0 +1,55 @@
+use std::cell::{Cell, Ref, RefCell};
+
+/// A generic node in the network, used to represent anything that can supply, demand, or pass
+/// along flow. Each node has a supply (negative for a demand) and a collection of the ID numbers of
+/// the arcs that touch it. Arcs are listed at both of their endpoints so that the residual network
+/// can be searched from either end.
+pub(super) struct Node {
+    supply: Cell<i64>,
+    connected_arcs: RefCell<Vec<usize>>
+}
+
+impl Node {
+    /// Create new Node
+    pub fn new(supply: i64) -> Node {
+        Node { supply: Cell::new(supply), connected_arcs: RefCell::new(Vec::new()) }
+    }
+
+    /// Get the node's supply; negative values represent demand
+    pub fn get_supply(&self) -> i64 {
+        self.supply.get()
+    }
+
+    /// Change the node's supply; negative values represent demand
+    pub fn set_supply(&self, supply: i64) {
+        self.supply.set(supply);
+    }
+
+    /// Create new connection. Callers are responsible for not listing the same arc twice.
+    pub fn add_connection(&self, arc_id: usize) {
+        #[cfg(feature = "profiling")]
+        {
+            puffin::profile_function!();
+        }
+
+        self.connected_arcs.borrow_mut().push(arc_id);
+    }
+
+    /// Returns a reference to the list of connected arc IDs.
+    pub fn get_connections(&self) -> Ref<'_, Vec<usize>> {
+        #[cfg(feature = "profiling")]
+        {
+            puffin::profile_function!();
+        }
+
+        self.connected_arcs.borrow()
+    }
+}
+
+#[cfg(test)]
+impl Node {
+    /// Get number of connected arcs
+    pub fn get_num_connections(&self) -> usize {
+        self.connected_arcs.borrow().len()
+    }
+}
@@ -0,0 +1,192 @@
+use crate::network::flow::{ArcId, FlowError, FlowNetwork, NodeId};
+
+#[test]
+fn test_push_flow() {
+    // setup
+    let cost = 16.8;
+    let network = FlowNetwork::new();
+    let node_a = network.add_node(1);
+    let node_b = network.add_node(-1);
+    let arc = network.add_arc(node_a, node_b, cost, 0, 1).unwrap();
+
+    // test
+    assert_eq!(network.nodes.borrow()[node_a.0].get_num_connections(), 1);
+    assert_eq!(network.nodes.borrow()[node_b.0].get_num_connections(), 1);
+    let mut excess = network.initialize_flow().unwrap();
+    assert_eq!(excess, vec![1, -1]);
+    network.push_flow_down_path(&[(node_a.0, arc.0)], &mut excess);
+    assert_eq!(excess, vec![0, 0]);
+    let arcs = network.arcs.borrow();
+    assert_eq!(arcs[arc.0].get_residual_capacity(node_a.0), 0);
+    assert_eq!(arcs[arc.0].get_residual_capacity(node_b.0), 1);
+    assert_eq!(arcs[arc.0].get_residual_cost(node_b.0), -cost);
+    assert_eq!(arcs[arc.0].get_opposite_node_id(node_b.0), node_a.0);
+}
+
+#[test]
+fn test_shortest_path() {
+    // setup: two workers and two tasks, each task needing exactly one worker
+    let network = FlowNetwork::new();
+    let source = network.add_node(2);
+    let sink = network.add_node(-2);
+    let tasks = [network.add_node(0), network.add_node(0)];
+    let workers = [network.add_node(0), network.add_node(0)];
+    for task in tasks {
+        network.add_arc(task, sink, 0.0, 1, 1).unwrap();
+    }
+    for (worker, costs) in workers.iter().zip([[2.5_f32, 3.0_f32], [2.6_f32, 1.9_f32]]) {
+        network.add_arc(source, *worker, 0.0, 0, 1).unwrap();
+        for (task, cost) in tasks.iter().zip(costs) {
+            network.add_arc(*worker, *task, cost, 0, 1).unwrap();
+        }
+    }
+
+    // test: lower bounds of 1 on task arcs start filled, so flow goes from the source to the tasks
+    assert_eq!(network.get_num_nodes(), 6);
+    assert_eq!(network.get_num_arcs(), 8);
+    let mut excess = network.initialize_flow().unwrap();
+    assert_eq!(excess, vec![2, -2 + 2, -1, -1, 0, 0]);
+    let path = network.find_shortest_path(&excess).unwrap().unwrap();
+    assert_eq!(path.len(), 2);
+    assert_eq!(path[0].0, source.0);
+    assert_eq!(path[1].0, workers[1].0);
+    let path_cost: f32 = path.iter()
+        .map(|(node_id, arc_id)| network.arcs.borrow()[*arc_id].get_residual_cost(*node_id))
+        .sum();
+    assert_eq!(path_cost, 1.9_f32);
+    network.push_flow_down_path(&path, &mut excess);
+    for (node_id, arc_id) in path {
+        assert_eq!(network.arcs.borrow()[arc_id].get_residual_capacity(node_id), 0,
+                   "Arc {} not saturated!", arc_id);
+    }
+}
+
+#[test]
+fn test_transportation_problem() {
+    // setup: three plants ship to two warehouses; one route must carry at least 2 units
+    let network = FlowNetwork::new();
+    let plants = [network.add_node(4), network.add_node(3), network.add_node(1)];
+    let warehouses = [network.add_node(-5), network.add_node(-3)];
+    let costs = [[2.0_f32, 6.0_f32], [3.0_f32, 1.0_f32], [5.0_f32, 2.0_f32]];
+    let mut arcs = Vec::new();
+    for (plant, plant_costs) in plants.iter().zip(costs) {
+        for (warehouse, cost) in warehouses.iter().zip(plant_costs) {
+            arcs.push(network.add_arc(*plant, *warehouse, cost, 0, 10).unwrap());
+        }
+    }
+    let forced = network.add_arc(plants[2], warehouses[0], 5.0, 1, 1).unwrap();
+
+    // test
+    network.solve().unwrap();
+    assert_eq!(network.get_flow(forced), Ok(1));
+    assert_eq!(network.get_flow(arcs[0]), Ok(4));
+    assert_eq!(network.get_flow(arcs[1]), Ok(0));
+    assert_eq!(network.get_flow(arcs[2]), Ok(0));
+    assert_eq!(network.get_flow(arcs[3]), Ok(3));
+    assert_eq!(network.get_flow(arcs[4]), Ok(0));
+    assert_eq!(network.get_flow(arcs[5]), Ok(0));
+    assert_eq!(network.get_total_cost(), 8.0 + 3.0 + 5.0);
+    assert!(network.get_num_augmentations() > 0);
+}
+
+#[test]
+fn test_negative_costs() {
+    // setup: an optional arc with negative cost should be used even though a free route exists
+    let network = FlowNetwork::new();
+    let start = network.add_node(1);
+    let middle = network.add_node(0);
+    let end = network.add_node(-1);
+    let direct = network.add_arc(start, end, 0.0, 0, 1).unwrap();
+    let first_leg = network.add_arc(start, middle, -2.0, 0, 1).unwrap();
+    let second_leg = network.add_arc(middle, end, 1.0, 0, 1).unwrap();
+
+    // test
+    network.solve().unwrap();
+    assert_eq!(network.get_flow(direct), Ok(0));
+    assert_eq!(network.get_flow(first_leg), Ok(1));
+    assert_eq!(network.get_flow(second_leg), Ok(1));
+    assert_eq!(network.get_total_cost(), -1.0);
+}
+
+#[test]
+fn test_infeasible() {
+    let network = FlowNetwork::new();
+    let start = network.add_node(2);
+    let end = network.add_node(-2);
+    network.add_arc(start, end, 1.0, 0, 1).unwrap();
+    let infeasible = |message: &str| Err(FlowError::Infeasible(message.to_string()));
+    assert_eq!(network.solve(), infeasible("No flow can meet every supply and demand!"));
+
+    network.set_supply(start, 1).unwrap();
+    assert_eq!(network.solve(), infeasible("Total supply does not match total demand!"));
+
+    network.set_supply(end, -1).unwrap();
+    network.add_arc(start, end, 1.0, 2, 1).unwrap();
+    assert_eq!(network.solve(), infeasible("Arc has lower bound 2 above upper bound 1!"));
+}
+
+#[test]
+fn test_unknown_node() {
+    // a node from a larger network is not part of this one
+    let larger = FlowNetwork::new();
+    let nodes: Vec<NodeId> = (0..3).map(|_| larger.add_node(0)).collect();
+    let network = FlowNetwork::new();
+    let start = network.add_node(1);
+    assert_eq!(network.add_arc(start, nodes[2], 1.0, 0, 1), Err(FlowError::UnknownNode));
+    assert_eq!(network.set_supply(nodes[2], 1), Err(FlowError::UnknownNode));
+    assert_eq!(network.get_supply(nodes[2]), Err(FlowError::UnknownNode));
+    assert_eq!(network.get_supply(start), Ok(1));
+    assert_eq!(network.get_num_arcs(), 0);
+}
+
+#[test]
+fn test_unknown_arc() {
+    // an arc from a larger network is not part of this one
+    let larger = FlowNetwork::new();
+    let nodes: Vec<NodeId> = (0..2).map(|_| larger.add_node(0)).collect();
+    let arcs: Vec<ArcId> = (0..2)
+        .map(|_| larger.add_arc(nodes[0], nodes[1], 1.0, 0, 1).unwrap())
+        .collect();
+    let network = FlowNetwork::new();
+    let start = network.add_node(0);
+    let end = network.add_node(0);
+    let arc = network.add_arc(start, end, 2.0, 0, 1).unwrap();
+    assert_eq!(network.get_flow(arcs[1]), Err(FlowError::UnknownArc));
+    assert_eq!(network.get_cost(arcs[1]), Err(FlowError::UnknownArc));
+    assert_eq!(network.set_bounds(arcs[1], 0, 0), Err(FlowError::UnknownArc));
+    assert_eq!(network.get_reroute_costs(&[arc, arcs[1]]), Err(FlowError::UnknownArc));
+    assert_eq!(network.get_cost(arc), Ok(2.0));
+}
+
+#[test]
+fn test_reroute_costs() {
+    // setup: the transportation problem again, without the forced route
+    let network = FlowNetwork::new();
+    let plants = [network.add_node(4), network.add_node(3), network.add_node(1)];
+    let warehouses = [network.add_node(-5), network.add_node(-3)];
+    let costs = [[2.0_f32, 6.0_f32], [3.0_f32, 1.0_f32], [5.0_f32, 2.0_f32]];
+    let mut arcs = Vec::new();
+    for (plant, plant_costs) in plants.iter().zip(costs) {
+        for (warehouse, cost) in warehouses.iter().zip(plant_costs) {
+            arcs.push(network.add_arc(*plant, *warehouse, cost, 0, 4).unwrap());
+        }
+    }
+    network.solve().unwrap();
+    assert_eq!(network.get_total_cost(), 8.0 + 3.0 + 2.0 + 2.0);
+
+    // test: sending one more unit from the first plant to the second warehouse means the second
+    // plant sends one unit less there and one more to the first warehouse instead, which in turn
+    // needs one unit less from the first plant
+    let reroute_costs = network.get_reroute_costs(&arcs).unwrap();
+    assert_eq!(reroute_costs[1], Some(6.0 - 1.0 + 3.0 - 2.0));
+    // a full arc can't carry more, and an arc with flow can't be rerouted by cancelling itself
+    assert_eq!(reroute_costs[0], None);
+    assert_eq!(network.get_flow(arcs[2]), Ok(1));
+    assert_eq!(reroute_costs[2], Some(3.0 - 2.0 + 6.0 - 1.0));
+
+    // bounds can be tightened around the flow, but not past it
+    assert_eq!(network.set_bounds(arcs[1], 0, 0), Ok(()));
+    assert_eq!(network.get_reroute_costs(&arcs[1..2]).unwrap(), [None]);
+    assert!(matches!(network.set_bounds(arcs[0], 0, 3), Err(FlowError::Infeasible(_))));
+    assert_eq!(network.get_flow(arcs[0]), Ok(4));
+}
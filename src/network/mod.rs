@@ -1,103 +1,445 @@
 //! # Network
 //!
-//! This module contains definitions and implementations for the Network struct, as well as
-//! submodules for the Node and Arc structs and a custom error type to represent infeasibility in
-//! the problem specification. A network stores its constituent nodes and arcs in vectors and passes
-//! their indices to anything that needs to hold a reference to them.
-
-mod node;
-mod arc;
+//! This module contains the assignment model, which records the tasks to fill, the workers to
+//! assign, and the cost of assigning each worker to each task they can perform. Solving the model
+//! translates it into a general min cost flow problem for the `flow` submodule, which any other
+//! min cost flow problem (e.g. a transportation problem) can use directly. In the flow, each group
+//! of workers who must share a task takes the place of its members. A branch and bound search over
+//! the flow keeps workers in conflict apart.
+//!
+//! When tasks also rank the workers, the model can instead be solved for a stable matching, or
+//! checked for the pairs that make a min cost assignment unstable. The tasks' scores can also be
+//! combined with the workers' costs into the cost to minimize. Before solving, the network can be
+//! checked for problems that are apparent without solving it, and once solved, it can summarize
+//! its assignments in a set of statistics. A custom error type represents infeasibility in the
+//! problem specification.
+
+pub mod flow;
+mod task;
+mod worker;
+mod stable_matching;
+mod score_combination;
+mod cost_normalization;
+mod criterion;
+mod previous_roster;
+mod backup;
+mod conflicts;
+mod statistics;
+mod validation;
 mod feasibility_error;
 #[cfg(test)]
 mod test;
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::iter::zip;
 use std::rc::Rc;
-use std::sync::Arc;
-use crate::network::feasibility_error::FeasibilityError;
-use crate::ui::{CurrentStatus, Status};
-
-/// A Network is a collection of nodes and the arcs that connect those nodes.
-pub(crate) struct Network {
-    min_flow_satisfied: Cell<bool>,
-    min_flow_amount: Cell<usize>,
-    max_flow_amount: Cell<usize>,
-    num_tasks: Cell<usize>,
-    nodes: RefCell<Vec<node::Node>>,
-    arcs: RefCell<Vec<arc::Arc>>,
-    task_names: RefCell<HashMap<Rc<String>, usize>>,
-    worker_names: RefCell<HashMap<usize, Rc<String>>>,
+use std::time::{Duration, Instant};
+pub use crate::network::backup::Backup;
+pub use crate::network::conflicts::{HeuristicLimit, Optimality};
+pub use crate::network::cost_normalization::CostNormalization;
+pub use crate::network::criterion::Criterion;
+pub use crate::network::feasibility_error::FeasibilityError;
+pub use crate::network::previous_roster::{Move, MoveReason};
+pub use crate::network::score_combination::ScoreCombination;
+pub use crate::network::statistics::{Statistics, TaskStatistics};
+pub use crate::network::validation::{InputProblem, Severity};
+pub use crate::network::task::{Task, TaskDetails};
+pub use crate::network::worker::Worker;
+use crate::network::conflicts::GAP_TOLERANCE;
+use crate::network::flow::{ArcId, FlowError, FlowNetwork};
+use crate::status::{Status, StatusTracker};
+
+/// The goal to pursue when assigning workers to tasks
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Objective {
+    /// Minimize the total cost of all assignments, respecting each task's minimum and maximum
+    #[default]
+    MinCost,
+    /// Find a stable matching from both workers' and tasks' preferences, respecting each task's
+    /// maximum
+    StableMatching,
+    /// Minimize the total cost of all assignments, then report any pairs of worker and task that
+    /// would both rather be assigned to each other
+    MinCostAndStability,
+}
+
+/// Each task's backup workers, as (worker ID, rise in total cost) pairs in order, cheapest first
+type TaskBackups = Vec<Vec<(usize, f32)>>;
+
+/// Each unit of workers' arcs to the tasks in a flow network, as (task ID, arc ID) pairs
+type UnitArcs = Vec<Vec<(usize, ArcId)>>;
+
+/// An assignment of units of workers to tasks, found by solving a flow network
+struct UnitSolution {
+    /// The solved flow network
+    flow: FlowNetwork,
+    /// Each unit's arcs to the tasks in the flow network
+    unit_arcs: UnitArcs,
+    /// The (task ID, cost) pairs of the tasks each unit could be assigned to
+    unit_costs: Vec<Vec<(usize, f32)>>,
+    /// The cost of the cheapest flow before any group was held to a single task, which no
+    /// solution that keeps the groups together can beat
+    lower_bound: f32,
+}
+
+impl UnitSolution {
+    /// Get the task each unit was assigned to, if any
+    fn get_unit_tasks(&self) -> Result<Vec<Option<usize>>, FlowError> {
+        get_unit_tasks(&self.flow, &self.unit_arcs)
+    }
+
+    /// Get the total cost of the assignment
+    fn get_cost(&self) -> f32 {
+        self.flow.get_total_cost()
+    }
+}
+
+/// A Network is a collection of tasks and the workers that can be assigned to them. Once solved, it
+/// also records which task each worker has been assigned to.
+pub struct Network {
+    tasks: RefCell<Vec<task::Task>>,
+    task_ids: RefCell<HashMap<Rc<String>, usize>>,
+    workers: RefCell<Vec<worker::Worker>>,
+    worker_ids: RefCell<HashMap<Rc<String>, usize>>,
+    worker_attribute_names: RefCell<Vec<Rc<String>>>,
+    worker_groups: RefCell<Vec<(Rc<String>, Vec<usize>)>>,
+    conflicts: RefCell<Vec<(usize, usize)>>,
+    assignments: RefCell<Vec<Option<usize>>>,
+    blocking_pairs: RefCell<Option<Vec<(usize, usize)>>>,
+    score_combination: Cell<ScoreCombination>,
+    cost_normalization: Cell<CostNormalization>,
+    criteria: RefCell<Vec<Criterion>>,
+    previous_assignments: RefCell<Option<HashMap<Rc<String>, Rc<String>>>>,
+    change_penalty: Cell<f32>,
+    num_backups: Cell<usize>,
+    backups: RefCell<Option<TaskBackups>>,
+    optimality: Cell<Option<Optimality>>,
+    solve_time: Cell<Option<Duration>>,
+    num_augmentations: Cell<Option<usize>>,
 }
 
 impl Network {
-    /// Create a new Network and its source and sink nodes, ensuring those two nodes have IDs 0 and
-    /// 1.
+    /// Create a new Network with no tasks or workers.
     pub fn new() -> Network {
-        // DO NOT allow the vectors stored in self.nodes and self.arcs to be reordered and
-        // DO NOT share indices into them outside of this struct. Indices are used in
-        // unchecked slicing and therefore must be correct to avoid out-of-bounds accesses.
-        let new_network = Network {
-            min_flow_satisfied: Cell::new(false),
-            min_flow_amount: Cell::new(0),
-            max_flow_amount: Cell::new(0),
-            num_tasks: Cell::new(0),
-            nodes: RefCell::new(Vec::new()),
-            arcs: RefCell::new(Vec::new()),
-            task_names: RefCell::new(HashMap::new()),
-            worker_names: RefCell::new(HashMap::new())
-        };
-        new_network.add_node(node::Node::new()); // flow source, id 0
-        new_network.add_node(node::Node::new()); // flow sink, id 1
-        new_network
+        Network {
+            tasks: RefCell::new(Vec::new()),
+            task_ids: RefCell::new(HashMap::new()),
+            workers: RefCell::new(Vec::new()),
+            worker_ids: RefCell::new(HashMap::new()),
+            worker_attribute_names: RefCell::new(Vec::new()),
+            worker_groups: RefCell::new(Vec::new()),
+            conflicts: RefCell::new(Vec::new()),
+            assignments: RefCell::new(Vec::new()),
+            blocking_pairs: RefCell::new(None),
+            score_combination: Cell::new(ScoreCombination::WorkerOnly),
+            cost_normalization: Cell::new(CostNormalization::None),
+            criteria: RefCell::new(Vec::new()),
+            previous_assignments: RefCell::new(None),
+            change_penalty: Cell::new(0.0),
+            num_backups: Cell::new(0),
+            backups: RefCell::new(None),
+            optimality: Cell::new(None),
+            solve_time: Cell::new(None),
+            num_augmentations: Cell::new(None),
+        }
     }
 
-    /// Add a new node to the network representing a task, and connect that node to the sink.
+    /// Add a new task that requires at least `min_workers` and accepts at most `max_workers`.
     pub fn add_task(&self, name: Rc<String>, min_workers: usize, max_workers: usize) {
-        let task_node = node::Node::with_capacity(max_workers);
-        let task_id = self.add_node(task_node);
-
-        self.min_flow_amount.set(self.min_flow_amount.get() + min_workers);
-        self.max_flow_amount.set(self.max_flow_amount.get() + max_workers);
-        self.num_tasks.set(self.num_tasks.get() + 1);
-        if min_workers > 0 {
-            // end node is the sink; cost is 0 because this arc does not connect workers to tasks
-            self.add_arc(task_id, 1, 0.0,
-                         min_workers, max_workers);
-        } else {
-            // draw in reverse order as above since this task is already at its minimum requirement
-            self.add_arc(1, task_id, 0.0,
-                         min_workers, max_workers);
+        let mut tasks = self.tasks.borrow_mut();
+        self.task_ids.borrow_mut().insert(Rc::clone(&name), tasks.len());
+        tasks.push(task::Task::new(name, min_workers, max_workers));
+    }
+
+    /// Set the marginal cost of staffing the named task with each additional worker: the first
+    /// entry is the cost of its first worker, the second entry the cost of its second worker, and
+    /// so on, with any workers beyond the end of the list costing the same as the last entry. This
+    /// lets the solver balance workers across tasks when extra workers are worth less to a task.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the task has not been added to the network, or if the costs ever decrease.
+    pub fn set_staffing_costs(&self, task_name: &Rc<String>, marginal_costs: Vec<f32>) {
+        let task_id = *self.task_ids.borrow().get(task_name)
+            .unwrap_or_else(|| panic!("Staffing costs provided for unknown task {}", task_name));
+        self.tasks.borrow_mut()[task_id].set_staffing_costs(marginal_costs);
+    }
+
+    /// Record the named task's priority, group, shortage penalty and required skills, which are
+    /// kept with the task but do not affect its assignments
+    ///
+    /// # Panics
+    ///
+    /// Panics if the task has not been added to the network.
+    pub fn set_task_details(&self, task_name: &Rc<String>, details: TaskDetails) {
+        let task_id = *self.task_ids.borrow().get(task_name)
+            .unwrap_or_else(|| panic!("Details provided for unknown task {}", task_name));
+        self.tasks.borrow_mut()[task_id].set_details(details);
+    }
+
+    /// Add a new worker who must be assigned to one of the tasks listed in the `task_affinity`
+    /// slice, using each affinity as the cost of assigning the worker to that task.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an affinity is provided for a task that has not been added to the network.
+    pub fn add_worker(&self, name: Rc<String>, task_affinity: &[(&Rc<String>, f32)]) {
+        self.insert_worker(name, task_affinity, false);
+    }
+
+    /// Add a new worker who can be assigned to one of the tasks listed in the `task_affinity`
+    /// slice, but only will be if that is needed to meet a task's minimum or lowers the total cost
+    /// (i.e. the assignment has negative cost).
+    ///
+    /// # Panics
+    ///
+    /// Panics if an affinity is provided for a task that has not been added to the network.
+    pub fn add_optional_worker(&self, name: Rc<String>, task_affinity: &[(&Rc<String>, f32)]) {
+        self.insert_worker(name, task_affinity, true);
+    }
+
+    /// Add a new worker, either mandatory or optional, normalizing their costs as chosen with
+    /// `set_cost_normalization`
+    fn insert_worker(&self, name: Rc<String>, task_affinity: &[(&Rc<String>, f32)],
+                     optional: bool) {
+        let task_ids = self.task_ids.borrow();
+        let affinities = task_affinity.iter()
+            .map(|(task_name, cost)| {
+                let task_id = task_ids.get(*task_name)
+                    .unwrap_or_else(|| panic!("Affinity provided for unknown task {}", task_name));
+                (*task_id, *cost)
+            })
+            .collect::<Vec<(usize, f32)>>();
+        let raw_costs: Vec<f32> = affinities.iter().map(|(_, cost)| *cost).collect();
+        let normalized = affinities.iter()
+            .map(|(task_id, _)| *task_id)
+            .zip(self.cost_normalization.get().normalize(&raw_costs))
+            .collect();
+        let mut workers = self.workers.borrow_mut();
+        self.worker_ids.borrow_mut().insert(Rc::clone(&name), workers.len());
+        workers.push(worker::Worker::new(name, normalized, affinities, optional));
+    }
+
+    /// Record how each task listed in the `task_scores` slice scores the named worker, where lower
+    /// scores are better. Tasks that do not list the worker will not accept them in a stable
+    /// matching.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the worker or any of the tasks has not been added to the network.
+    pub fn add_task_preferences(&self, worker_name: &Rc<String>,
+                                task_scores: &[(&Rc<String>, f32)]) {
+        let task_ids = self.task_ids.borrow();
+        let scores = task_scores.iter()
+            .map(|(task_name, score)| {
+                let task_id = task_ids.get(*task_name)
+                    .unwrap_or_else(|| panic!("Preference provided for unknown task {}", task_name));
+                (*task_id, *score)
+            })
+            .collect();
+        let worker_id = *self.worker_ids.borrow().get(worker_name)
+            .unwrap_or_else(|| panic!("Preferences provided for unknown worker {}", worker_name));
+        self.workers.borrow_mut()[worker_id].set_task_scores(scores);
+    }
+
+    /// Declare a named group of workers who must all be assigned to the same task (e.g. a trainee
+    /// and their mentor), or all be left without one. The group can only go to the tasks that
+    /// every member can perform, at the sum of their costs, and takes up a place there for each
+    /// member. It is optional only if all of its members are.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group has no members, or if any member has not been added to the network or
+    /// is already in a group.
+    pub fn add_worker_group(&self, name: Rc<String>, members: &[Rc<String>]) {
+        assert!(!members.is_empty(), "Worker group {} has no members", name);
+        let worker_ids = self.worker_ids.borrow();
+        let mut groups = self.worker_groups.borrow_mut();
+        let mut member_ids = Vec::new();
+        for member in members {
+            let worker_id = *worker_ids.get(member)
+                .unwrap_or_else(|| panic!("Unknown worker {} in group {}", member, name));
+            assert!(!member_ids.contains(&worker_id)
+                        && !groups.iter().any(|(_, ids)| ids.contains(&worker_id)),
+                    "Worker {} is already in a group", member);
+            member_ids.push(worker_id);
         }
-        self.task_names.borrow_mut().insert(name, task_id);
-    }
-
-    /// Add a new node to the network representing a worker, connect the source to the new node, and
-    /// connect the new node to all tasks the worker can perform (i.e. those listed in the
-    /// task_affinity vector).
-    pub fn add_worker(&self, name: Rc<String>, task_affinity: &Vec<(&Rc<String>, f32)>) {
-        let task_names = self.task_names.borrow();
-        let num_tasks = task_names.len();
-
-        let worker_node = node::Node::with_capacity(num_tasks);
-        let worker_id = self.add_node(worker_node);
-        // connect source to worker - no cost here, and each worker can be assigned exactly once so
-        // the flow bound is 1 for both phases of the min cost augmentation
-        self.add_arc(0, worker_id, 0.0, 1, 1);
-
-        // connect the worker to each task they can perform, using their affinity as the cost of the
-        // new arc - flow bound stays 1
-        for affinity in task_affinity {
-            let task_id = task_names.get(affinity.0)
-                .expect(&*format!("Affinity provided for unknown task {}", affinity.0));
-            self.add_arc(worker_id, *task_id, affinity.1,
-                         1, 1);
+        groups.push((name, member_ids));
+    }
+
+    /// Get the name and members of every group of workers declared with `add_worker_group`, in
+    /// the order they were declared
+    pub fn get_worker_groups(&self) -> Vec<(Rc<String>, Vec<Rc<String>>)> {
+        let workers = self.workers.borrow();
+        self.worker_groups.borrow().iter()
+            .map(|(name, members)| (Rc::clone(name), members.iter()
+                .map(|worker_id| Rc::clone(workers[*worker_id].get_name()))
+                .collect()))
+            .collect()
+    }
+
+    /// Set the names of the attributes that describe each worker (e.g. "Employee ID" or
+    /// "Department"). The solver ignores them, but writers echo each worker's values so that the
+    /// results can be joined back to other records.
+    pub fn set_worker_attribute_names(&self, names: Vec<Rc<String>>) {
+        *self.worker_attribute_names.borrow_mut() = names;
+    }
+
+    /// Get the names of the attributes that describe each worker, in the order they were set
+    pub fn get_worker_attribute_names(&self) -> Vec<Rc<String>> {
+        self.worker_attribute_names.borrow().clone()
+    }
+
+    /// Record the named worker's value for each attribute named with `set_worker_attribute_names`,
+    /// in the same order
+    ///
+    /// # Panics
+    ///
+    /// Panics if the worker has not been added to the network.
+    pub fn set_worker_attributes(&self, worker_name: &Rc<String>, values: Vec<String>) {
+        let worker_id = *self.worker_ids.borrow().get(worker_name)
+            .unwrap_or_else(|| panic!("Attributes provided for unknown worker {}", worker_name));
+        self.workers.borrow_mut()[worker_id].set_attributes(values);
+    }
+
+    /// Choose how the cost of each assignment combines the worker's cost for the task with the
+    /// task's score for the worker. Takes effect the next time the network is solved for min cost.
+    pub fn set_score_combination(&self, score_combination: ScoreCombination) {
+        self.score_combination.set(score_combination);
+    }
+
+    /// Set the named criteria that are weighed together into each worker's costs, in the order
+    /// their values are given to `combine_criteria` and `set_worker_criterion_values`
+    pub fn set_criteria(&self, criteria: Vec<Criterion>) {
+        *self.criteria.borrow_mut() = criteria;
+    }
+
+    /// Get the criteria that are weighed together into each worker's costs, which is empty unless
+    /// they were set with `set_criteria`
+    pub fn get_criteria(&self) -> Vec<Criterion> {
+        self.criteria.borrow().clone()
+    }
+
+    /// Weigh a worker's values for each criterion, given as (task name, value) pairs in the order
+    /// the criteria were set, into the cost of assigning them to each task, which is the sum of
+    /// each criterion's value multiplied by its weight. A task that any criterion leaves without a
+    /// value is left out, so the worker cannot perform it.
+    pub fn combine_criteria<'a>(&self, criterion_values: &[Vec<(&'a Rc<String>, f32)>])
+        -> Vec<(&'a Rc<String>, f32)> {
+        let criteria = self.criteria.borrow();
+        let Some(first_values) = criterion_values.first() else {
+            return Vec::new();
+        };
+        first_values.iter()
+            .filter_map(|(task_name, _)| zip(criteria.iter(), criterion_values)
+                .map(|(criterion, values)| values.iter()
+                    .find(|(t, _)| t == task_name)
+                    .map(|(_, value)| criterion.get_weight() * value))
+                .sum::<Option<f32>>()
+                .map(|cost| (*task_name, cost)))
+            .collect()
+    }
+
+    /// Record the named worker's values for each criterion, given as (task name, value) pairs in
+    /// the order the criteria were set, so that the total cost can be broken down by criterion
+    ///
+    /// # Panics
+    ///
+    /// Panics if the worker or any of the tasks has not been added to the network.
+    pub fn set_worker_criterion_values(&self, worker_name: &Rc<String>,
+                                       criterion_values: &[Vec<(&Rc<String>, f32)>]) {
+        let task_ids = self.task_ids.borrow();
+        let values = criterion_values.iter()
+            .map(|values| values.iter()
+                .map(|(task_name, value)| {
+                    let task_id = task_ids.get(*task_name)
+                        .unwrap_or_else(|| panic!("Value provided for unknown task {}", task_name));
+                    (*task_id, *value)
+                })
+                .collect())
+            .collect();
+        let worker_id = *self.worker_ids.borrow().get(worker_name)
+            .unwrap_or_else(|| panic!("Values provided for unknown worker {}", worker_name));
+        self.workers.borrow_mut()[worker_id].set_criterion_values(values);
+    }
+
+    /// Choose how to rescale each worker's costs so that workers who score every task high and
+    /// workers who score every task low count equally. Applies to the workers added after it is
+    /// set, and the solver then minimizes the rescaled costs, while the costs as given are kept
+    /// for reporting.
+    pub fn set_cost_normalization(&self, cost_normalization: CostNormalization) {
+        self.cost_normalization.set(cost_normalization);
+    }
+
+    /// Get how each worker's costs are rescaled, as chosen with `set_cost_normalization`
+    pub fn get_cost_normalization(&self) -> CostNormalization {
+        self.cost_normalization.get()
+    }
+
+    /// Set the (worker name, task name) pairs of a previous roster, so that re-planning changes as
+    /// few of its assignments as possible: assigning a worker to any task but the one they had
+    /// before adds the change penalty to the cost. Workers and tasks that are not in the network
+    /// are accepted, as the problem may have changed since. Takes effect the next time the network
+    /// is solved for min cost.
+    pub fn set_previous_assignments(&self, assignments: Vec<(Rc<String>, Rc<String>)>,
+                                    change_penalty: f32) {
+        *self.previous_assignments.borrow_mut() = Some(assignments.into_iter().collect());
+        self.change_penalty.set(change_penalty);
+    }
+
+    /// Get the penalty for assigning the named worker to the named task, which is the change
+    /// penalty if the worker had a different task in the previous roster
+    fn get_change_penalty(&self, worker_name: &Rc<String>, task_name: &Rc<String>) -> f32 {
+        match self.previous_assignments.borrow().as_ref().and_then(|p| p.get(worker_name)) {
+            Some(previous_task) if previous_task != task_name => self.change_penalty.get(),
+            _ => 0.0
         }
-        self.worker_names.borrow_mut().insert(worker_id, name);
     }
 
-    /// Perform minimum cost augmentation to build a min cost max flow by assigning one worker at a
-    /// time.
-    pub fn find_min_cost_max_flow(&self, status_tracker: &Arc<CurrentStatus>)
+    /// Choose how many backup workers to list for each task when the network is next solved for
+    /// min cost, or 0 to list none
+    pub fn set_num_backups(&self, num_backups: usize) {
+        self.num_backups.set(num_backups);
+    }
+
+    /// Check whether a worker with the given name has been added to the network
+    pub fn has_worker(&self, name: &Rc<String>) -> bool {
+        self.worker_ids.borrow().contains_key(name)
+    }
+
+    /// Get a copy of every task in the network, in the order they were added. Workers refer to
+    /// tasks by their index in this list.
+    pub fn get_tasks(&self) -> Vec<Task> {
+        self.tasks.borrow().clone()
+    }
+
+    /// Get a copy of every worker in the network, in the order they were added
+    pub fn get_workers(&self) -> Vec<Worker> {
+        self.workers.borrow().clone()
+    }
+
+    /// Get the index of the task each worker was assigned to when the network was last solved, in
+    /// the same order as `get_workers`, or `None` for workers left without a task
+    pub fn get_assignments(&self) -> Vec<Option<usize>> {
+        self.assignments.borrow().clone()
+    }
+
+    /// Find the cheapest assignment of every mandatory worker (and any helpful optional workers) to
+    /// a task that satisfies each task's minimum and maximum number of workers, by solving the
+    /// equivalent min cost flow problem: a source node passes one unit of flow to each worker,
+    /// which must be used for mandatory workers, each worker passes flow to the tasks they can
+    /// perform at the cost of the assignment, and each task passes between its minimum and maximum
+    /// number of workers on to a sink. The sink returns all of the flow to the source, so the flow
+    /// circulates and the amount sent through optional workers is up to the solver.
+    ///
+    /// A group of workers who must share a task passes one unit of flow for each member, so that
+    /// it takes up a place for each of them. The flow may share those units out over several
+    /// tasks, in which case the group is held to each task it could go to in turn (or, if it is
+    /// optional, to none) and the cheapest result is kept, until no group is split. This keeps
+    /// every group together, but unlike the rest of the solution, is not guaranteed to be optimal.
+    /// Workers in conflict are then kept apart by a search described in the `conflicts` module.
+    /// With either, `get_optimality` tells whether the solution is proven optimal.
+    pub fn find_min_cost_max_flow(&self, status_tracker: &dyn StatusTracker)
         -> Result<(), FeasibilityError> {
         #[cfg(feature = "profiling")] {
             puffin::profile_function!();
@@ -105,298 +447,559 @@ impl Network {
 
         // initial checks for feasibility: make sure number of workers is within the range specified
         // by total min and total max
-        let nodes = self.nodes.borrow();
-        let num_workers = nodes.len() - self.num_tasks.get() - 2; // 2 are source and sink
-        if num_workers < self.min_flow_amount.get() {
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+        let num_workers = workers.len();
+        let num_mandatory = workers.iter().filter(|w| !w.is_optional()).count();
+        let score_combination = self.score_combination.get();
+        if score_combination != ScoreCombination::WorkerOnly
+            && !workers.iter().any(worker::Worker::has_task_scores) {
+            return Err(FeasibilityError {
+                message: "Combining scores requires tasks' preferences for workers!".to_string()
+            });
+        }
+        // unbounded tasks may give their maximum as usize::MAX, so the totals must not overflow
+        if num_workers < tasks.iter().map(task::Task::get_min_workers)
+            .fold(0, usize::saturating_add) {
             return Err(FeasibilityError { message: "Not enough workers to assign!".to_string() });
         }
-        if num_workers > self.max_flow_amount.get() {
+        if num_mandatory > tasks.iter().map(task::Task::get_max_workers)
+            .fold(0, usize::saturating_add) {
             return Err(FeasibilityError {
                 message: "Not enough capacity for workers!".to_string()
             });
         }
 
-        let mut current_flow = 0_usize;
-        if self.min_flow_amount.get() == 0 {
-            self.reset_arcs_for_second_phase();
-        }
-
-        // Connections from the source are unassigned workers - loop until they're all assigned.
-        let source = unsafe {
-            nodes.get_unchecked(0)
+        let units = self.get_units();
+        let unit_costs: Vec<_> = units.iter().map(|unit| self.get_unit_costs(unit)).collect();
+        let start = Instant::now();
+        let num_augmentations = Cell::new(0);
+        let (solution, optimality) = if self.conflicts.borrow().is_empty() {
+            let solution = self.solve_units(&units, unit_costs, status_tracker,
+                                            &num_augmentations)?;
+            // without groups to keep together, the cheapest flow is the cheapest solution
+            let optimality = units.iter().any(|unit| unit.len() > 1)
+                .then(|| Optimality::new(solution.get_cost(), solution.lower_bound,
+                                         HeuristicLimit::GroupPlacement));
+            (solution, optimality)
+        } else {
+            let (solution, optimality) = self.find_conflict_free(&units, unit_costs,
+                                                                 status_tracker,
+                                                                 &num_augmentations)?;
+            (solution, Some(optimality))
         };
-        while source.get_num_connections() > 0 {
-            // find shortest path from source to sink - if no path found, then notify the user that
-            // the assignment is infeasible. note that the path returned is in reverse order.
-            let path = self.find_shortest_path()?;
-
-            // path found, push flow and increment the amount of flow
-            self.push_flow_down_path(&path);
-            current_flow += 1;
-            status_tracker.set_status(Status::InProgress((current_flow as f32) / (num_workers as f32)));
-
-            if current_flow == self.min_flow_amount.get() {
-                // minimum requirement achieved: invert arcs that touch the sink
-                self.reset_arcs_for_second_phase();
+
+        let unit_tasks = solution.get_unit_tasks()?;
+        let mut assignments = vec![None; num_workers];
+        for (unit, task) in zip(&units, &unit_tasks) {
+            for worker_id in unit {
+                assignments[*worker_id] = *task;
             }
+        }
 
-            #[cfg(feature = "profiling")]
-            {
-                if current_flow % 100 == 0 {
-                    puffin::GlobalProfiler::lock().new_frame();
-                }
+        // a backup's chain of moves must not split a group, so the backups are found in a flow
+        // network that holds every unit at its task in the solution, with the same tasks barred,
+        // and then releases the workers outside groups
+        let num_backups = self.num_backups.get();
+        let UnitSolution { mut flow, mut unit_arcs, unit_costs, .. } = solution;
+        if num_backups > 0 && units.iter().any(|unit| unit.len() > 1) {
+            let pins = vec![None; units.len()];
+            (flow, unit_arcs) = self.solve_flow(&units, &unit_costs, &pins, Some(&unit_tasks),
+                                                status_tracker, &num_augmentations)?;
+            if get_unit_tasks(&flow, &unit_arcs)? != unit_tasks {
+                return Err(FeasibilityError {
+                    message: "Unable to find backup workers for the solution found!".to_string()
+                });
+            }
+            for (_, arc_id) in zip(&units, &unit_arcs)
+                .filter(|(unit, _)| unit.len() == 1)
+                .flat_map(|(_, arcs)| arcs) {
+                flow.set_bounds(*arc_id, 0, 1)?;
             }
         }
+        self.solve_time.set(Some(start.elapsed()));
+        self.num_augmentations.set(Some(num_augmentations.get()));
+        self.optimality.set(optimality);
+
+        *self.blocking_pairs.borrow_mut() = None;
+        *self.backups.borrow_mut() = match num_backups {
+            0 => None,
+            num_backups => {
+                let worker_arcs: Vec<(usize, &[(usize, ArcId)])> = zip(&units, &unit_arcs)
+                    .filter(|(unit, _)| unit.len() == 1)
+                    .map(|(unit, arcs)| (unit[0], arcs.as_slice()))
+                    .collect();
+                // no move in a backup's chain may put a worker on a task with someone they are in
+                // conflict with, so the arcs to those workers' tasks are closed, even where the
+                // chain would move the other worker on
+                let conflicts = self.conflicts.borrow();
+                for (worker_id, arcs) in &worker_arcs {
+                    let others: Vec<usize> = conflicts.iter()
+                        .filter_map(|(first, second)| match *worker_id {
+                            id if id == *first => Some(*second),
+                            id if id == *second => Some(*first),
+                            _ => None
+                        })
+                        .collect();
+                    for (task_id, arc_id) in arcs.iter() {
+                        if others.iter().any(|other| assignments[*other] == Some(*task_id)) {
+                            flow.set_bounds(*arc_id, 0, 0)?;
+                        }
+                    }
+                }
+                // a proven optimal solution cannot be improved, so any fall in cost within the
+                // tolerance is rounding error
+                let proven_optimal = optimality.is_none_or(|optimality| optimality.proven_optimal);
+                let tolerance = proven_optimal
+                    .then(|| GAP_TOLERANCE * flow.get_total_cost().abs().max(1.0));
+                Some(find_backups(&flow, &worker_arcs, tasks.len(), num_backups, tolerance)?)
+            }
+        };
+        *self.assignments.borrow_mut() = assignments;
 
         Ok(())
     }
 
-    /// Get cost of flow from arcs leaving the supplied node(s). If the supplied node IDs are the
-    /// task node IDs, this method will return -1 times the total cost of worker assignments, since
-    /// assigning a worker to a task involves negating the corresponding arc's cost.
-    pub fn get_cost_of_arcs_from_nodes(&self, nodes: &[Rc<String>]) -> f32 {
-        let task_names = self.task_names.borrow();
-        nodes.iter()
-            .flat_map(|node| {
-                let node_id = task_names.get(node)
-                    .expect(&*format!("Cannot find id for task {}", node));
-                self.get_cost_of_flow_from_node(*node_id)
-            })
-            .sum()
+    /// Assign the units of workers to the tasks they can perform, at the given costs, by solving
+    /// the flow network. The flow may share a group's places out over several tasks, in which case
+    /// the group is held to each task it could go to in turn (or, if it is optional, to none) and
+    /// the cheapest result is kept, until no group is split.
+    fn solve_units(&self, units: &[Vec<usize>], unit_costs: Vec<Vec<(usize, f32)>>,
+                   status_tracker: &dyn StatusTracker, num_augmentations: &Cell<usize>)
+        -> Result<UnitSolution, FeasibilityError> {
+        let workers = self.workers.borrow();
+        let mut pins = vec![None; units.len()];
+        let (mut flow, mut unit_arcs) = self.solve_flow(units, &unit_costs, &pins, None,
+                                                        status_tracker, num_augmentations)?;
+        let lower_bound = flow.get_total_cost();
+        while let Some(unit_id) = find_split_unit(&flow, &unit_arcs, units)? {
+            let optional = units[unit_id].iter().all(|worker_id| workers[*worker_id].is_optional());
+            let choices = unit_costs[unit_id].iter()
+                .map(|(task_id, _)| Some(*task_id))
+                .chain(optional.then_some(None));
+            let mut best: Option<(Option<usize>, (FlowNetwork, UnitArcs))> = None;
+            for choice in choices {
+                pins[unit_id] = Some(choice);
+                if let Ok(solved) = self.solve_flow(units, &unit_costs, &pins, None,
+                                                    status_tracker, num_augmentations) {
+                    if best.as_ref().is_none_or(|(_, (best_flow, _))|
+                        solved.0.get_total_cost() < best_flow.get_total_cost()) {
+                        best = Some((choice, solved));
+                    }
+                }
+            }
+            let Some((choice, solved)) = best else {
+                let groups = self.worker_groups.borrow();
+                let group_name = groups.iter()
+                    .find(|(_, members)| *members == units[unit_id])
+                    .map(|(name, _)| Rc::clone(name))
+                    .unwrap_or_default();
+                return Err(FeasibilityError {
+                    message: format!("Unable to keep worker group {} together!", group_name)
+                });
+            };
+            pins[unit_id] = Some(choice);
+            (flow, unit_arcs) = solved;
+        }
+        Ok(UnitSolution { flow, unit_arcs, unit_costs, lower_bound })
     }
 
-    /// Create and return a `HashMap` of which workers are assigned to which tasks
-    pub fn get_worker_assignments(&self) -> HashMap<Rc<String>, Vec<Rc<String>>> {
-        let mut assignments = HashMap::new();
-        let tasks= self.task_names.borrow();
-        for task in tasks.keys() {
-            let task_id = tasks.get(task).unwrap();
-            let workers = self.nodes.borrow()[*task_id].get_connections()
-                .iter()
-                .map(|a| self.arcs.borrow()[*a].get_end_node_id())
-                .filter(|n| *n != 1)
-                .map(|id| self.worker_names.borrow().get(&id).unwrap().clone())
-                .collect();
-            assignments.insert(task.clone(), workers);
-        }
+    /// Split the workers into the units the solver assigns, in the order the workers were added:
+    /// each group of workers who must share a task is a single unit, and every other worker is a
+    /// unit on their own. Each unit lists the indices of its workers.
+    fn get_units(&self) -> Vec<Vec<usize>> {
+        let groups = self.worker_groups.borrow();
+        (0..self.workers.borrow().len())
+            .filter_map(|worker_id| match groups.iter()
+                .find(|(_, members)| members.contains(&worker_id)) {
+                // a group takes the place of its first member
+                Some((_, members)) if members[0] == worker_id => Some(members.clone()),
+                Some(_) => None,
+                None => Some(vec![worker_id])
+            })
+            .collect()
+    }
 
-        assignments
+    /// Get the (task index, cost) pairs for every task that all of a unit's workers can perform,
+    /// where the cost of the task is the sum of each worker's cost, combined with the task's score
+    /// for them as chosen with `set_score_combination`, plus any penalty for changing their task
+    /// in the previous roster
+    fn get_unit_costs(&self, unit: &[usize]) -> Vec<(usize, f32)> {
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+        let score_combination = self.score_combination.get();
+        let worker_cost = |worker: &worker::Worker, task_id: usize| {
+            // tasks that will not accept the worker under the score combination are left out
+            let cost = score_combination.combine(worker.get_affinity(task_id)?,
+                                                 worker.get_task_score(task_id))?;
+            Some(cost + self.get_change_penalty(worker.get_name(), tasks[task_id].get_name()))
+        };
+        workers[unit[0]].get_affinities().iter()
+            .filter_map(|(task_id, _)| unit.iter()
+                .map(|worker_id| worker_cost(&workers[*worker_id], *task_id))
+                .sum::<Option<f32>>()
+                .map(|cost| (*task_id, cost)))
+            .collect()
     }
 
-    /// Take ownership of a Node and add it to the network's collection of nodes.
-    fn add_node(&self, new_node: node::Node) -> usize {
-        let mut nodes = self.nodes.borrow_mut();
-        let node_id = nodes.len();
-        nodes.push(new_node);
-        node_id
+    /// Build and solve the min cost flow problem for assigning the given units of workers at the
+    /// given costs. A unit pinned to `Some(task)` must be assigned there, and one pinned to `None`
+    /// must be left unassigned. With `held` tasks, each unit is held at its task (or left
+    /// unassigned) by the bounds of its arcs instead, keeping every arc so that the bounds can be
+    /// released after solving. Returns the solved flow network and each unit's arcs to the tasks.
+    fn solve_flow(&self, units: &[Vec<usize>], unit_costs: &[Vec<(usize, f32)>],
+                  pins: &[Option<Option<usize>>], held: Option<&[Option<usize>]>,
+                  status_tracker: &dyn StatusTracker, num_augmentations: &Cell<usize>)
+        -> Result<(FlowNetwork, UnitArcs), FeasibilityError> {
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+
+        let flow = FlowNetwork::new();
+        let source = flow.add_node(0);
+        let sink = flow.add_node(0);
+        flow.add_arc(sink, source, 0.0, 0, workers.len())?;
+        let task_nodes = tasks.iter()
+            .map(|task| {
+                let task_node = flow.add_node(0);
+                // one arc per block of workers that cost the same to add to the task - without a
+                // schedule of staffing costs, this is a single arc with cost 0
+                for (cost, min_workers, max_workers) in task.get_staffing_blocks() {
+                    flow.add_arc(task_node, sink, cost, min_workers, max_workers)?;
+                }
+                Ok(task_node)
+            })
+            .collect::<Result<Vec<_>, FlowError>>()?;
+        let unit_arcs = zip(units, zip(unit_costs, pins)).enumerate()
+            .map(|(unit_id, (unit, (costs, pin)))| {
+                let unit_node = flow.add_node(0);
+                // each worker can be assigned at most once, and mandatory workers exactly once -
+                // a group is mandatory unless all of its members are optional
+                let size = unit.len();
+                let optional = unit.iter().all(|worker_id| workers[*worker_id].is_optional());
+                let (min_flow, max_flow) = match pin {
+                    Some(None) => (0, 0),
+                    Some(Some(_)) => (size, size),
+                    None if optional => (0, size),
+                    None => (size, size)
+                };
+                flow.add_arc(source, unit_node, 0.0, min_flow, max_flow)?;
+                // a group's cost is shared out over the places it takes up
+                costs.iter()
+                    .filter(|(task_id, _)| pin.is_none_or(|pinned| pinned == Some(*task_id)))
+                    .map(|(task_id, cost)| {
+                        let (min_flow, max_flow) = match held.map(|tasks| tasks[unit_id]) {
+                            Some(task) if task == Some(*task_id) => (size, size),
+                            Some(_) => (0, 0),
+                            None => (0, size)
+                        };
+                        Ok((*task_id, flow.add_arc(unit_node, task_nodes[*task_id],
+                                                   cost / size as f32, min_flow, max_flow)?))
+                    })
+                    .collect()
+            })
+            .collect::<Result<UnitArcs, FlowError>>()?;
+
+        flow.solve_with_progress(|fraction| status_tracker.set_status(Status::InProgress(fraction)))
+            .map_err(|err| match err {
+                FlowError::Infeasible(_) =>
+                    FeasibilityError { message: "Unable to assign all workers!".to_string() },
+                err => err.into()
+            })?;
+        num_augmentations.set(num_augmentations.get() + flow.get_num_augmentations());
+        Ok((flow, unit_arcs))
     }
 
-    /// Create a new Arc and add it to the network's collection of arcs
-    fn add_arc(&self, start_node_id: usize, end_node_id: usize, cost: f32, min_flow: usize,
-               max_flow: usize) {
-        let new_arc = arc::Arc::new(start_node_id, end_node_id, cost, min_flow, max_flow);
-        let mut arcs = self.arcs.borrow_mut();
-        self.nodes.borrow()[start_node_id].add_connection(arcs.len());
-        arcs.push( new_arc);
+    /// Get the total cost of all worker assignments made by solving the network, combining
+    /// workers' costs and tasks' scores as chosen with `set_score_combination`, plus the cost of
+    /// staffing each task with its assigned workers and any penalties for changing the previous
+    /// roster.
+    pub fn get_total_cost(&self) -> f32 {
+        let score_combination = self.score_combination.get();
+        let workers = self.workers.borrow();
+        let assignment_cost: f32 = self.assignments.borrow().iter()
+            .zip(workers.iter())
+            .filter_map(|(assignment, worker)| assignment.and_then(|task_id|
+                score_combination.combine(worker.get_affinity(task_id)?,
+                                          worker.get_task_score(task_id))))
+            .sum();
+        assignment_cost + self.get_staffing_cost_total().unwrap_or(0.0)
+            + self.get_change_penalty_total().unwrap_or(0.0)
     }
 
-    /// Find the shortest path from the network's source node to its sink node, using an adaptation
-    /// of the Bellman-Ford algorithm.
-    fn find_shortest_path(&self) -> Result<Vec<usize>, FeasibilityError> {
-        #[cfg(feature = "profiling")]
-        {
-            puffin::profile_function!();
-        }
+    /// Get the total of the penalties for assigning workers to tasks other than the ones they had
+    /// in the previous roster, if one was set with `set_previous_assignments`
+    pub fn get_change_penalty_total(&self) -> Option<f32> {
+        self.previous_assignments.borrow().as_ref()?;
+        let tasks = self.tasks.borrow();
+        Some(self.assignments.borrow().iter()
+            .zip(self.workers.borrow().iter())
+            .filter_map(|(assignment, worker)| assignment.map(|task_id|
+                self.get_change_penalty(worker.get_name(), tasks[task_id].get_name())))
+            .sum())
+    }
 
-        let nodes = self.nodes.borrow();
-        let arcs = self.arcs.borrow();
-        let num_nodes = nodes.len();
-
-        // Initialize vectors that represent the paths found so far - at start, we have found no
-        // paths, so no node has a found predecessor and all nodes are considered infinite distance
-        // from the source, except for the source itself. Node IDs are sequential usize that start
-        // from zero to enable using them as indices in these vectors.
-        let mut distances = vec![f32::INFINITY; num_nodes];
-        distances[0] = 0.0;
-        let mut predecessors: Vec<Option<usize>> = vec![None; num_nodes];
-
-        // Search for shortest path, starting from the source.
-        let mut nodes_updated = vec![0]; // stores ID numbers
-        let mut num_iterations = 0_usize;
-        while !nodes_updated.is_empty() && num_iterations < num_nodes {
-            let nodes_to_search_from = nodes_updated.clone();
-            nodes_updated.clear();
-
-            // for each node updated in the last iteration, see if any of its existing connections
-            // result in a shorter path to any other node than what's been found so far
-            for node_id in &nodes_to_search_from {
-                let node = unsafe {
-                    nodes.get_unchecked(*node_id)
-                };
-                for connected_arc_id in node.get_connections().iter() {
-                    let connected_arc = unsafe {
-                        arcs.get_unchecked(*connected_arc_id)
-                    };
-                    let connected_node_id = connected_arc.get_end_node_id();
-                    // calculate distances
-                    let cur_dist = unsafe {
-                        *distances.get_unchecked(connected_node_id)
-                    };
-                    let dist_to_here = unsafe {
-                        *distances.get_unchecked(*node_id)
-                    };
-                    let dist_from_here = connected_arc.get_cost();
-
-                    if dist_to_here + dist_from_here < cur_dist {
-                        // found a shorter path to the connected node
-                        distances[connected_node_id] = dist_to_here + dist_from_here;
-                        predecessors[connected_node_id] = Some(*node_id);
-                        if connected_node_id != 1 {
-                            // omit arcs leaving the sink, as these arcs cannot be part of a path to
-                            // the sink (else it would be a walk instead of a path) and their
-                            // representation within the code is an imperfect mirror of the residual
-                            // network for the sake of keeping their data in memory
-                            nodes_updated.push(connected_node_id);
+    /// List the workers whose assignments differ from the ones they had in the previous roster,
+    /// with the reason for each move, if a previous roster was set with
+    /// `set_previous_assignments`. Workers who were not in the previous roster are left out. A
+    /// move is only put down to a lower cost if it lowered the worker's own cost, after the change
+    /// penalty; other moves were made for the rest of the solution.
+    pub fn get_moves(&self) -> Option<Vec<Move>> {
+        let previous_assignments = self.previous_assignments.borrow();
+        let previous_assignments = previous_assignments.as_ref()?;
+        let tasks = self.tasks.borrow();
+        let task_ids = self.task_ids.borrow();
+        let workers = self.workers.borrow();
+        let num_previous = |task_name: &Rc<String>| workers.iter()
+            .filter(|worker| previous_assignments.get(worker.get_name()) == Some(task_name))
+            .count();
+        let score_combination = self.score_combination.get();
+        let own_cost = |worker: &worker::Worker, task_id: usize| score_combination
+            .combine(worker.get_affinity(task_id)?, worker.get_task_score(task_id));
+        Some(self.assignments.borrow().iter()
+            .zip(workers.iter())
+            .filter_map(|(assignment, worker)| {
+                let from = previous_assignments.get(worker.get_name())?;
+                let to = assignment.map(|task_id| Rc::clone(tasks[task_id].get_name()));
+                if to.as_ref() == Some(from) {
+                    return None;
+                }
+                let reason = match task_ids.get(from) {
+                    None => MoveReason::TaskRemoved,
+                    Some(task_id) if worker.get_affinity(*task_id).is_none() =>
+                        MoveReason::NotEligible,
+                    Some(task_id) if num_previous(from) > tasks[*task_id].get_max_workers() =>
+                        MoveReason::OverCapacity,
+                    Some(task_id) => {
+                        let before = own_cost(worker, *task_id);
+                        let after = match assignment {
+                            Some(new_id) => own_cost(worker, *new_id)
+                                .map(|cost| cost + self.change_penalty.get()),
+                            None => Some(0.0)
+                        };
+                        match (before, after) {
+                            (Some(before), Some(after)) if after < before => MoveReason::LowerCost,
+                            _ => MoveReason::Rebalanced
                         }
                     }
-                }
-            }
+                };
+                Some(Move {
+                    worker: Rc::clone(worker.get_name()),
+                    from: Rc::clone(from),
+                    to,
+                    reason,
+                })
+            })
+            .collect())
+    }
 
-            num_iterations += 1;
-            // eliminate duplicated entries to make sure we only search once before an update
-            nodes_updated.sort_unstable();
-            nodes_updated.dedup();
-        }
+    /// List up to the chosen number of backup workers for each task, if any were asked for with
+    /// `set_num_backups` when the network was solved for min cost. A task's backups are the workers
+    /// not assigned to it who could be moved there with the smallest rise in total cost, cheapest
+    /// first, counting any chain of other moves needed to keep the solution feasible, none of which
+    /// puts a worker on a task with someone they are in conflict with. Workers in groups are left
+    /// out, as moving one of them alone would split their group. If the solution is not proven
+    /// optimal, a rise can be negative, meaning that the move lowers the total cost.
+    pub fn get_backups(&self) -> Option<Vec<Backup>> {
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+        let assignments = self.assignments.borrow();
+        Some(self.backups.borrow().as_ref()?.iter()
+            .enumerate()
+            .flat_map(|(task_id, task_backups)| task_backups.iter()
+                .enumerate()
+                .map(|(index, (worker_id, cost_increase))| Backup {
+                    task: Rc::clone(tasks[task_id].get_name()),
+                    rank: index + 1,
+                    worker: Rc::clone(workers[*worker_id].get_name()),
+                    current_task: assignments[*worker_id]
+                        .map(|current| Rc::clone(tasks[current].get_name())),
+                    cost_increase: *cost_increase,
+                })
+                .collect::<Vec<_>>())
+            .collect())
+    }
 
-        assert!(num_iterations < num_nodes, "Negative cycle detected - this can't happen in the \
-                                             algorithm this code attempts to implement, so there \
-                                             must be a bug.");
-        if predecessors[1].is_none() {
-            return Err(FeasibilityError { message: "Unable to assign all workers!".to_string() });
+    /// Get the total cost of staffing each task with the workers assigned to it, if any task has a
+    /// schedule of staffing costs.
+    pub fn get_staffing_cost_total(&self) -> Option<f32> {
+        let tasks = self.tasks.borrow();
+        if !tasks.iter().any(task::Task::has_staffing_costs) {
+            return None;
         }
-
-        // construct path backwards; unwrap won't panic because the vector is never empty
-        let mut path = vec![1];
-        while let Some(node_id) = unsafe {
-            predecessors.get_unchecked(*path.last().unwrap())
-        } {
-            path.push(*node_id);
+        let mut num_assigned = vec![0_usize; tasks.len()];
+        for task_id in self.assignments.borrow().iter().flatten() {
+            num_assigned[*task_id] += 1;
         }
+        Some(tasks.iter()
+            .zip(num_assigned)
+            .map(|(task, n)| task.get_staffing_cost(n))
+            .sum())
+    }
 
-        // confirm the last node found was the source - if not, there's a bug
-        assert_eq!(*path.last().unwrap(), 0, "Path does not start at source!");
-
-        Ok(path)
+    /// Get the total of the workers' costs for the tasks they were assigned to, i.e. how satisfied
+    /// the workers are with their assignments (lower is better).
+    pub fn get_worker_score_total(&self) -> f32 {
+        let workers = self.workers.borrow();
+        self.assignments.borrow().iter()
+            .zip(workers.iter())
+            .filter_map(|(assignment, worker)|
+                assignment.and_then(|task_id| worker.get_affinity(task_id)))
+            .sum()
     }
 
-    /// Push flow down each arc in a path.
-    fn push_flow_down_path(&self, path: &[usize]) {
-        #[cfg(feature = "profiling")]
-        {
-            puffin::profile_function!();
-        }
-        let arcs = self.arcs.borrow();
-        for node_pair in path.windows(2) {
-            let arc_id = self.find_connecting_arc_id(node_pair[1], node_pair[0])
-                .expect("Can't find an arc that's part of the path!");
-            let arc = unsafe {
-                arcs.get_unchecked(arc_id)
-            };
-            let arc_inverted = arc.push_flow(self.min_flow_satisfied.get());
-            if arc_inverted {
-                let nodes = self.nodes.borrow();
-                unsafe {
-                    nodes.get_unchecked(node_pair[1]).remove_connection(arc_id);
-                    nodes.get_unchecked(node_pair[0]).add_connection(arc_id);
-                }
-            }
+    /// Get the total of the workers' costs, as given before they were rescaled, for the tasks they
+    /// were assigned to, if the costs were rescaled with `set_cost_normalization`
+    pub fn get_raw_cost_total(&self) -> Option<f32> {
+        if self.cost_normalization.get() == CostNormalization::None {
+            return None;
         }
+        let workers = self.workers.borrow();
+        Some(self.assignments.borrow().iter()
+            .zip(workers.iter())
+            .filter_map(|(assignment, worker)|
+                assignment.and_then(|task_id| worker.get_raw_affinity(task_id)))
+            .sum())
     }
 
-    /// The second phase of minimum cost augmentation starts with all tasks having their minimum
-    /// requirement satisfied, and allows further assignment of all remaining workers up to the max
-    /// for each task. This method resets all arcs touching the sink to account for the
-    /// corresponding changes in the residual network.
-    /// This method uses unsafe blocks to skip bounds checks when indexing self.nodes. For the
-    /// unsafe blocks to work properly, there must be no way to destroy the sink node, no way to
-    /// reorder nodes within self.nodes, and no way to create an arc that starts and/or ends at an
-    /// invalid node.
-    fn reset_arcs_for_second_phase(&self) {
-        #[cfg(feature = "profiling")]
-        {
-            puffin::profile_function!();
+    /// Break the workers' costs for the tasks they were assigned to down by criterion, giving each
+    /// criterion with the total of its values multiplied by its weight. Without criteria set with
+    /// `set_criteria`, the list is empty.
+    pub fn get_criterion_totals(&self) -> Vec<(Criterion, f32)> {
+        let workers = self.workers.borrow();
+        let assignments = self.assignments.borrow();
+        self.criteria.borrow().iter()
+            .enumerate()
+            .map(|(criterion_id, criterion)| {
+                let total: f32 = assignments.iter()
+                    .zip(workers.iter())
+                    .filter_map(|(assignment, worker)| assignment.and_then(|task_id|
+                        worker.get_criterion_value(criterion_id, task_id)))
+                    .sum();
+                (criterion.clone(), criterion.get_weight() * total)
+            })
+            .collect()
+    }
+
+    /// Get the total of the tasks' scores for the workers assigned to them, i.e. how satisfied the
+    /// tasks are with their assignments (lower is better), if any task scored the workers.
+    /// Assignments the task did not score are left out of the total.
+    pub fn get_task_score_total(&self) -> Option<f32> {
+        let workers = self.workers.borrow();
+        if !workers.iter().any(worker::Worker::has_task_scores) {
+            return None;
         }
+        Some(self.assignments.borrow().iter()
+            .zip(workers.iter())
+            .filter_map(|(assignment, worker)|
+                assignment.and_then(|task_id| worker.get_task_score(task_id)))
+            .sum())
+    }
 
-        let nodes = self.nodes.borrow();
-        let arcs = self.arcs.borrow();
-        let connections = unsafe {
-            // Required invariant is that self.nodes contains at least two nodes, which is satisfied
-            // in Network::new() - the node at index 1 is the sink.
-            nodes.get_unchecked(1).get_connections().clone()
-        };
-        self.min_flow_satisfied.set(true);
-        for connection in connections {
-            let arc = unsafe {
-                arcs.get_unchecked(connection)
-            };
-            let arc_inverted = arc.update_for_second_phase();
-            if arc_inverted {
-                unsafe {
-                    // Required invariant is that the arc's start and end node IDs are both valid
-                    // node IDs. Satisfied in Network::add_worker and Network::add_task by only
-                    // creating arcs between valid nodes, and maintained by the Arc interface not
-                    // providing a way to change which nodes any given arc connects.
-                    nodes.get_unchecked(arc.get_end_node_id()).remove_connection(connection);
-                    nodes.get_unchecked(arc.get_start_node_id()).add_connection(connection);
-                }
+    /// Create and return a `HashMap` of which workers are assigned to which tasks
+    pub fn get_worker_assignments(&self) -> HashMap<Rc<String>, Vec<Rc<String>>> {
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+        let mut assignments: HashMap<Rc<String>, Vec<Rc<String>>> = tasks.iter()
+            .map(|task| (Rc::clone(task.get_name()), Vec::new()))
+            .collect();
+        for (worker, assignment) in workers.iter().zip(self.assignments.borrow().iter()) {
+            if let Some(task_id) = assignment {
+                assignments.get_mut(tasks[*task_id].get_name())
+                    .unwrap()
+                    .push(Rc::clone(worker.get_name()));
             }
         }
+
+        assignments
     }
 
-    /// Find the ID of the arc that connects the two identified nodes, if any
-    fn find_connecting_arc_id(&self, start_node_id: usize, end_node_id: usize) -> Option<usize> {
-        #[cfg(feature = "profiling")]
-        {
-            puffin::profile_function!();
-        }
+    /// Get the names of all mandatory workers who were not assigned to any task
+    pub fn get_unassigned_workers(&self) -> Vec<Rc<String>> {
+        self.get_unassigned_workers_where(|worker| !worker.is_optional())
+    }
 
-        let nodes = self.nodes.borrow();
-        let arcs = self.arcs.borrow();
-        let node = unsafe {
-            nodes.get_unchecked(start_node_id)
-        };
-        let id = node.get_connections().iter().copied()
-            .find(|c| unsafe {
-                arcs.get_unchecked(*c).get_end_node_id() == end_node_id
-            });
-        id
+    /// Get the names of all optional workers who were not assigned to any task
+    pub fn get_unused_optional_workers(&self) -> Vec<Rc<String>> {
+        self.get_unassigned_workers_where(worker::Worker::is_optional)
     }
 
-    /// Find the total cost of all arcs leaving the node specified by the given ID.
-    fn get_cost_of_flow_from_node(&self, node: usize) -> Vec<f32> {
-        self.nodes.borrow()[node]
-            .get_connections()
-            .iter()
-            .map(|connected_node|
-                self.arcs.borrow()[*connected_node].get_cost())
+    /// Get the names of all workers matching the filter who were not assigned to any task
+    fn get_unassigned_workers_where<F>(&self, filter: F) -> Vec<Rc<String>>
+        where F: Fn(&worker::Worker) -> bool {
+        self.workers.borrow().iter()
+            .zip(self.assignments.borrow().iter())
+            .filter(|(worker, assignment)| assignment.is_none() && filter(worker))
+            .map(|(worker, _)| Rc::clone(worker.get_name()))
             .collect()
     }
 }
 
-#[cfg(test)]
-impl Network {
-    /// Get total distance of a path by adding the costs of each arc in the path.
-    fn get_path_cost(&self, path: &Vec<usize>) -> f32 {
-        path.windows(2)
-            .map(|node_pair| {
-                let arcs = self.arcs.borrow();
-                for arc_id in self.nodes.borrow()[node_pair[0]].get_connections().iter() {
-                    if arcs[*arc_id].get_end_node_id() == node_pair[1] {
-                        return arcs[*arc_id].get_cost();
-                    }
-                }
-                panic!("No arc found from {} to {}", node_pair[0], node_pair[1])
-            })
-            .sum()
+impl Default for Network {
+    fn default() -> Self {
+        Network::new()
+    }
+}
+
+/// Rank the workers who could be moved to each task they were not assigned to, by the rise in the
+/// total cost of the solved flow network, keeping the cheapest `num_backups` for each task. Each
+/// worker's arcs are given as (task ID, arc ID) pairs, along with the worker's ID. A rise in cost
+/// no further below 0 than the `rounding_tolerance`, if one is given, is taken as 0.
+fn find_backups(flow: &FlowNetwork, worker_arcs: &[(usize, &[(usize, ArcId)])],
+                num_tasks: usize, num_backups: usize, rounding_tolerance: Option<f32>)
+    -> Result<TaskBackups, FlowError> {
+    let candidates: Vec<(usize, usize, ArcId)> = worker_arcs.iter()
+        .flat_map(|(worker_id, arcs)| arcs.iter()
+            .filter(|(_, arc_id)| flow.get_flow(*arc_id) == Ok(0))
+            .map(|(task_id, arc_id)| (*worker_id, *task_id, *arc_id)))
+        .collect();
+    let arcs: Vec<ArcId> = candidates.iter().map(|(_, _, arc_id)| *arc_id).collect();
+
+    let mut backups = vec![Vec::new(); num_tasks];
+    for ((worker_id, task_id, _), cost) in zip(candidates, flow.get_reroute_costs(&arcs)?) {
+        let cost = match (cost, rounding_tolerance) {
+            (Some(cost), Some(tolerance)) if cost > -tolerance => Some(cost.max(0.0)),
+            (cost, _) => cost
+        };
+        if let Some(cost) = cost {
+            backups[task_id].push((worker_id, cost));
+        }
+    }
+    for task_backups in &mut backups {
+        task_backups.sort_by(|a: &(usize, f32), b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        task_backups.truncate(num_backups);
+    }
+    Ok(backups)
+}
+
+/// Get the task the flow assigns each unit of workers to, if any
+fn get_unit_tasks(flow: &FlowNetwork, unit_arcs: &[Vec<(usize, ArcId)>])
+    -> Result<Vec<Option<usize>>, FlowError> {
+    unit_arcs.iter()
+        .map(|arcs| get_flows(flow, arcs)
+            .map(|flows| flows.first().map(|(task_id, _)| *task_id)))
+        .collect()
+}
+
+/// Find the first unit of workers whose places the flow shares out over several tasks, or only
+/// partly fills
+fn find_split_unit(flow: &FlowNetwork, unit_arcs: &[Vec<(usize, ArcId)>], units: &[Vec<usize>])
+    -> Result<Option<usize>, FlowError> {
+    for (unit_id, (unit, arcs)) in zip(units, unit_arcs).enumerate() {
+        let flows: Vec<usize> = get_flows(flow, arcs)?.into_iter()
+            .map(|(_, amount)| amount)
+            .collect();
+        if !flows.is_empty() && flows != [unit.len()] {
+            return Ok(Some(unit_id));
+        }
     }
+    Ok(None)
+}
+
+/// Get the (task ID, flow) pairs of the arcs to tasks that carry flow
+fn get_flows(flow: &FlowNetwork, arcs: &[(usize, ArcId)])
+    -> Result<Vec<(usize, usize)>, FlowError> {
+    arcs.iter()
+        .map(|(task_id, arc_id)| Ok((*task_id, flow.get_flow(*arc_id)?)))
+        .filter(|pair| !matches!(pair, Ok((_, 0))))
+        .collect()
 }
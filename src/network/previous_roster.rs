@@ -0,0 +1,45 @@
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+/// Why a worker was moved away from the task they had in the previous roster
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MoveReason {
+    /// Their previous task is not in the network any more
+    TaskRemoved,
+    /// They can no longer perform their previous task
+    NotEligible,
+    /// Their previous task has fewer places than it had workers in the previous roster
+    OverCapacity,
+    /// Moving them lowered their own cost by more than the change penalty, by giving them a much
+    /// cheaper task or none
+    LowerCost,
+    /// They were moved for the rest of the solution, e.g. to fill another task's minimum, to keep
+    /// them apart from a worker they are in conflict with or together with their group, or to make
+    /// room for another worker
+    Rebalanced,
+}
+
+impl Display for MoveReason {
+    /// Describe the reason in words, for the output
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            MoveReason::TaskRemoved => "previous task was removed",
+            MoveReason::NotEligible => "no longer eligible for previous task",
+            MoveReason::OverCapacity => "previous task has fewer places",
+            MoveReason::LowerCost => "lower cost for the worker",
+            MoveReason::Rebalanced => "needed elsewhere in the solution",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// A worker whose assignment differs from the one they had in the previous roster
+#[derive(Clone, Debug, PartialEq)]
+pub struct Move {
+    pub worker: Rc<String>,
+    /// The task the worker had in the previous roster
+    pub from: Rc<String>,
+    /// The task the worker has now, if any
+    pub to: Option<Rc<String>>,
+    pub reason: MoveReason,
+}
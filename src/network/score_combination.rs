@@ -0,0 +1,31 @@
+//! Ways to combine the cost of assigning a worker to a task with the score the task gave the worker
+//! into the single cost the solver minimizes. Lower values are better on both sides.
+
+/// How to turn a worker's cost for a task and the task's score for the worker into one cost
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum ScoreCombination {
+    /// Use only the workers' costs, ignoring the tasks' scores
+    #[default]
+    WorkerOnly,
+    /// Add the worker's cost and the task's score, each multiplied by its weight
+    WeightedSum { worker_weight: f32, task_weight: f32 },
+    /// Multiply the worker's cost by the task's score
+    Product,
+    /// Use whichever of the worker's cost and the task's score is lower
+    Min,
+}
+
+impl ScoreCombination {
+    /// Combine a worker's cost for a task with the task's score for the worker, if it gave one.
+    /// Returns `None` when the combination needs a score the task did not give, meaning the task
+    /// will not accept the worker.
+    pub fn combine(&self, worker_cost: f32, task_score: Option<f32>) -> Option<f32> {
+        match self {
+            ScoreCombination::WorkerOnly => Some(worker_cost),
+            ScoreCombination::WeightedSum { worker_weight, task_weight } =>
+                task_score.map(|s| worker_weight * worker_cost + task_weight * s),
+            ScoreCombination::Product => task_score.map(|s| worker_cost * s),
+            ScoreCombination::Min => task_score.map(|s| worker_cost.min(s)),
+        }
+    }
+}
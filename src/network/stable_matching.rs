@@ -0,0 +1,157 @@
+//! Stable matching for the assignment model. A matching is stable when no worker and task would
+//! both rather be assigned to each other than keep their current assignments: such a pair is called
+//! a blocking pair. Workers rank tasks by the cost of the assignment and tasks rank workers by the
+//! scores they gave them, with lower values preferred on both sides.
+
+use std::cmp::Ordering;
+use std::rc::Rc;
+use crate::network::{FeasibilityError, Network};
+use crate::network::worker::Worker;
+
+impl Network {
+    /// Find a stable matching with the many-to-one deferred acceptance (hospitals/residents)
+    /// algorithm. Each worker proposes to the tasks they can perform in order of preference, and
+    /// each task holds on to the best proposals it has received, up to its maximum number of
+    /// workers, rejecting the rest. Tasks only accept workers they have scored. The result is the
+    /// stable matching that is best for every worker; it does not enforce task minimums, and
+    /// workers that every acceptable task rejects are left unassigned.
+    pub fn find_stable_matching(&self) -> Result<(), FeasibilityError> {
+        #[cfg(feature = "profiling")] {
+            puffin::profile_function!();
+        }
+
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+        if !workers.iter().any(Worker::has_task_scores) {
+            return Err(FeasibilityError {
+                message: "Stable matching requires tasks' preferences for workers!".to_string()
+            });
+        }
+        if !self.worker_groups.borrow().is_empty() {
+            return Err(FeasibilityError {
+                message: "Stable matching cannot keep groups of workers together!".to_string()
+            });
+        }
+        if !self.conflicts.borrow().is_empty() {
+            return Err(FeasibilityError {
+                message: "Stable matching cannot keep conflicting workers apart!".to_string()
+            });
+        }
+
+        // list the tasks each worker will propose to, most preferred first
+        let proposals: Vec<Vec<usize>> = workers.iter()
+            .map(|worker| {
+                let mut acceptable: Vec<(usize, f32)> = worker.get_affinities().iter()
+                    .filter(|(task_id, _)| worker.get_task_score(*task_id).is_some())
+                    .copied()
+                    .collect();
+                acceptable.sort_by(|a, b| a.1.total_cmp(&b.1));
+                acceptable.into_iter().map(|(task_id, _)| task_id).collect()
+            })
+            .collect();
+
+        let mut next_proposal = vec![0_usize; workers.len()];
+        let mut held: Vec<Vec<usize>> = vec![Vec::new(); tasks.len()];
+        let mut free_workers: Vec<usize> = (0..workers.len()).rev().collect();
+        while let Some(worker_id) = free_workers.pop() {
+            // a worker who has been rejected by every acceptable task stays unassigned
+            let task_id = match proposals[worker_id].get(next_proposal[worker_id]) {
+                Some(task_id) => *task_id,
+                None => continue
+            };
+            next_proposal[worker_id] += 1;
+
+            held[task_id].push(worker_id);
+            if held[task_id].len() > tasks[task_id].get_max_workers() {
+                // over capacity: the task rejects the worker it likes least
+                let (position, _) = held[task_id].iter().enumerate()
+                    .max_by(|a, b| compare_for_task(&workers, task_id, *a.1, *b.1))
+                    .unwrap();
+                free_workers.push(held[task_id].swap_remove(position));
+            }
+        }
+
+        let mut assignments = vec![None; workers.len()];
+        for (task_id, task_workers) in held.iter().enumerate() {
+            for worker_id in task_workers {
+                assignments[*worker_id] = Some(task_id);
+            }
+        }
+        *self.assignments.borrow_mut() = assignments;
+        *self.blocking_pairs.borrow_mut() = None;
+        *self.backups.borrow_mut() = None;
+        self.optimality.set(None);
+        self.solve_time.set(None);
+        self.num_augmentations.set(None);
+
+        Ok(())
+    }
+
+    /// Find every blocking pair in the current assignments and record them so they can be reported
+    /// alongside the assignments. A worker and a task form a blocking pair when the worker prefers
+    /// the task to their current assignment (or is unassigned), the task has scored the worker, and
+    /// the task either has room for another worker or prefers this worker to one it was given.
+    /// Returns the number of blocking pairs found.
+    pub fn record_blocking_pairs(&self) -> usize {
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+        let assignments = self.assignments.borrow();
+
+        let mut assigned_to_task: Vec<Vec<usize>> = vec![Vec::new(); tasks.len()];
+        for (worker_id, assignment) in assignments.iter().enumerate() {
+            if let Some(task_id) = assignment {
+                assigned_to_task[*task_id].push(worker_id);
+            }
+        }
+
+        let mut blocking_pairs = Vec::new();
+        for (worker_id, worker) in workers.iter().enumerate() {
+            let current_cost = assignments.get(worker_id).copied().flatten()
+                .and_then(|task_id| worker.get_affinity(task_id));
+            for (task_id, cost) in worker.get_affinities() {
+                if worker.get_task_score(*task_id).is_none() {
+                    continue;
+                }
+                let worker_prefers_task = match current_cost {
+                    Some(current) => *cost < current,
+                    None => true
+                };
+                if !worker_prefers_task {
+                    continue;
+                }
+
+                let task_prefers_worker = assigned_to_task[*task_id].len()
+                    < tasks[*task_id].get_max_workers()
+                    || assigned_to_task[*task_id].iter().any(|other_id|
+                        compare_for_task(&workers, *task_id, worker_id, *other_id)
+                            == Ordering::Less);
+                if task_prefers_worker {
+                    blocking_pairs.push((worker_id, *task_id));
+                }
+            }
+        }
+
+        let num_blocking_pairs = blocking_pairs.len();
+        *self.blocking_pairs.borrow_mut() = Some(blocking_pairs);
+        num_blocking_pairs
+    }
+
+    /// Get the (worker name, task name) blocking pairs found by the last call to
+    /// `record_blocking_pairs`, if it has been called since the network was last solved
+    pub fn get_blocking_pairs(&self) -> Option<Vec<(Rc<String>, Rc<String>)>> {
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+        self.blocking_pairs.borrow().as_ref().map(|pairs| pairs.iter()
+            .map(|(worker_id, task_id)| (Rc::clone(workers[*worker_id].get_name()),
+                                         Rc::clone(tasks[*task_id].get_name())))
+            .collect())
+    }
+}
+
+/// Order two workers by how much the given task likes them, most preferred first. Workers the task
+/// has not scored come last, and ties go to the worker added to the network first.
+fn compare_for_task(workers: &[Worker], task_id: usize, first: usize, second: usize) -> Ordering {
+    let first_score = workers[first].get_task_score(task_id).unwrap_or(f32::INFINITY);
+    let second_score = workers[second].get_task_score(task_id).unwrap_or(f32::INFINITY);
+    first_score.total_cmp(&second_score).then(first.cmp(&second))
+}
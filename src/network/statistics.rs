@@ -0,0 +1,100 @@
+//! Summary statistics for a solved assignment model: how well each task is staffed, what the
+//! assignments cost, how many workers got their best option, and how the solver got there. Costs
+//! are the workers' costs for their tasks, before any combination with the tasks' scores.
+
+use std::rc::Rc;
+use std::time::Duration;
+use crate::network::Network;
+
+/// How one task was staffed and what its assignments cost
+#[derive(Clone, Debug)]
+pub struct TaskStatistics {
+    /// The task's name
+    pub name: Rc<String>,
+    /// The number of workers assigned to the task
+    pub num_assigned: usize,
+    /// The minimum number of workers the task requires
+    pub min_workers: usize,
+    /// The maximum number of workers the task accepts
+    pub max_workers: usize,
+    /// The mean cost of the task's assignments, if it has any
+    pub mean_cost: Option<f32>,
+    /// The lowest cost of the task's assignments, if it has any
+    pub min_cost: Option<f32>,
+    /// The highest cost of the task's assignments, if it has any
+    pub max_cost: Option<f32>,
+}
+
+/// A summary of the assignments in a solved network
+#[derive(Clone, Debug)]
+pub struct Statistics {
+    /// Statistics for each task, in the order the tasks were added
+    pub tasks: Vec<TaskStatistics>,
+    /// The number of assignments made at each distinct cost, from the lowest cost to the highest
+    pub cost_histogram: Vec<(f32, usize)>,
+    /// The number of workers assigned to a task
+    pub num_assigned: usize,
+    /// The number of workers in the network
+    pub num_workers: usize,
+    /// The number of assigned workers whose task costs as little as any task they could perform
+    pub num_best_option: usize,
+    /// How long the min cost flow took to solve, if the network was last solved that way
+    pub solve_time: Option<Duration>,
+    /// How many augmenting paths the min cost flow pushed flow down, if the network was last
+    /// solved that way
+    pub num_augmentations: Option<usize>,
+}
+
+impl Network {
+    /// Summarize the current assignments in a set of statistics
+    pub fn get_statistics(&self) -> Statistics {
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+        let assignments = self.assignments.borrow();
+
+        // the cost of every assignment, grouped by task
+        let mut task_costs: Vec<Vec<f32>> = vec![Vec::new(); tasks.len()];
+        let mut num_best_option = 0;
+        for (worker, assignment) in workers.iter().zip(assignments.iter()) {
+            if let Some(task_id) = assignment {
+                let cost = worker.get_affinity(*task_id).unwrap_or_default();
+                task_costs[*task_id].push(cost);
+                if worker.get_best_affinity().is_some_and(|best_cost| cost <= best_cost) {
+                    num_best_option += 1;
+                }
+            }
+        }
+
+        let mut all_costs: Vec<f32> = task_costs.iter().flatten().copied().collect();
+        all_costs.sort_by(f32::total_cmp);
+        let mut cost_histogram: Vec<(f32, usize)> = Vec::new();
+        for cost in all_costs {
+            match cost_histogram.last_mut() {
+                Some((last_cost, count)) if *last_cost == cost => *count += 1,
+                _ => cost_histogram.push((cost, 1))
+            }
+        }
+
+        Statistics {
+            tasks: tasks.iter()
+                .zip(task_costs.iter())
+                .map(|(task, costs)| TaskStatistics {
+                    name: Rc::clone(task.get_name()),
+                    num_assigned: costs.len(),
+                    min_workers: task.get_min_workers(),
+                    max_workers: task.get_max_workers(),
+                    mean_cost: if costs.is_empty() { None }
+                               else { Some(costs.iter().sum::<f32>() / costs.len() as f32) },
+                    min_cost: costs.iter().copied().min_by(f32::total_cmp),
+                    max_cost: costs.iter().copied().max_by(f32::total_cmp),
+                })
+                .collect(),
+            cost_histogram,
+            num_assigned: assignments.iter().flatten().count(),
+            num_workers: workers.len(),
+            num_best_option,
+            solve_time: self.solve_time.get(),
+            num_augmentations: self.num_augmentations.get(),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+/// A task that workers can be assigned to, along with the minimum number of workers it requires,
+/// the maximum number of workers it can accept, and the marginal cost of staffing it with each
+/// additional worker.
+#[derive(Clone, Debug)]
+pub struct Task {
+    name: Rc<String>,
+    min_workers: usize,
+    max_workers: usize,
+    staffing_costs: Vec<f32>,
+    details: TaskDetails,
+}
+
+/// Details that an input file can record about a task. The solver does not use them, but keeps
+/// them with the task so that they are written back out when the problem is converted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaskDetails {
+    /// How important the task is, where higher numbers are more important
+    pub priority: Option<i32>,
+    /// The name of the group the task belongs to, e.g. a department
+    pub group: Option<Rc<String>>,
+    /// The cost of each worker the task is short of its minimum
+    pub shortage_penalty: Option<f32>,
+    /// The skills a worker needs for the task
+    pub required_skills: Vec<Rc<String>>,
+}
+
+impl Task {
+    /// Create a new Task
+    pub(super) fn new(name: Rc<String>, min_workers: usize, max_workers: usize) -> Task {
+        Task {
+            name, min_workers, max_workers,
+            staffing_costs: Vec::new(),
+            details: TaskDetails::default(),
+        }
+    }
+
+    /// Get the task's name
+    pub fn get_name(&self) -> &Rc<String> {
+        &self.name
+    }
+
+    /// Get the minimum number of workers the task requires
+    pub fn get_min_workers(&self) -> usize {
+        self.min_workers
+    }
+
+    /// Get the maximum number of workers the task can accept
+    pub fn get_max_workers(&self) -> usize {
+        self.max_workers
+    }
+
+    /// Set the marginal cost of each additional worker: the first entry is the cost of the first
+    /// worker, the second is the cost of the second worker, and so on, with any workers beyond the
+    /// end of the list costing the same as the last entry. Costs must not decrease.
+    pub(super) fn set_staffing_costs(&mut self, staffing_costs: Vec<f32>) {
+        assert!(staffing_costs.windows(2).all(|pair| pair[0] <= pair[1]),
+                "Staffing costs for task {} must not decrease!", self.name);
+        self.staffing_costs = staffing_costs;
+    }
+
+    /// Get the marginal cost of each additional worker, which is empty if the task has no schedule
+    /// of staffing costs
+    pub fn get_staffing_costs(&self) -> &[f32] {
+        &self.staffing_costs
+    }
+
+    /// Check whether the task has a schedule of staffing costs
+    pub fn has_staffing_costs(&self) -> bool {
+        !self.staffing_costs.is_empty()
+    }
+
+    /// Set the task's priority, group, shortage penalty and required skills
+    pub(super) fn set_details(&mut self, details: TaskDetails) {
+        self.details = details;
+    }
+
+    /// Get the task's priority, group, shortage penalty and required skills
+    pub fn get_details(&self) -> &TaskDetails {
+        &self.details
+    }
+
+    /// Get the marginal cost of the `n`th worker assigned to the task, counting from 1
+    fn get_marginal_cost(&self, n: usize) -> f32 {
+        self.staffing_costs.get(n - 1)
+            .or_else(|| self.staffing_costs.last())
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Get the total staffing cost of assigning `num_workers` workers to the task
+    pub fn get_staffing_cost(&self, num_workers: usize) -> f32 {
+        (1..=num_workers).map(|n| self.get_marginal_cost(n)).sum()
+    }
+
+    /// Split the task's staffing into (cost, min workers, max workers) blocks of consecutive
+    /// workers that share a marginal cost. Since costs never decrease, a min cost flow through
+    /// parallel arcs with these bounds fills the cheaper blocks first, and the blocks' minimums
+    /// add up to the task's minimum.
+    pub fn get_staffing_blocks(&self) -> Vec<(f32, usize, usize)> {
+        let mut blocks: Vec<(f32, usize, usize)> = Vec::new();
+        let mut add_workers = |cost: f32, first: usize, count: usize| {
+            // workers first..first + count all cost the same; those up to the minimum are required
+            let required = self.min_workers.saturating_sub(first - 1).min(count);
+            match blocks.last_mut() {
+                Some((block_cost, block_min, block_max)) if *block_cost == cost => {
+                    *block_min += required;
+                    *block_max += count;
+                },
+                _ => blocks.push((cost, required, count))
+            }
+        };
+
+        let num_listed = self.staffing_costs.len().min(self.max_workers);
+        for n in 1..=num_listed {
+            add_workers(self.get_marginal_cost(n), n, 1);
+        }
+        if self.max_workers > num_listed || self.max_workers == 0 {
+            // all workers beyond the end of the list cost the same as the last one listed
+            add_workers(self.get_marginal_cost(num_listed + 1), num_listed + 1,
+                        self.max_workers - num_listed);
+        }
+        blocks
+    }
+}
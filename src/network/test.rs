@@ -1,80 +1,20 @@
 use std::rc::Rc;
-use crate::network::Network;
-use crate::ui::CurrentStatus;
-
-#[test]
-fn test_push_flow() {
-    // setup
-    let node_a_id = 0;
-    let node_b_id = 1;
-    let cost = 16.8;
-    let network = Network::new();
-    network.add_arc(node_a_id, node_b_id, cost, 1, 1);
-
-    // test
-    assert_eq!(network.nodes.borrow()[node_a_id].get_num_connections(), 1);
-    assert_eq!(network.nodes.borrow()[node_b_id].get_num_connections(), 0);
-    assert_eq!(network.arcs.borrow()[network.nodes.borrow()[node_a_id].get_first_connected_arc_id().unwrap()].get_end_node_id(),
-               node_b_id);
-    network.push_flow_down_path(&vec![1, 0]);
-    assert_eq!(network.nodes.borrow()[node_a_id].get_num_connections(), 0);
-    assert_eq!(network.nodes.borrow()[node_b_id].get_num_connections(), 1);
-    assert_eq!(network.arcs.borrow()[network.nodes.borrow()[node_b_id].get_first_connected_arc_id().unwrap()].get_end_node_id(),
-               node_a_id);
-    assert_eq!(network.arcs.borrow()[0].get_cost(), -cost);
-    assert_eq!(network.arcs.borrow()[0].get_start_node_id(), node_b_id);
-    assert_eq!(network.arcs.borrow()[0].get_end_node_id(), node_a_id);
-}
-
-#[test]
-fn test_shortest_path() {
-    // setup
-    let network = Network::new();
-    let task_names = vec![Rc::new("Task 1".into()),
-                                       Rc::new("Task 2".into())];
-    let worker_names = vec![
-        Rc::new("Worker 1".into()),
-        Rc::new("Worker 2".into()),
-    ];
-    // add task 1
-    network.add_task(Rc::clone(&task_names[0]), 1, 1);
-    network.add_task(Rc::clone(&task_names[1]),1, 1);
-    network.add_worker(Rc::clone(&worker_names[0]),
-                       &vec![(&task_names[0], 2.5_f32),
-                             (&task_names[1], 3.0_f32)]);
-    network.add_worker(Rc::clone(&worker_names[1]),
-                       &vec![(&task_names[0], 2.6_f32),
-                             (&task_names[1], 1.9_f32)]);
-
-    // test
-    assert_eq!(network.nodes.borrow().len(), 6);
-    assert_eq!(network.arcs.borrow().len(), 8);
-    let mut path = network.find_shortest_path().unwrap();
-    assert_eq!(path.len(), 4);
-    assert_eq!(*path.first().unwrap(), 1);
-    assert_eq!(*path.last().unwrap(), 0);
-    path.reverse();
-    assert_eq!(network.get_path_cost(&path), 1.9_f32);
-    path.reverse();
-    network.push_flow_down_path(&path);
-    for node_pair in path.windows(2) {
-        network.find_connecting_arc_id(node_pair[0], node_pair[1])
-            .expect(&*format!("Arc between {} and {} not inverted!", node_pair[1], node_pair[0]));
-    }
-}
+use crate::network::{Backup, CostNormalization, HeuristicLimit, MoveReason, Network, Optimality,
+                     ScoreCombination, Severity};
+use crate::status::CurrentStatus;
 
 #[test]
 fn test_min_cost_augmentation() {
     // setup
     let network = Network::new();
-    let task_names = vec![
+    let task_names = [
         Rc::new("Task 1".into()),
         Rc::new("Task 2".into()),
         Rc::new("Task 3".into()),
         Rc::new("Task 4".into()),
         Rc::new("Task 5".into()),
     ];
-    let worker_names = vec![
+    let worker_names = [
         Rc::new("Worker 1".to_string()),
         Rc::new("Worker 2".to_string()),
         Rc::new("Worker 3".to_string()),
@@ -92,54 +32,607 @@ fn test_min_cost_augmentation() {
     network.add_task(Rc::clone(&task_names[3]), 2, 3);
     network.add_task(Rc::clone(&task_names[4]), 1, 2);
     network.add_worker(worker_names[0].clone(),
-                       &vec![(&task_names[0], 3.0),
-                             (&task_names[1], 4.0), (&task_names[2], 1.5),
-                             (&task_names[3], 1.5), (&task_names[4], 5.0)]);
+                       &[(&task_names[0], 3.0),
+                         (&task_names[1], 4.0), (&task_names[2], 1.5),
+                         (&task_names[3], 1.5), (&task_names[4], 5.0)]);
     network.add_worker(worker_names[1].clone(),
-                       &vec![(&task_names[0], 4.0),
-                             (&task_names[1], 3.0), (&task_names[2], 6.0),
-                             (&task_names[3], 2.0), (&task_names[4], 1.0)]);
+                       &[(&task_names[0], 4.0),
+                         (&task_names[1], 3.0), (&task_names[2], 6.0),
+                         (&task_names[3], 2.0), (&task_names[4], 1.0)]);
     network.add_worker(worker_names[2].clone(),
-                       &vec![(&task_names[0], 2.0),
-                             (&task_names[1], 5.0), (&task_names[2], 4.0),
-                             (&task_names[3], 1.0), (&task_names[4], 3.0)]);
+                       &[(&task_names[0], 2.0),
+                         (&task_names[1], 5.0), (&task_names[2], 4.0),
+                         (&task_names[3], 1.0), (&task_names[4], 3.0)]);
     network.add_worker(worker_names[3].clone(),
-                       &vec![(&task_names[0], 3.0),
-                             (&task_names[1], 5.0), (&task_names[2], 1.0),
-                             (&task_names[3], 4.0), (&task_names[4], 0.0)]);
+                       &[(&task_names[0], 3.0),
+                         (&task_names[1], 5.0), (&task_names[2], 1.0),
+                         (&task_names[3], 4.0), (&task_names[4], 0.0)]);
     network.add_worker(worker_names[4].clone(),
-                       &vec![(&task_names[0], 1.0),
-                             (&task_names[1], 4.0), (&task_names[2], 2.0),
-                             (&task_names[3], 3.0), (&task_names[4], 5.0)]);
+                       &[(&task_names[0], 1.0),
+                         (&task_names[1], 4.0), (&task_names[2], 2.0),
+                         (&task_names[3], 3.0), (&task_names[4], 5.0)]);
     network.add_worker(worker_names[5].clone(),
-                       &vec![(&task_names[0], 5.0),
-                             (&task_names[1], 3.0), (&task_names[2], 1.0),
-                             (&task_names[3], 4.0), (&task_names[4], 2.0)]);
+                       &[(&task_names[0], 5.0),
+                         (&task_names[1], 3.0), (&task_names[2], 1.0),
+                         (&task_names[3], 4.0), (&task_names[4], 2.0)]);
     network.add_worker(worker_names[6].clone(),
-                       &vec![(&task_names[0], 1.0),
-                             (&task_names[1], 3.0), (&task_names[2], 5.0),
-                             (&task_names[3], 4.0), (&task_names[4], 2.0)]);
+                       &[(&task_names[0], 1.0),
+                         (&task_names[1], 3.0), (&task_names[2], 5.0),
+                         (&task_names[3], 4.0), (&task_names[4], 2.0)]);
     network.add_worker(worker_names[7].clone(),
-                       &vec![(&task_names[0], 4.0),
-                             (&task_names[1], 3.0), (&task_names[2], 5.0),
-                             (&task_names[3], 1.0), (&task_names[4], 2.0)]);
+                       &[(&task_names[0], 4.0),
+                         (&task_names[1], 3.0), (&task_names[2], 5.0),
+                         (&task_names[3], 1.0), (&task_names[4], 2.0)]);
     network.add_worker(worker_names[8].clone(),
-                       &vec![(&task_names[0], 5.0),
-                             (&task_names[1], 2.0), (&task_names[2], 3.0),
-                             (&task_names[3], 4.0), (&task_names[4], 1.0)]);
+                       &[(&task_names[0], 5.0),
+                         (&task_names[1], 2.0), (&task_names[2], 3.0),
+                         (&task_names[3], 4.0), (&task_names[4], 1.0)]);
     network.add_worker(worker_names[9].clone(),
-                       &vec![(&task_names[0], 2.0),
-                             (&task_names[1], 5.0), (&task_names[2], 1.0),
-                             (&task_names[3], 3.0), (&task_names[4], 4.0)]);
+                       &[(&task_names[0], 2.0),
+                         (&task_names[1], 5.0), (&task_names[2], 1.0),
+                         (&task_names[3], 3.0), (&task_names[4], 4.0)]);
 
     // test
-    assert_eq!(network.nodes.borrow().len(), 17);
-    assert_eq!(network.arcs.borrow().len(), 65);
-    assert_eq!(network.nodes.borrow()[0].get_num_connections(), 10);
-    assert_eq!(network.nodes.borrow()[1].get_num_connections(), 1);
+    assert_eq!(network.tasks.borrow().len(), 5);
+    assert_eq!(network.workers.borrow().len(), 10);
     network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
-    let total_cost = -network.get_cost_of_arcs_from_nodes(&task_names);
-    assert_eq!(network.nodes.borrow()[0].get_num_connections(), 0);
-    assert_eq!(network.nodes.borrow()[1].get_num_connections(), 4);
+    let total_cost = network.get_total_cost();
+    assert!(network.assignments.borrow().iter().all(Option::is_some));
+    let assignments = network.get_worker_assignments();
+    assert_eq!(assignments.get(&task_names[1]).unwrap().len(), 2);
+    assert!(assignments.values().map(Vec::len).sum::<usize>() == 10);
     assert!((total_cost - 12.5_f32).abs() / 12.5_f32 < 5e-10_f32);
 }
+
+#[test]
+fn test_unable_to_assign() {
+    // setup: both workers can only perform the first task, which accepts one of them
+    let network = Network::new();
+    let task_names = [Rc::new("Task 1".to_string()),
+                          Rc::new("Task 2".to_string())];
+    network.add_task(Rc::clone(&task_names[0]), 0, 1);
+    network.add_task(Rc::clone(&task_names[1]), 0, 1);
+    network.add_worker(Rc::new("Worker 1".to_string()), &[(&task_names[0], 1.0)]);
+    network.add_worker(Rc::new("Worker 2".to_string()), &[(&task_names[0], 2.0)]);
+
+    // test
+    let result = network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new()));
+    assert_eq!(result.err().unwrap().message, "Unable to assign all workers!");
+}
+
+#[test]
+fn test_unbounded_maxima() {
+    // setup: two tasks with no upper limit, given as the largest maximum
+    let network = Network::new();
+    let task_names = [Rc::new("Task 1".to_string()),
+                          Rc::new("Task 2".to_string())];
+    network.add_task(Rc::clone(&task_names[0]), 0, usize::MAX);
+    network.add_task(Rc::clone(&task_names[1]), 0, usize::MAX);
+    network.add_worker(Rc::new("Worker 1".to_string()),
+                       &[(&task_names[0], 1.0), (&task_names[1], 2.0)]);
+
+    // test: the total capacity does not overflow
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_total_cost(), 1.0);
+}
+
+#[test]
+fn test_stable_matching() {
+    // setup: both workers would rather do task A, and task A would rather have worker 2
+    let network = Network::new();
+    let task_names = [Rc::new("Task A".to_string()),
+                          Rc::new("Task B".to_string())];
+    let worker_names = [Rc::new("Worker 1".to_string()),
+                            Rc::new("Worker 2".to_string())];
+    network.add_task(Rc::clone(&task_names[0]), 0, 1);
+    network.add_task(Rc::clone(&task_names[1]), 0, 1);
+    network.add_worker(Rc::clone(&worker_names[0]),
+                       &[(&task_names[0], 1.0), (&task_names[1], 5.0)]);
+    network.add_worker(Rc::clone(&worker_names[1]),
+                       &[(&task_names[0], 2.0), (&task_names[1], 3.0)]);
+
+    // test: no stable matching without the tasks' preferences
+    assert!(network.find_stable_matching().is_err());
+    network.add_task_preferences(&worker_names[0],
+                                 &[(&task_names[0], 2.0), (&task_names[1], 1.0)]);
+    network.add_task_preferences(&worker_names[1],
+                                 &[(&task_names[0], 1.0), (&task_names[1], 1.0)]);
+
+    // test: the cheapest assignment gives task A to worker 1, which worker 2 and task A both dislike
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_total_cost(), 4.0);
+    assert_eq!(network.get_blocking_pairs(), None);
+    assert_eq!(network.record_blocking_pairs(), 1);
+    assert_eq!(network.get_blocking_pairs().unwrap(),
+               vec![(Rc::clone(&worker_names[1]), Rc::clone(&task_names[0]))]);
+
+    // test: the stable matching swaps the workers and has no blocking pairs
+    network.find_stable_matching().unwrap();
+    let assignments = network.get_worker_assignments();
+    assert_eq!(assignments.get(&task_names[0]).unwrap(), &vec![Rc::clone(&worker_names[1])]);
+    assert_eq!(assignments.get(&task_names[1]).unwrap(), &vec![Rc::clone(&worker_names[0])]);
+    assert_eq!(network.record_blocking_pairs(), 0);
+}
+
+#[test]
+fn test_stable_matching_capacity() {
+    // setup: three workers all prefer the one task that takes two of them, and it will not take
+    // worker 3 at all
+    let network = Network::new();
+    let task_name = Rc::new("Task".to_string());
+    let worker_names = [Rc::new("Worker 1".to_string()),
+                            Rc::new("Worker 2".to_string()),
+                            Rc::new("Worker 3".to_string())];
+    network.add_task(Rc::clone(&task_name), 0, 2);
+    for (worker_name, score) in worker_names.iter().zip([Some(2.0), Some(1.0), None]) {
+        network.add_worker(Rc::clone(worker_name), &[(&task_name, 1.0)]);
+        if let Some(s) = score {
+            network.add_task_preferences(worker_name, &[(&task_name, s)]);
+        }
+    }
+
+    // test
+    network.find_stable_matching().unwrap();
+    assert_eq!(network.get_worker_assignments().get(&task_name).unwrap().len(), 2);
+    assert_eq!(network.get_unassigned_workers(), vec![Rc::clone(&worker_names[2])]);
+}
+
+#[test]
+fn test_score_combination() {
+    // setup: worker 1 is cheaper for task A, but task A strongly prefers worker 2
+    let network = Network::new();
+    let task_names = [Rc::new("Task A".to_string()),
+                      Rc::new("Task B".to_string())];
+    let worker_names = [Rc::new("Worker 1".to_string()),
+                        Rc::new("Worker 2".to_string())];
+    network.add_task(Rc::clone(&task_names[0]), 1, 1);
+    network.add_task(Rc::clone(&task_names[1]), 1, 1);
+    network.add_worker(Rc::clone(&worker_names[0]),
+                       &[(&task_names[0], 1.0), (&task_names[1], 2.0)]);
+    network.add_worker(Rc::clone(&worker_names[1]),
+                       &[(&task_names[0], 2.0), (&task_names[1], 2.0)]);
+    let status = std::sync::Arc::new(CurrentStatus::new());
+
+    // test: combining without tasks' preferences is an error
+    network.set_score_combination(ScoreCombination::Min);
+    assert!(network.find_min_cost_max_flow(&status).is_err());
+    network.add_task_preferences(&worker_names[0],
+                                 &[(&task_names[0], 5.0), (&task_names[1], 1.0)]);
+    network.add_task_preferences(&worker_names[1],
+                                 &[(&task_names[0], 1.0), (&task_names[1], 1.0)]);
+
+    // test: workers' costs alone give task A to worker 1
+    network.set_score_combination(ScoreCombination::WorkerOnly);
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_total_cost(), 3.0);
+    assert_eq!(network.get_worker_score_total(), 3.0);
+    assert_eq!(network.get_task_score_total(), Some(6.0));
+
+    // test: an equally weighted sum gives task A to worker 2
+    network.set_score_combination(ScoreCombination::WeightedSum {
+        worker_weight: 1.0, task_weight: 1.0
+    });
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_total_cost(), 6.0);
+    assert_eq!(network.get_worker_score_total(), 4.0);
+    assert_eq!(network.get_task_score_total(), Some(2.0));
+
+    // test: products and minima follow the combined costs
+    network.set_score_combination(ScoreCombination::Product);
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_total_cost(), 4.0);
+    network.set_score_combination(ScoreCombination::Min);
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_total_cost(), 2.0);
+}
+
+#[test]
+fn test_normalize() {
+    let costs = [1.0, 3.0, 3.0, 5.0];
+    assert_eq!(CostNormalization::None.normalize(&costs), costs);
+    assert_eq!(CostNormalization::ZScore.normalize(&costs),
+               [-std::f32::consts::SQRT_2, 0.0, 0.0, std::f32::consts::SQRT_2]);
+    assert_eq!(CostNormalization::MinMax.normalize(&costs), [0.0, 0.5, 0.5, 1.0]);
+    assert_eq!(CostNormalization::Rank.normalize(&costs), [1.0, 2.5, 2.5, 4.0]);
+    assert_eq!(CostNormalization::SumTo(6.0).normalize(&costs), [0.5, 1.5, 1.5, 2.5]);
+
+    // test: costs without any spread, or that cannot be scaled, are handled
+    assert_eq!(CostNormalization::ZScore.normalize(&[4.0, 4.0]), [0.0, 0.0]);
+    assert_eq!(CostNormalization::MinMax.normalize(&[4.0, 4.0]), [0.0, 0.0]);
+    assert_eq!(CostNormalization::SumTo(1.0).normalize(&[-1.0, 1.0]), [-1.0, 1.0]);
+    assert!(CostNormalization::Rank.normalize(&[]).is_empty());
+}
+
+#[test]
+fn test_cost_normalization() {
+    // setup: worker 2 scores on a much wider scale than worker 1, so their costs dominate
+    let task_names = [Rc::new("Task A".to_string()),
+                      Rc::new("Task B".to_string()),
+                      Rc::new("Task C".to_string())];
+    let worker_names = [Rc::new("Worker 1".to_string()),
+                        Rc::new("Worker 2".to_string())];
+    let build = |normalization: CostNormalization| {
+        let network = Network::new();
+        network.set_cost_normalization(normalization);
+        network.add_task(Rc::clone(&task_names[0]), 1, 1);
+        network.add_task(Rc::clone(&task_names[1]), 1, 1);
+        network.add_task(Rc::clone(&task_names[2]), 0, 0);
+        network.add_worker(Rc::clone(&worker_names[0]), &[(&task_names[0], 1.0),
+                           (&task_names[1], 2.0), (&task_names[2], 3.0)]);
+        network.add_worker(Rc::clone(&worker_names[1]), &[(&task_names[0], 10.0),
+                           (&task_names[1], 20.0), (&task_names[2], 90.0)]);
+        network
+    };
+    let status = std::sync::Arc::new(CurrentStatus::new());
+
+    // test: with the costs as given, worker 2 gets task A and no raw costs are reported
+    let network = build(CostNormalization::None);
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_worker_assignments().get(&task_names[0]).unwrap(), &[
+        Rc::clone(&worker_names[1])]);
+    assert_eq!(network.get_total_cost(), 12.0);
+    assert_eq!(network.get_raw_cost_total(), None);
+
+    // test: with z-scores, worker 1 minds more about task A, so gets it
+    let network = build(CostNormalization::ZScore);
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_worker_assignments().get(&task_names[0]).unwrap(), &[
+        Rc::clone(&worker_names[0])]);
+    assert!(network.get_total_cost() < 0.0);
+    assert_eq!(network.get_raw_cost_total(), Some(21.0));
+    let workers = network.get_workers();
+    assert_eq!(workers[1].get_raw_affinity(1), Some(20.0));
+    assert_eq!(workers[1].get_raw_affinities(), [(0, 10.0), (1, 20.0), (2, 90.0)]);
+    assert!(workers[1].get_affinity(1).unwrap() < 0.0);
+}
+
+#[test]
+fn test_previous_roster() {
+    // setup: task A has fewer places than before, and task Gone was removed
+    let network = Network::new();
+    let name = |n: &str| Rc::new(n.to_string());
+    let (a, b, c) = (name("Task A"), name("Task B"), name("Task C"));
+    network.add_task(Rc::clone(&a), 0, 1);
+    network.add_task(Rc::clone(&b), 0, 3);
+    network.add_task(Rc::clone(&c), 0, 3);
+    network.add_worker(name("Worker 1"), &[(&b, 5.0), (&c, 1.0)]);
+    network.add_worker(name("Worker 2"), &[(&b, 1.0)]);
+    network.add_worker(name("Worker 3"), &[(&b, 1.0)]);
+    network.add_worker(name("Worker 4"), &[(&a, 1.0), (&b, 3.0), (&c, 3.0)]);
+    network.add_worker(name("Worker 5"), &[(&a, 2.0), (&b, 3.0), (&c, 3.0)]);
+    network.add_worker(name("Worker 6"), &[(&b, 1.0), (&c, 2.0)]);
+    network.set_previous_assignments(vec![
+        (name("Worker 1"), Rc::clone(&b)),
+        (name("Worker 2"), name("Task Gone")),
+        (name("Worker 3"), Rc::clone(&c)),
+        (name("Worker 4"), Rc::clone(&a)),
+        (name("Worker 5"), Rc::clone(&a)),
+        (name("Worker 6"), Rc::clone(&c)),
+        (name("Worker 7"), Rc::clone(&a)),
+    ], 2.0);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+
+    // test: worker 6 would save less than the penalty by moving, so stays put
+    assert_eq!(network.get_assignments()[5], Some(2));
+    assert_eq!(network.get_change_penalty_total(), Some(8.0));
+    let moves = network.get_moves().unwrap();
+    let moved: Vec<(&str, MoveReason)> = moves.iter()
+        .map(|change| (change.worker.as_str(), change.reason))
+        .collect();
+    assert_eq!(moved, [("Worker 1", MoveReason::LowerCost), ("Worker 2", MoveReason::TaskRemoved),
+                       ("Worker 3", MoveReason::NotEligible),
+                       ("Worker 5", MoveReason::OverCapacity)]);
+    assert_eq!(moves[0].to, Some(c));
+
+    // test: a worker moved to fill another task's minimum, at a higher cost to themselves, is
+    // not said to have been moved for a lower cost
+    let network = Network::new();
+    network.add_task(Rc::clone(&a), 0, 2);
+    network.add_task(Rc::clone(&b), 1, 1);
+    network.add_worker(name("Worker 1"), &[(&a, 1.0), (&b, 2.0)]);
+    network.add_worker(name("Worker 2"), &[(&a, 1.0)]);
+    network.set_previous_assignments(vec![(name("Worker 1"), Rc::clone(&a))], 1.0);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let moves = network.get_moves().unwrap();
+    assert_eq!(moves.len(), 1);
+    assert_eq!((moves[0].to.as_ref(), moves[0].reason), (Some(&b), MoveReason::Rebalanced));
+}
+
+#[test]
+fn test_backups() {
+    // setup: task A takes exactly one worker, so a backup there moves its worker to task B
+    let network = Network::new();
+    let name = |n: &str| Rc::new(n.to_string());
+    let (a, b) = (name("Task A"), name("Task B"));
+    network.add_task(Rc::clone(&a), 1, 1);
+    network.add_task(Rc::clone(&b), 1, 3);
+    network.add_worker(name("Worker 1"), &[(&a, 1.0), (&b, 4.0)]);
+    network.add_worker(name("Worker 2"), &[(&a, 2.0), (&b, 1.0)]);
+    network.add_worker(name("Worker 3"), &[(&b, 2.0)]);
+    network.add_worker(name("Worker 4"), &[(&a, 5.0), (&b, 3.0)]);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_backups(), None);
+    network.set_num_backups(1);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+
+    // test: worker 4 could also stand in at task A, but at a higher cost than worker 2
+    assert_eq!(network.get_total_cost(), 7.0);
+    assert_eq!(network.get_backups(), Some(vec![
+        Backup {
+            task: Rc::clone(&a),
+            rank: 1,
+            worker: name("Worker 2"),
+            current_task: Some(Rc::clone(&b)),
+            cost_increase: 2.0 - 1.0 + 4.0 - 1.0,
+        },
+        Backup {
+            task: Rc::clone(&b),
+            rank: 1,
+            worker: name("Worker 1"),
+            current_task: Some(Rc::clone(&a)),
+            cost_increase: 4.0 - 1.0 + 2.0 - 1.0,
+        },
+    ]));
+    network.set_num_backups(2);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let backups = network.get_backups().unwrap();
+    assert_eq!((backups[1].worker.as_str(), backups[1].rank), ("Worker 4", 2));
+    assert_eq!(backups[1].cost_increase, 5.0 - 1.0 + 4.0 - 3.0);
+}
+
+#[test]
+fn test_worker_groups() {
+    // setup: the trainee prefers task A and the mentor task B, and the cheapest way to share a
+    // task would be to split the group's two places between them
+    let network = Network::new();
+    let name = |n: &str| Rc::new(n.to_string());
+    let (a, b) = (name("Task A"), name("Task B"));
+    network.add_task(Rc::clone(&a), 0, 2);
+    network.add_task(Rc::clone(&b), 0, 3);
+    network.add_worker(name("Trainee"), &[(&a, 1.0), (&b, 4.0)]);
+    network.add_worker(name("Mentor"), &[(&a, 4.0), (&b, 2.0)]);
+    network.add_worker(name("Worker"), &[(&a, 1.0), (&b, 3.0)]);
+    network.add_worker_group(name("Pair"), &[name("Trainee"), name("Mentor")]);
+    assert!(network.find_problems().is_empty());
+
+    // test: the group goes to task B, where it costs 6, so that the other worker can have task A
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_assignments(), [Some(1), Some(1), Some(0)]);
+    assert_eq!(network.get_total_cost(), 7.0);
+    // the group is placed by holding it to each of its tasks in turn, which cannot be proven
+    // optimal against the flow that split it, at half the group's cost in each task
+    assert_eq!(network.get_optimality(), Some(Optimality {
+        proven_optimal: false,
+        lower_bound: 2.5 + 3.0 + 1.0,
+        gap: 0.5,
+        limit: Some(HeuristicLimit::GroupPlacement),
+    }));
+    assert_eq!(network.get_worker_groups(), [(name("Pair"), vec![name("Trainee"),
+                                                                  name("Mentor")])]);
+
+    // backups are found with the group held at its task, so only the other worker is listed
+    network.set_num_backups(1);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_assignments(), [Some(1), Some(1), Some(0)]);
+    assert_eq!(network.get_backups(), Some(vec![Backup {
+        task: Rc::clone(&b),
+        rank: 1,
+        worker: name("Worker"),
+        current_task: Some(Rc::clone(&a)),
+        cost_increase: 3.0 - 1.0,
+    }]));
+    network.set_num_backups(0);
+
+    // a group needs a task that all of its members can perform, with room for all of them
+    network.add_task(name("Task C"), 0, 1);
+    network.add_optional_worker(name("Worker 2"), &[(&a, 1.0)]);
+    network.add_optional_worker(name("Worker 3"), &[(&b, 1.0)]);
+    network.add_optional_worker(name("Worker 4"), &[(&a, 1.0), (&name("Task C"), 1.0)]);
+    network.add_optional_worker(name("Worker 5"), &[(&name("Task C"), 1.0)]);
+    network.add_worker_group(name("Apart"), &[name("Worker 2"), name("Worker 3")]);
+    network.add_worker_group(name("Crowded"), &[name("Worker 4"), name("Worker 5")]);
+    let problems: Vec<String> = network.find_problems().into_iter()
+        .map(|problem| problem.message)
+        .collect();
+    assert_eq!(problems, ["No task can be performed by every member of group Apart!",
+                          "Group Crowded has 2 members, more than any task they can all perform \
+                          accepts!"]);
+}
+
+#[test]
+fn test_conflicts() {
+    // setup: the first two workers both want task A, and the third does not mind
+    let network = Network::new();
+    let name = |n: &str| Rc::new(n.to_string());
+    let (a, b) = (name("Task A"), name("Task B"));
+    network.add_task(Rc::clone(&a), 0, 2);
+    network.add_task(Rc::clone(&b), 0, 2);
+    network.add_worker(name("Worker 1"), &[(&a, 0.0), (&b, 5.0)]);
+    network.add_worker(name("Worker 2"), &[(&a, 0.0), (&b, 3.0)]);
+    network.add_worker(name("Worker 3"), &[(&a, 2.0), (&b, 2.0)]);
+    let status = std::sync::Arc::new(CurrentStatus::new());
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_assignments()[..2], [Some(0), Some(0)]);
+    assert_eq!(network.get_optimality(), None);
+
+    // test: in conflict, the worker who minds task B less is moved there, and the search proves
+    // that no solution is cheaper
+    network.add_conflict(&name("Worker 1"), &name("Worker 2"));
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_assignments()[..2], [Some(0), Some(1)]);
+    assert_eq!(network.get_total_cost(), 5.0);
+    assert_eq!(network.get_optimality(),
+               Some(Optimality { proven_optimal: true, lower_bound: 5.0, gap: 0.0, limit: None }));
+    assert_eq!(network.get_conflicts(), [(name("Worker 1"), name("Worker 2"))]);
+
+    // workers in conflict cannot be kept together
+    network.add_worker_group(name("Pair"), &[name("Worker 1"), name("Worker 2")]);
+    let problems: Vec<String> = network.find_problems().into_iter()
+        .map(|problem| problem.message)
+        .collect();
+    assert_eq!(problems, ["Worker 1 and Worker 2 are in conflict, but both belong to group Pair!"]);
+    assert!(network.find_min_cost_max_flow(&status).is_err());
+}
+
+#[test]
+fn test_backups_with_conflicts() {
+    // setup: moving worker X to task A would send worker Y to task B, where worker Z is
+    let network = Network::new();
+    let name = |n: &str| Rc::new(n.to_string());
+    let (a, b, c) = (name("Task A"), name("Task B"), name("Task C"));
+    network.add_task(Rc::clone(&a), 1, 1);
+    network.add_task(Rc::clone(&b), 0, 2);
+    network.add_task(Rc::clone(&c), 0, 2);
+    network.add_worker(name("Worker X"), &[(&a, 1.0), (&c, 0.0)]);
+    network.add_worker(name("Worker Y"), &[(&a, 0.0), (&b, 1.0), (&c, 5.0)]);
+    network.add_worker(name("Worker Z"), &[(&b, 0.0)]);
+    network.add_conflict(&name("Worker Y"), &name("Worker Z"));
+    network.set_num_backups(1);
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    assert_eq!(network.get_assignments(), [Some(2), Some(0), Some(1)]);
+
+    // test: the chain of moves sends worker Y to task C instead, keeping them apart from Z
+    assert_eq!(network.get_backups(), Some(vec![
+        Backup {
+            task: Rc::clone(&a),
+            rank: 1,
+            worker: name("Worker X"),
+            current_task: Some(Rc::clone(&c)),
+            cost_increase: 1.0 + 5.0,
+        },
+        Backup {
+            task: Rc::clone(&c),
+            rank: 1,
+            worker: name("Worker Y"),
+            current_task: Some(Rc::clone(&a)),
+            cost_increase: 5.0 + 1.0,
+        },
+    ]));
+}
+
+#[test]
+fn test_staffing_costs() {
+    // setup: every worker prefers task A, but a third worker on either task costs 5 more
+    let network = Network::new();
+    let task_names = [Rc::new("Task A".to_string()),
+                      Rc::new("Task B".to_string())];
+    network.add_task(Rc::clone(&task_names[0]), 0, 4);
+    network.add_task(Rc::clone(&task_names[1]), 1, 4);
+    for i in 0..5 {
+        network.add_worker(Rc::new(format!("Worker {}", i)),
+                           &[(&task_names[0], 0.0), (&task_names[1], 1.0)]);
+    }
+    let status = std::sync::Arc::new(CurrentStatus::new());
+
+    // test: without staffing costs, task B only gets its minimum
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_worker_assignments().get(&task_names[1]).unwrap().len(), 1);
+    assert_eq!(network.get_staffing_cost_total(), None);
+    assert_eq!(network.get_total_cost(), 1.0);
+
+    // test: with staffing costs, the workers are balanced across the tasks
+    network.set_staffing_costs(&task_names[0], vec![0.0, 0.0, 5.0]);
+    network.set_staffing_costs(&task_names[1], vec![0.0, 0.0, 5.0]);
+    assert_eq!(network.tasks.borrow()[0].get_staffing_blocks(),
+               vec![(0.0, 0, 2), (5.0, 0, 2)]);
+    assert_eq!(network.tasks.borrow()[1].get_staffing_blocks(),
+               vec![(0.0, 1, 2), (5.0, 0, 2)]);
+    network.find_min_cost_max_flow(&status).unwrap();
+    let assignments = network.get_worker_assignments();
+    assert_eq!(assignments.get(&task_names[0]).unwrap().len(), 3);
+    assert_eq!(assignments.get(&task_names[1]).unwrap().len(), 2);
+    assert_eq!(network.get_staffing_cost_total(), Some(5.0));
+    assert_eq!(network.get_total_cost(), 7.0);
+}
+
+#[test]
+fn test_optional_workers() {
+    // setup: the task needs two workers but only one is mandatory
+    let network = Network::new();
+    let task_name = Rc::new("Task".to_string());
+    let worker_names = [Rc::new("Mandatory".to_string()),
+                        Rc::new("Cheap".to_string()),
+                        Rc::new("Expensive".to_string()),
+                        Rc::new("Helpful".to_string())];
+    network.add_task(Rc::clone(&task_name), 2, 3);
+    network.add_worker(Rc::clone(&worker_names[0]), &[(&task_name, 1.0)]);
+    network.add_optional_worker(Rc::clone(&worker_names[1]), &[(&task_name, 2.0)]);
+    network.add_optional_worker(Rc::clone(&worker_names[2]), &[(&task_name, 3.0)]);
+    let status = std::sync::Arc::new(CurrentStatus::new());
+
+    // test: the cheaper optional worker fills the task's minimum
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_total_cost(), 3.0);
+    assert!(network.get_unassigned_workers().is_empty());
+    assert_eq!(network.get_unused_optional_workers(), vec![Rc::clone(&worker_names[2])]);
+
+    // test: an optional worker with negative cost is used since it lowers the total
+    network.add_optional_worker(Rc::clone(&worker_names[3]), &[(&task_name, -1.0)]);
+    network.find_min_cost_max_flow(&status).unwrap();
+    assert_eq!(network.get_total_cost(), 0.0);
+    assert_eq!(network.get_unused_optional_workers(),
+               vec![Rc::clone(&worker_names[1]), Rc::clone(&worker_names[2])]);
+}
+
+#[test]
+fn test_statistics() {
+    // setup: two workers want the same task, which only has room for one of them
+    let network = Network::new();
+    let task_names = [Rc::new("Popular".to_string()), Rc::new("Unpopular".to_string()),
+                      Rc::new("Unstaffed".to_string())];
+    network.add_task(Rc::clone(&task_names[0]), 1, 1);
+    network.add_task(Rc::clone(&task_names[1]), 1, 2);
+    network.add_task(Rc::clone(&task_names[2]), 0, 1);
+    network.add_worker(Rc::new("Alice".to_string()),
+                       &[(&task_names[0], 1.0), (&task_names[1], 3.0), (&task_names[2], 9.0)]);
+    network.add_worker(Rc::new("Bob".to_string()),
+                       &[(&task_names[0], 1.0), (&task_names[1], 2.0), (&task_names[2], 9.0)]);
+    network.add_worker(Rc::new("Carol".to_string()), &[(&task_names[1], 2.0)]);
+
+    // test: nothing has been solved yet
+    let statistics = network.get_statistics();
+    assert_eq!(statistics.num_assigned, 0);
+    assert!(statistics.solve_time.is_none());
+
+    // test: Alice gets the popular task, leaving Bob and Carol on the unpopular one
+    network.find_min_cost_max_flow(&std::sync::Arc::new(CurrentStatus::new())).unwrap();
+    let statistics = network.get_statistics();
+    assert_eq!(statistics.num_assigned, 3);
+    assert_eq!(statistics.num_workers, 3);
+    assert_eq!(statistics.num_best_option, 2);
+    assert!(statistics.solve_time.is_some());
+    assert!(statistics.num_augmentations.unwrap() > 0);
+    assert_eq!(statistics.cost_histogram, vec![(1.0, 1), (2.0, 2)]);
+    let unpopular = &statistics.tasks[1];
+    assert_eq!((unpopular.num_assigned, unpopular.min_workers, unpopular.max_workers), (2, 1, 2));
+    assert_eq!((unpopular.mean_cost, unpopular.min_cost, unpopular.max_cost),
+               (Some(2.0), Some(2.0), Some(2.0)));
+    let unstaffed = &statistics.tasks[2];
+    assert_eq!(unstaffed.num_assigned, 0);
+    assert!(unstaffed.mean_cost.is_none());
+}
+
+#[test]
+fn test_find_problems() {
+    // setup: three mandatory workers for tasks with room for two, one of whom can do nothing
+    let network = Network::new();
+    let task_names = [Rc::new("Busy".to_string()), Rc::new("Picky".to_string())];
+    network.add_task(Rc::clone(&task_names[0]), 1, 1);
+    network.add_task(Rc::clone(&task_names[1]), 2, 1);
+    network.add_worker(Rc::new("Alice".to_string()), &[(&task_names[0], 1.0)]);
+    network.add_worker(Rc::new("Bob".to_string()), &[(&task_names[0], 1.0), (&task_names[1], 2.0)]);
+    network.add_worker(Rc::new("Carol".to_string()), &[]);
+
+    // test
+    let problems: Vec<(Severity, String)> = network.find_problems().into_iter()
+        .map(|problem| (problem.severity, problem.message))
+        .collect();
+    assert_eq!(problems, [
+        (Severity::Error, "Worker Carol has no eligible tasks!".to_string()),
+        (Severity::Error,
+         "Only 1 workers are eligible for task Picky, which needs at least 2!".to_string()),
+        (Severity::Error,
+         "Not enough capacity for workers: 3 mandatory workers for a total maximum of 2!"
+             .to_string()),
+    ]);
+}
@@ -0,0 +1,107 @@
+//! Checks on an assignment model that can be made without solving it. They find tasks that no
+//! worker can perform, workers who cannot perform any task, groups of workers who cannot share any
+//! task or who are in conflict with each other, and too few or too many workers for the tasks'
+//! minimums and maximums. Problems that make the model infeasible are errors, while the rest are
+//! warnings about parts of the model that can never be used.
+
+use crate::network::Network;
+
+/// How serious a problem with the model is
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The model cannot be solved (or the input cannot be read) until the problem is fixed
+    Error,
+    /// The model can be solved, but probably not as intended
+    Warning,
+}
+
+/// A problem found in the model, described for the user
+#[derive(Clone, Debug)]
+pub struct InputProblem {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Network {
+    /// Find every problem with the tasks and workers that is apparent without solving the network
+    pub fn find_problems(&self) -> Vec<InputProblem> {
+        let tasks = self.tasks.borrow();
+        let workers = self.workers.borrow();
+        let mut problems = Vec::new();
+        let mut report = |severity, message| problems.push(InputProblem { severity, message });
+
+        let mut num_eligible = vec![0_usize; tasks.len()];
+        for worker in workers.iter() {
+            for (task_id, _) in worker.get_affinities() {
+                num_eligible[*task_id] += 1;
+            }
+            if worker.get_affinities().is_empty() {
+                if worker.is_optional() {
+                    report(Severity::Warning, format!(
+                        "Optional worker {} has no eligible tasks and will never be assigned!",
+                        worker.get_name()));
+                } else {
+                    report(Severity::Error,
+                           format!("Worker {} has no eligible tasks!", worker.get_name()));
+                }
+            }
+        }
+
+        for (task, eligible) in tasks.iter().zip(num_eligible) {
+            let min_workers = task.get_min_workers();
+            if eligible == 0 && min_workers == 0 {
+                report(Severity::Warning,
+                       format!("No worker is eligible for task {}!", task.get_name()));
+            } else if eligible == 0 {
+                report(Severity::Error, format!(
+                    "No worker is eligible for task {}, which needs at least {}!",
+                    task.get_name(), min_workers));
+            } else if eligible < min_workers {
+                report(Severity::Error, format!(
+                    "Only {} workers are eligible for task {}, which needs at least {}!",
+                    eligible, task.get_name(), min_workers));
+            }
+        }
+
+        for (name, members) in self.worker_groups.borrow().iter() {
+            let shared_tasks: Vec<usize> = workers[members[0]].get_affinities().iter()
+                .map(|(task_id, _)| *task_id)
+                .filter(|task_id| members.iter()
+                    .all(|worker_id| workers[*worker_id].get_affinity(*task_id).is_some()))
+                .collect();
+            if shared_tasks.is_empty() {
+                report(Severity::Error,
+                       format!("No task can be performed by every member of group {}!", name));
+            } else if shared_tasks.iter()
+                .all(|task_id| tasks[*task_id].get_max_workers() < members.len()) {
+                report(Severity::Error, format!(
+                    "Group {} has {} members, more than any task they can all perform accepts!",
+                    name, members.len()));
+            }
+            for (first, second) in self.conflicts.borrow().iter() {
+                if members.contains(first) && members.contains(second) {
+                    report(Severity::Error, format!(
+                        "{} and {} are in conflict, but both belong to group {}!",
+                        workers[*first].get_name(), workers[*second].get_name(), name));
+                }
+            }
+        }
+
+        let total_min: usize = tasks.iter().map(|task| task.get_min_workers()).sum();
+        let total_max = tasks.iter()
+            .fold(0_usize, |total, task| total.saturating_add(task.get_max_workers()));
+        let num_mandatory = workers.iter().filter(|worker| !worker.is_optional()).count();
+        if workers.len() < total_min {
+            report(Severity::Error, format!(
+                "Not enough workers to assign: {} workers for a total minimum of {}!",
+                workers.len(), total_min));
+        }
+        if num_mandatory > total_max {
+            report(Severity::Error, format!(
+                "Not enough capacity for workers: {} mandatory workers for a total maximum of {}!",
+                num_mandatory, total_max));
+        }
+
+        problems
+    }
+}
@@ -0,0 +1,135 @@
+use std::rc::Rc;
+
+/// A worker to be assigned to a task, along with the cost of assigning the worker to each task they
+/// can perform and, optionally, how each task scores the worker (lower is better). Tasks are
+/// identified by their index in the network's list of tasks. Mandatory workers must be assigned,
+/// while optional workers are only assigned when they are needed or make the assignment cheaper.
+/// A worker can also carry the values of the network's worker attributes (e.g. an employee ID),
+/// which the solver does not use but which are written out with the worker's assignment. When the
+/// network rescales each worker's costs, the worker keeps their costs as given (the raw costs) for
+/// reporting, while the solver uses the rescaled ones. When the costs weigh together several
+/// criteria, the worker also keeps their value for each task on each criterion.
+#[derive(Clone, Debug)]
+pub struct Worker {
+    name: Rc<String>,
+    affinities: Vec<(usize, f32)>,
+    raw_affinities: Vec<(usize, f32)>,
+    criterion_values: Vec<Vec<(usize, f32)>>,
+    task_scores: Vec<(usize, f32)>,
+    optional: bool,
+    attributes: Vec<String>,
+}
+
+impl Worker {
+    /// Create a new Worker with the costs the solver uses and the costs as given
+    pub(super) fn new(name: Rc<String>, affinities: Vec<(usize, f32)>,
+                      raw_affinities: Vec<(usize, f32)>, optional: bool) -> Worker {
+        Worker {
+            name,
+            affinities,
+            raw_affinities,
+            criterion_values: Vec::new(),
+            task_scores: Vec::new(),
+            optional,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Get the worker's name
+    pub fn get_name(&self) -> &Rc<String> {
+        &self.name
+    }
+
+    /// Check whether the worker is optional rather than mandatory
+    pub fn is_optional(&self) -> bool {
+        self.optional
+    }
+
+    /// Get the (task index, cost) pairs for every task the worker can perform
+    pub fn get_affinities(&self) -> &[(usize, f32)] {
+        &self.affinities
+    }
+
+    /// Get the cost of assigning the worker to the task with the given index, if they can
+    /// perform it
+    pub fn get_affinity(&self, task_id: usize) -> Option<f32> {
+        self.affinities.iter()
+            .find(|(t, _)| *t == task_id)
+            .map(|(_, cost)| *cost)
+    }
+
+    /// Get the (task index, cost) pairs for every task the worker can perform, with the costs as
+    /// given before the network rescaled them
+    pub fn get_raw_affinities(&self) -> &[(usize, f32)] {
+        &self.raw_affinities
+    }
+
+    /// Get the cost, as given before the network rescaled it, of assigning the worker to the task
+    /// with the given index, if they can perform it
+    pub fn get_raw_affinity(&self, task_id: usize) -> Option<f32> {
+        self.raw_affinities.iter()
+            .find(|(t, _)| *t == task_id)
+            .map(|(_, cost)| *cost)
+    }
+
+    /// Record the worker's (task index, value) pairs for each of the network's criteria, replacing
+    /// any values recorded previously
+    pub(super) fn set_criterion_values(&mut self, criterion_values: Vec<Vec<(usize, f32)>>) {
+        self.criterion_values = criterion_values;
+    }
+
+    /// Get the (task index, value) pairs for the criterion with the given index in the network's
+    /// list of criteria, which is empty if none were recorded
+    pub fn get_criterion_values(&self, criterion_id: usize) -> &[(usize, f32)] {
+        self.criterion_values.get(criterion_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get the worker's unweighted value for the task with the given index on the criterion with
+    /// the given index, if one was recorded
+    pub fn get_criterion_value(&self, criterion_id: usize, task_id: usize) -> Option<f32> {
+        self.get_criterion_values(criterion_id).iter()
+            .find(|(t, _)| *t == task_id)
+            .map(|(_, value)| *value)
+    }
+
+    /// Get the lowest cost of assigning the worker to any task they can perform, if there is one
+    pub fn get_best_affinity(&self) -> Option<f32> {
+        self.affinities.iter()
+            .map(|(_, cost)| *cost)
+            .min_by(f32::total_cmp)
+    }
+
+    /// Record how each task scores the worker, replacing any scores recorded previously
+    pub(super) fn set_task_scores(&mut self, task_scores: Vec<(usize, f32)>) {
+        self.task_scores = task_scores;
+    }
+
+    /// Get the (task index, score) pairs for every task that scored the worker
+    pub fn get_task_scores(&self) -> &[(usize, f32)] {
+        &self.task_scores
+    }
+
+    /// Check whether any task has scored the worker
+    pub fn has_task_scores(&self) -> bool {
+        !self.task_scores.is_empty()
+    }
+
+    /// Get the score the task with the given index gave the worker, if the task would accept them
+    pub fn get_task_score(&self, task_id: usize) -> Option<f32> {
+        self.task_scores.iter()
+            .find(|(t, _)| *t == task_id)
+            .map(|(_, score)| *score)
+    }
+
+    /// Record the worker's value for each of the network's worker attributes, replacing any values
+    /// recorded previously
+    pub(super) fn set_attributes(&mut self, attributes: Vec<String>) {
+        self.attributes = attributes;
+    }
+
+    /// Get the worker's value for the attribute with the given index in the network's list of
+    /// worker attributes, which is blank if none was recorded
+    pub fn get_attribute(&self, attribute_id: usize) -> &str {
+        self.attributes.get(attribute_id).map_or("", String::as_str)
+    }
+}
@@ -0,0 +1,189 @@
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+use crate::io::{CsvDialect, FileType, OutputLayout, OverwritePolicy, ParseError, RankScheme,
+                ReadError, read_previous_assignments, Reader, reader_factory, save_atomically,
+                SourceLocation, ValidationIssue, write_statistics_file, writer_factory};
+use crate::network::{CostNormalization, FeasibilityError, Network, Objective, ScoreCombination};
+use crate::status::StatusTracker;
+
+/// Choices that control how the solver turns its input into assignments
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SolverOptions {
+    pub objective: Objective,
+    pub score_combination: ScoreCombination,
+    pub normalization: CostNormalization,
+    pub layout: OutputLayout,
+    pub overwrite: OverwritePolicy,
+    pub dialect: CsvDialect,
+    pub ranking: RankScheme,
+    pub previous_roster: Option<PreviousRoster>,
+    /// How many backup workers to list for each task, or 0 for none
+    pub num_backups: usize,
+}
+
+/// An output file from an earlier run whose assignments should change as little as possible
+#[derive(Clone, Debug)]
+pub(crate) struct PreviousRoster {
+    /// The CSV output file holding the previous assignments, in either layout
+    pub filename: String,
+    /// The cost added to assigning a worker to any task but the one they had before
+    pub change_penalty: f32,
+}
+
+/// The stage at which the solver gave up, along with the reason
+#[derive(Debug)]
+pub(crate) enum SolverError {
+    /// The input file could not be opened or could not be parsed
+    Input(ReadError),
+    /// The problem described by the input has no solution
+    Infeasible(FeasibilityError),
+    /// The output file could not be written
+    Output(std::io::Error),
+}
+
+impl Display for SolverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverError::Input(e) => match e.describe_location() {
+                Some(location) => write!(f, "{} ({})", e, location),
+                None => write!(f, "{}", e)
+            },
+            SolverError::Output(e) => write!(f, "{}", e),
+            SolverError::Infeasible(e) => write!(f, "{}", e)
+        }
+    }
+}
+
+/// Everything found by validating an input file: the size of the problem it describes, and every
+/// issue with it
+pub(crate) struct ValidationReport {
+    pub num_tasks: usize,
+    pub num_workers: usize,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Count the issues that stop the input from being read or solved
+    pub fn count_errors(&self) -> usize {
+        self.issues.iter().filter(|issue| issue.is_error()).count()
+    }
+}
+
+impl Display for ValidationReport {
+    /// List the issues one per line
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines: Vec<String> = self.issues.iter().map(ValidationIssue::to_string).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+pub(crate) struct Solver {
+    reader: RefCell<Box<dyn Reader>>,
+    writer_type: FileType,
+    options: SolverOptions,
+    network: Network,
+}
+
+impl Solver {
+    pub fn new(in_file_type: FileType, out_file_type: FileType, options: SolverOptions) -> Self {
+        let network = Network::new();
+        network.set_score_combination(options.score_combination);
+        network.set_cost_normalization(options.normalization);
+        network.set_num_backups(options.num_backups);
+        Solver {
+            reader: RefCell::new(reader_factory(in_file_type, options.dialect, &options.ranking)),
+            writer_type: out_file_type,
+            options,
+            network
+        }
+    }
+
+    /// Read the input file, assign the workers according to the chosen objective and write the
+    /// assignments to the output file, reporting progress to the status tracker along the way.
+    /// Reporting the outcome is left to the caller.
+    pub fn assign_workers(&self, infile: String, outfile: String, status: &dyn StatusTracker)
+        -> Result<(), SolverError> {
+        self.read_input(infile.clone())?;
+        self.check_task_preferences(&infile)?;
+        if let Some(roster) = &self.options.previous_roster {
+            let assignments = read_previous_assignments(&roster.filename, &self.options.dialect)
+                .map_err(|err| SolverError::Input(err.in_file(&roster.filename)))?;
+            self.network.set_previous_assignments(assignments, roster.change_penalty);
+        }
+
+        let solve_result = match self.options.objective {
+            Objective::MinCost => self.network.find_min_cost_max_flow(status),
+            Objective::StableMatching => self.network.find_stable_matching(),
+            Objective::MinCostAndStability => self.network.find_min_cost_max_flow(status)
+                .map(|_| {
+                    self.network.record_blocking_pairs();
+                })
+        };
+        solve_result.map_err(SolverError::Infeasible)?;
+
+        let writer = writer_factory(self.writer_type, self.reader.borrow().clone_task_names(),
+                                    self.options.layout, self.options.dialect);
+        save_atomically(&outfile, self.options.overwrite,
+                        |temp_file| writer.write_file(&self.network, temp_file))
+            .map_err(SolverError::Output)
+    }
+
+    /// Write a report of statistics about the assignments made by `assign_workers` to a CSV file
+    pub fn write_statistics(&self, filename: String) -> Result<(), SolverError> {
+        let statistics = self.network.get_statistics();
+        save_atomically(&filename, self.options.overwrite,
+                        |temp_file| write_statistics_file(&statistics, &self.options.dialect,
+                                                          temp_file))
+            .map_err(SolverError::Output)
+    }
+
+    /// Read the input file without solving it, collecting every issue that would stop it being
+    /// read or solved rather than stopping at the first. Only a file that cannot be opened or read
+    /// at all is an error.
+    pub fn validate_input(&self, infile: String) -> Result<ValidationReport, SolverError> {
+        let issues = self.reader.borrow_mut()
+            .validate_file(infile, &self.network)
+            .map_err(|err| SolverError::Input(ReadError::Io(err)))?;
+        Ok(ValidationReport {
+            num_tasks: self.network.get_tasks().len(),
+            num_workers: self.network.get_workers().len(),
+            issues,
+        })
+    }
+
+    /// Read the input file and write the same problem back out as an input file of the output
+    /// file type
+    pub fn convert_input(&self, infile: String, outfile: String) -> Result<(), SolverError> {
+        self.read_input(infile)?;
+        let writer = writer_factory(self.writer_type, self.reader.borrow().clone_task_names(),
+                                    self.options.layout, self.options.dialect);
+        save_atomically(&outfile, self.options.overwrite,
+                        |temp_file| writer.write_input_file(&self.network, temp_file))
+            .map_err(SolverError::Output)
+    }
+
+    /// Populate the network from the input file
+    fn read_input(&self, infile: String) -> Result<(), SolverError> {
+        self.reader.borrow_mut()
+            .read_file(infile.clone(), &self.network)
+            .map_err(|err| SolverError::Input(err.in_file(&infile)))
+    }
+
+    /// Check that the input holds the tasks' preferences for workers if the chosen objective or
+    /// score combination needs them. Their absence is a problem with the input file rather than
+    /// an infeasible problem, so it is reported as a parse error.
+    fn check_task_preferences(&self, infile: &str) -> Result<(), SolverError> {
+        let message = if self.options.objective == Objective::StableMatching {
+            "Stable matching requires tasks' preferences for workers!"
+        } else if self.options.score_combination != ScoreCombination::WorkerOnly {
+            "Combining scores requires tasks' preferences for workers!"
+        } else {
+            return Ok(());
+        };
+        if self.network.get_workers().iter().any(|worker| worker.has_task_scores()) {
+            return Ok(());
+        }
+        let err = ParseError::malformed(SourceLocation::default(), message.to_string());
+        Err(SolverError::Input(err.in_file(infile).into()))
+    }
+}
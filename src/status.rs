@@ -0,0 +1,53 @@
+//! The solver's progress, shared between the solver and whatever reports it: the GUI's status
+//! line or the progress line printed by the command-line interface.
+
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+pub enum Status {
+    Success,
+    Failure(String), // error message
+    InProgress(f32), // fraction complete
+    NotStarted
+}
+
+pub struct CurrentStatus {
+    status: Mutex<Status>
+}
+
+impl CurrentStatus {
+    pub fn new() -> Self {
+        CurrentStatus {
+            status: Mutex::new(Status::NotStarted)
+        }
+    }
+
+    pub fn get_status(&self) -> Status {
+        self.status.lock().unwrap().clone()
+    }
+
+}
+
+/// Anything that can be kept up to date on the solver's progress, such as the status shared with
+/// the GUI or the progress line printed by the command-line interface
+pub trait StatusTracker {
+    fn set_status(&self, new_status: Status);
+}
+
+impl StatusTracker for CurrentStatus {
+    fn set_status(&self, new_status: Status) {
+        *self.status.lock().unwrap() = new_status;
+    }
+}
+
+impl<T: StatusTracker + ?Sized> StatusTracker for Arc<T> {
+    fn set_status(&self, new_status: Status) {
+        (**self).set_status(new_status);
+    }
+}
+
+impl Default for CurrentStatus {
+    fn default() -> Self {
+        CurrentStatus::new()
+    }
+}
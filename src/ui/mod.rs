@@ -1,41 +1,16 @@
-use std::sync::{Arc, Mutex};
+//! The graphical interface used by the `assignment-solver` binary, built only with the `gui`
+//! feature.
+
+use std::sync::Arc;
 use eframe::egui::Vec2;
+use crate::status::CurrentStatus;
 
-mod solver;
 mod solver_gui;
 
-#[derive(Clone)]
-pub enum Status {
-    Success,
-    Failure(String), // error message
-    InProgress(f32), // fraction complete
-    NotStarted
-}
-
-pub struct CurrentStatus {
-    status: Mutex<Status>
-}
-
-impl CurrentStatus {
-    pub fn new() -> Self {
-        CurrentStatus {
-            status: Mutex::new(Status::NotStarted)
-        }
-    }
-
-    pub fn get_status(&self) -> Status {
-        self.status.lock().unwrap().clone()
-    }
-
-    pub fn set_status(&self, new_status: Status) {
-        *self.status.lock().unwrap() = new_status;
-    }
-}
-
 pub fn launch_ui(status_tracker: Arc<CurrentStatus>) {
     let options = eframe::NativeOptions {
         drag_and_drop_support: true,
-        initial_window_size: Some(Vec2 { x: 1024.0, y: 384.0 }),
+        initial_window_size: Some(Vec2 { x: 1024.0, y: 512.0 }),
         ..Default::default()
     };
 
@@ -5,13 +5,19 @@ use eframe::egui::{Color32, FontId};
 use eframe::egui::FontFamily::Proportional;
 use eframe::egui::panel::TopBottomSide;
 use eframe::egui::TextStyle;
-use crate::io::FileType;
-use crate::ui::{CurrentStatus, Status};
-use crate::ui::solver::Solver;
+use crate::io::{DecimalSeparator, Delimiter, FileType, OutputLayout, OverwritePolicy, RankCosts,
+                ReadError, TextEncoding, UnrankedTasks};
+use crate::network::{CostNormalization, Objective, ScoreCombination};
+use crate::solver::{PreviousRoster, Solver, SolverError, SolverOptions};
+use crate::status::{CurrentStatus, Status, StatusTracker};
 
 pub(super) struct SolverGui {
     infile: Option<String>,
     outfile: Option<String>,
+    // the layout of CSV input: a cost matrix, one worker-task pair per row, or ranked tasks
+    csv_input_type: FileType,
+    write_statistics: bool,
+    options: SolverOptions,
     cur_status: Arc<CurrentStatus>
 }
 
@@ -35,6 +41,9 @@ impl SolverGui {
         SolverGui {
             infile: None,
             outfile: None,
+            csv_input_type: FileType::Csv,
+            write_statistics: false,
+            options: SolverOptions::default(),
             cur_status: status_tracker
         }
     }
@@ -64,6 +73,42 @@ impl SolverGui {
                         });
                     }
                 });
+                ui.horizontal(|ui| {
+                    let csv_input_type = &mut self.csv_input_type;
+                    ui.label("CSV input lists:");
+                    ui.radio_value(csv_input_type, FileType::Csv,
+                                   "Each worker's cost for every task");
+                    ui.radio_value(csv_input_type, FileType::LongCsv,
+                                   "One worker, task and cost per row");
+                    ui.radio_value(csv_input_type, FileType::RankedCsv,
+                                   "Each worker's tasks in order of preference");
+                });
+                if self.csv_input_type == FileType::RankedCsv {
+                    ui.horizontal(|ui| {
+                        let ranking = &mut self.options.ranking;
+                        ui.label("Each rank costs:");
+                        if ui.radio(ranking.costs == RankCosts::Linear,
+                                    "Its number (1, 2, 3...)").clicked() {
+                            ranking.costs = RankCosts::Linear;
+                        }
+                        if ui.radio(ranking.costs == RankCosts::Exponential,
+                                    "Double the one before (1, 2, 4...)").clicked() {
+                            ranking.costs = RankCosts::Exponential;
+                        }
+                        ui.label("Unranked tasks:");
+                        if ui.radio(ranking.unranked == UnrankedTasks::Ineligible, "Ineligible")
+                            .clicked() {
+                            ranking.unranked = UnrankedTasks::Ineligible;
+                        }
+                        if ui.radio(matches!(ranking.unranked, UnrankedTasks::Cost(_)), "Cost")
+                            .clicked() {
+                            ranking.unranked = UnrankedTasks::Cost(10.0);
+                        }
+                        if let UnrankedTasks::Cost(cost) = &mut ranking.unranked {
+                            ui.add(egui::DragValue::new(cost).speed(0.5));
+                        }
+                    });
+                }
 
                 ui.vertical_centered(|ui| ui.heading("Select an output file:"));
                 ui.horizontal(|ui| {
@@ -81,6 +126,118 @@ impl SolverGui {
                         });
                     }
                 });
+                ui.horizontal(|ui| {
+                    let btn = egui::Button::new("Select previous roster (optional):");
+                    if ui.add(btn).clicked() {
+                        if let Some(path) = rfd::FileDialog::new().pick_file() {
+                            self.options.previous_roster = Some(PreviousRoster {
+                                filename: path.display().to_string(),
+                                change_penalty: 1.0,
+                            });
+                        }
+                    }
+                    if let Some(roster) = &mut self.options.previous_roster {
+                        ui.monospace(&roster.filename);
+                        ui.label("Change penalty:");
+                        ui.add(egui::DragValue::new(&mut roster.change_penalty).speed(0.1));
+                    }
+                    if self.options.previous_roster.is_some() && ui.button("Clear").clicked() {
+                        self.options.previous_roster = None;
+                    }
+                });
+                ui.checkbox(&mut self.write_statistics,
+                            "Also write a statistics report next to the output file");
+                ui.horizontal(|ui| {
+                    ui.label("Backup workers to list for each task:");
+                    ui.add(egui::DragValue::new(&mut self.options.num_backups).speed(0.1));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("If the output file exists:");
+                    let overwrite = &mut self.options.overwrite;
+                    ui.radio_value(overwrite, OverwritePolicy::Replace, "Replace it");
+                    ui.radio_value(overwrite, OverwritePolicy::Backup, "Back it up first");
+                    ui.radio_value(overwrite, OverwritePolicy::Refuse, "Stop");
+                });
+
+                ui.vertical_centered(|ui| ui.heading("Read and write CSV files with:"));
+                ui.horizontal(|ui| {
+                    let delimiter = &mut self.options.dialect.delimiter;
+                    ui.label("Delimiter:");
+                    ui.radio_value(delimiter, Delimiter::Auto, "Detect");
+                    ui.radio_value(delimiter, Delimiter::Comma, "Comma");
+                    ui.radio_value(delimiter, Delimiter::Semicolon, "Semicolon");
+                    ui.radio_value(delimiter, Delimiter::Tab, "Tab");
+                });
+                ui.horizontal(|ui| {
+                    let decimal_separator = &mut self.options.dialect.decimal_separator;
+                    ui.label("Decimals:");
+                    ui.radio_value(decimal_separator, DecimalSeparator::Dot, "1.5");
+                    ui.radio_value(decimal_separator, DecimalSeparator::Comma, "1,5");
+                    let encoding = &mut self.options.dialect.encoding;
+                    ui.label("Encoding:");
+                    ui.radio_value(encoding, TextEncoding::UTF_8, "UTF-8");
+                    ui.radio_value(encoding, TextEncoding::WINDOWS_1252, "Windows-1252");
+                });
+
+                ui.vertical_centered(|ui| ui.heading("Select an objective:"));
+                ui.horizontal(|ui| {
+                    let objective = &mut self.options.objective;
+                    ui.radio_value(objective, Objective::MinCost, "Minimum cost");
+                    ui.radio_value(objective, Objective::StableMatching, "Stable matching");
+                    ui.radio_value(objective, Objective::MinCostAndStability,
+                                   "Minimum cost, reporting blocking pairs");
+                });
+
+                ui.vertical_centered(|ui| ui.heading("Lay out the assignments:"));
+                ui.horizontal(|ui| {
+                    let layout = &mut self.options.layout;
+                    ui.radio_value(layout, OutputLayout::ByTask, "One column per task");
+                    ui.radio_value(layout, OutputLayout::ByWorker,
+                                   "One row per worker, with costs and regret");
+                });
+
+                ui.vertical_centered(|ui| ui.heading("Combine task preferences into costs:"));
+                ui.horizontal(|ui| {
+                    let combination = &mut self.options.score_combination;
+                    if ui.radio(*combination == ScoreCombination::WorkerOnly,
+                                "Workers' costs only").clicked() {
+                        *combination = ScoreCombination::WorkerOnly;
+                    }
+                    if ui.radio(matches!(combination, ScoreCombination::WeightedSum { .. }),
+                                "Weighted sum").clicked() {
+                        *combination = ScoreCombination::WeightedSum {
+                            worker_weight: 1.0, task_weight: 1.0
+                        };
+                    }
+                    if ui.radio(*combination == ScoreCombination::Product, "Product").clicked() {
+                        *combination = ScoreCombination::Product;
+                    }
+                    if ui.radio(*combination == ScoreCombination::Min, "Minimum").clicked() {
+                        *combination = ScoreCombination::Min;
+                    }
+                    if let ScoreCombination::WeightedSum { worker_weight, task_weight } = combination {
+                        ui.label("Worker weight:");
+                        ui.add(egui::DragValue::new(worker_weight).speed(0.1));
+                        ui.label("Task weight:");
+                        ui.add(egui::DragValue::new(task_weight).speed(0.1));
+                    }
+                });
+
+                ui.vertical_centered(|ui| ui.heading("Rescale each worker's costs:"));
+                ui.horizontal(|ui| {
+                    let normalization = &mut self.options.normalization;
+                    ui.radio_value(normalization, CostNormalization::None, "As given");
+                    ui.radio_value(normalization, CostNormalization::ZScore, "Z-score");
+                    ui.radio_value(normalization, CostNormalization::MinMax, "Min-max");
+                    ui.radio_value(normalization, CostNormalization::Rank, "Rank");
+                    if ui.radio(matches!(normalization, CostNormalization::SumTo(_)),
+                                "Sum to").clicked() {
+                        *normalization = CostNormalization::SumTo(1.0);
+                    }
+                    if let CostNormalization::SumTo(total) = normalization {
+                        ui.add(egui::DragValue::new(total).speed(0.1));
+                    }
+                });
             });
 
         egui::CentralPanel::default().frame(launch_frame).show(ctx, |ui| {
@@ -140,7 +297,7 @@ impl SolverGui {
             .frame(failure_frame)
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| ui.heading("Failure! The solver encountered a problem:"));
-                ui.label(msg);
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| ui.label(msg));
             });
         self.update_not_started(ctx, frame);
     }
@@ -162,13 +319,57 @@ impl SolverGui {
         };
 
         let status_tracker = self.cur_status.clone();
+        let options = self.options.clone();
+        let csv_input_type = self.csv_input_type;
+        let write_statistics = self.write_statistics;
         std::thread::spawn(move || {
-            let solver = Solver::new(FileType::Csv, FileType::Csv);
-            solver.assign_workers(infile, outfile, &status_tracker);
+            // pick each file's format from its extension, defaulting to CSV
+            let in_file_type = match FileType::from_path(&infile).unwrap_or(FileType::Csv) {
+                FileType::Csv => csv_input_type,
+                file_type => file_type
+            };
+            let out_file_type = FileType::from_path(&outfile).unwrap_or(FileType::Csv);
+            let solver = Solver::new(in_file_type, out_file_type, options.clone());
+            // the report goes beside the output file, e.g. "out-statistics.csv" for "out.xlsx"
+            let stats_file = get_statistics_path(&outfile);
+            let result = solver.assign_workers(infile.clone(), outfile, &status_tracker)
+                .and_then(|_| if write_statistics {
+                    solver.write_statistics(stats_file)
+                } else {
+                    Ok(())
+                });
+            match result {
+                Ok(()) => status_tracker.set_status(Status::Success),
+                Err(e) => {
+                    // when the input is at fault, validate it to report every issue at once
+                    let input_at_fault = match &e {
+                        SolverError::Input(err) => matches!(err, ReadError::Parse(_)),
+                        SolverError::Infeasible(_) => true,
+                        SolverError::Output(_) => false
+                    };
+                    let report = Some(Solver::new(in_file_type, out_file_type, options))
+                        .filter(|_| input_at_fault)
+                        .and_then(|validator| validator.validate_input(infile).ok())
+                        .filter(|report| !report.issues.is_empty());
+                    let message = match report {
+                        Some(report) => format!("{}\n\nEvery issue found in the input:\n{}",
+                                                e, report),
+                        None => e.to_string()
+                    };
+                    status_tracker.set_status(Status::Failure(message));
+                }
+            }
         });
     }
 }
 
+/// Get the path of the statistics report to write beside an output file
+fn get_statistics_path(outfile: &str) -> String {
+    let path = std::path::Path::new(outfile);
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("output");
+    path.with_file_name(format!("{}-statistics.csv", stem)).display().to_string()
+}
+
 impl eframe::App for SolverGui {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         match self.cur_status.get_status() {